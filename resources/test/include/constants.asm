@@ -0,0 +1 @@
+SCREEN = $0200
@@ -0,0 +1,2 @@
+NOP
+.include "cycle_a.asm"
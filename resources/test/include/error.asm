@@ -0,0 +1,2 @@
+NOP
+.include "lib/error.asm"
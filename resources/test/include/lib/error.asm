@@ -0,0 +1,2 @@
+NOP
+LDA ($1000),Y
@@ -0,0 +1,4 @@
+; Found in the include paths
+CLEAR: LDA #0
+STA SCREEN
+RTS
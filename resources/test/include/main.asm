@@ -0,0 +1,6 @@
+; Includes a file from its own directory and one from the include paths
+.org $8000
+.include "constants.asm"
+.include "routines.asm"
+RESET: JSR CLEAR
+BRK
@@ -0,0 +1,160 @@
+/// Timer periods (NTSC), in CPU cycles
+const RATE_TABLE: [u16; 16] = [428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54];
+
+const SAMPLE_ADDRESS_START: u16 = 0xC000;
+/// Sample fetches wrap around to $8000 after $FFFF
+const SAMPLE_ADDRESS_WRAP: u16 = 0x8000;
+const MAX_OUTPUT_LEVEL: u8 = 127;
+
+/// Delta modulation channel ($4010-$4013): plays 1-bit delta encoded samples read from the CPU bus
+///
+/// | Register | Bits      | Description                                  |
+/// |----------|-----------|----------------------------------------------|
+/// | 0        | IL-- RRRR | IRQ enabled, loop, rate index                |
+/// | 1        | -DDD DDDD | Direct load of the output level              |
+/// | 2        | AAAA AAAA | Sample address: $C000 + A * 64               |
+/// | 3        | LLLL LLLL | Sample length: L * 16 + 1 bytes              |
+///
+/// The channel can't access the bus itself: the bus polls `fetch_address` and answers
+/// with `fill_sample_buffer`, stalling the CPU meanwhile.
+///
+/// see: [APU DMC](https://www.nesdev.org/wiki/APU_DMC)
+#[derive(Debug)]
+pub(super) struct Dmc {
+    irq_enabled: bool,
+    looping: bool,
+    timer_period: u16,
+    timer: u16,
+    output_level: u8,
+    sample_address: u16,
+    sample_length: u16,
+    current_address: u16,
+    bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+    shift_register: u8,
+    bits_remaining: u8,
+    silence: bool,
+    pub(super) irq: bool
+}
+
+impl Default for Dmc {
+    fn default() -> Self {
+        Self {
+            irq_enabled: false,
+            looping: false,
+            timer_period: RATE_TABLE[0],
+            timer: 0,
+            output_level: 0,
+            sample_address: SAMPLE_ADDRESS_START,
+            sample_length: 1,
+            current_address: SAMPLE_ADDRESS_START,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+            irq: false
+        }
+    }
+}
+
+impl Dmc {
+    pub(super) fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.irq_enabled = data & 0b1000_0000 != 0;
+                if !self.irq_enabled {
+                    self.irq = false;
+                }
+                self.looping = data & 0b0100_0000 != 0;
+                self.timer_period = RATE_TABLE[(data & 0b1111) as usize];
+            },
+            1 => self.output_level = data & 0b0111_1111,
+            2 => self.sample_address = SAMPLE_ADDRESS_START + data as u16 * 64,
+            _ => self.sample_length = data as u16 * 16 + 1
+        }
+    }
+
+    /// Enabling the channel ($4015 bit 4) restarts the sample only if it's over. Disabling it stops the sample
+    pub(super) fn set_enabled(&mut self, enabled: bool) {
+        self.irq = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    pub(super) fn is_active(&self) -> bool {
+        self.bytes_remaining > 0
+    }
+
+    /// Address of the next sample byte, when the sample buffer needs to be refilled
+    pub(super) fn fetch_address(&self) -> Option<u16> {
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_address)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn fill_sample_buffer(&mut self, data: u8) {
+        self.sample_buffer = Some(data);
+        self.current_address = self.current_address.checked_add(1).unwrap_or(SAMPLE_ADDRESS_WRAP);
+        self.bytes_remaining -= 1;
+
+        if self.bytes_remaining == 0 {
+            if self.looping {
+                self.restart();
+            } else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    /// Clocked every CPU cycle
+    pub(super) fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period - 1;
+            self.clock_output();
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Each bit of the shift register raises (1) or lowers (0) the output level by 2
+    fn clock_output(&mut self) {
+        if !self.silence {
+            if self.shift_register & 1 != 0 {
+                if self.output_level <= MAX_OUTPUT_LEVEL - 2 {
+                    self.output_level += 2;
+                }
+            } else if self.output_level >= 2 {
+                self.output_level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(data) => {
+                    self.silence = false;
+                    self.shift_register = data;
+                },
+                None => self.silence = true
+            }
+        }
+    }
+
+    /// Output level between 0 and 127
+    pub(super) fn output(&self) -> u8 {
+        self.output_level
+    }
+}
@@ -0,0 +1,274 @@
+pub mod types;
+
+mod dmc;
+mod noise;
+mod pulse;
+mod triangle;
+mod units;
+
+use dmc::Dmc;
+use noise::Noise;
+use pulse::{Pulse, PulseChannel};
+use triangle::Triangle;
+use types::{FrameCounterMode, CPU_FREQUENCY, DEFAULT_SAMPLE_RATE};
+
+const PULSE_1_START: u16 = 0x4000;
+const PULSE_1_END: u16 = 0x4003;
+const PULSE_2_START: u16 = 0x4004;
+const PULSE_2_END: u16 = 0x4007;
+const TRIANGLE_START: u16 = 0x4008;
+const TRIANGLE_END: u16 = 0x400B;
+const NOISE_START: u16 = 0x400C;
+const NOISE_END: u16 = 0x400F;
+const DMC_START: u16 = 0x4010;
+const DMC_END: u16 = 0x4013;
+pub const STATUS: u16 = 0x4015;
+pub const FRAME_COUNTER: u16 = 0x4017;
+
+/// Frame counter steps (NTSC), in CPU cycles since the sequencer was reset
+const STEP_1: u32 = 7457;
+const STEP_2: u32 = 14913;
+const STEP_3: u32 = 22371;
+const STEP_4: u32 = 29829;
+const STEP_5: u32 = 37281;
+
+/// NES Audio Processing Unit (2A03)
+///
+/// | Address     | Channel                                          |
+/// |-------------|--------------------------------------------------|
+/// | $4000-$4003 | Pulse 1                                          |
+/// | $4004-$4007 | Pulse 2                                          |
+/// | $4008-$400B | Triangle                                         |
+/// | $400C-$400F | Noise                                            |
+/// | $4010-$4013 | DMC                                              |
+/// | $4015       | Status (read), channels enabled (write)          |
+/// | $4017       | Frame counter (write only, reads are for input)  |
+///
+/// The APU is clocked once per CPU cycle and produces f32 samples (0.0 - 1.0) at the configured
+/// sample rate, averaging the mixer output between two samples.
+///
+/// see: [APU](https://www.nesdev.org/wiki/APU)
+pub struct Apu {
+    pulse_1: Pulse,
+    pulse_2: Pulse,
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+    frame_counter_mode: FrameCounterMode,
+    frame_irq_inhibit: bool,
+    frame_irq: bool,
+    /// CPU cycles since the frame counter sequencer was reset
+    frame_cycle: u32,
+    /// Pulse timers are clocked every other CPU cycle
+    odd_cycle: bool,
+    sample_rate: u32,
+    /// Accumulates the sample rate every CPU cycle. A sample is produced when it exceeds the CPU frequency
+    sample_clock: u32,
+    sample_sum: f32,
+    sample_count: u32,
+    samples: Vec<f32>
+}
+
+impl Default for Apu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Apu {
+    pub fn new() -> Self {
+        Self::new_with_sample_rate(DEFAULT_SAMPLE_RATE)
+    }
+
+    pub fn new_with_sample_rate(sample_rate: u32) -> Self {
+        Self {
+            pulse_1: Pulse::new(PulseChannel::One),
+            pulse_2: Pulse::new(PulseChannel::Two),
+            triangle: Triangle::default(),
+            noise: Noise::default(),
+            dmc: Dmc::default(),
+            frame_counter_mode: FrameCounterMode::FourStep,
+            frame_irq_inhibit: false,
+            frame_irq: false,
+            frame_cycle: 0,
+            odd_cycle: false,
+            sample_rate,
+            sample_clock: 0,
+            sample_sum: 0.0,
+            sample_count: 0,
+            samples: Vec::new()
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Output rate in Hz. It should be lower than the CPU frequency
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    /// Returns the samples produced since the last call
+    pub fn take_samples(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+
+    /// Level of the IRQ line: frame counter or DMC interrupt
+    pub fn irq(&self) -> bool {
+        self.frame_irq || self.dmc.irq
+    }
+
+    pub fn write_register(&mut self, data: u8, address: u16) {
+        match address {
+            PULSE_1_START..=PULSE_1_END => self.pulse_1.write_register(address - PULSE_1_START, data),
+            PULSE_2_START..=PULSE_2_END => self.pulse_2.write_register(address - PULSE_2_START, data),
+            TRIANGLE_START..=TRIANGLE_END => self.triangle.write_register(address - TRIANGLE_START, data),
+            NOISE_START..=NOISE_END => self.noise.write_register(address - NOISE_START, data),
+            DMC_START..=DMC_END => self.dmc.write_register(address - DMC_START, data),
+            STATUS => {
+                self.pulse_1.length_counter.set_enabled(data & 0b0000_0001 != 0);
+                self.pulse_2.length_counter.set_enabled(data & 0b0000_0010 != 0);
+                self.triangle.length_counter.set_enabled(data & 0b0000_0100 != 0);
+                self.noise.length_counter.set_enabled(data & 0b0000_1000 != 0);
+                self.dmc.set_enabled(data & 0b0001_0000 != 0);
+            },
+            FRAME_COUNTER => {
+                self.frame_counter_mode = if data & 0b1000_0000 != 0 { FrameCounterMode::FiveStep } else { FrameCounterMode::FourStep };
+                self.frame_irq_inhibit = data & 0b0100_0000 != 0;
+                if self.frame_irq_inhibit {
+                    self.frame_irq = false;
+                }
+                self.frame_cycle = 0;
+                // 5-step mode clocks the units as soon as it's selected
+                if self.frame_counter_mode == FrameCounterMode::FiveStep {
+                    self.clock_quarter_frame();
+                    self.clock_half_frame();
+                }
+            },
+            _ => ()
+        }
+    }
+
+    /// Reads $4015 as the CPU does: clears the frame interrupt flag
+    pub fn read_status(&mut self) -> u8 {
+        let status = self.peek_status();
+        self.frame_irq = false;
+        status
+    }
+
+    /// $4015: IF-D NT21 (DMC interrupt, frame interrupt, DMC active, length counters active)
+    pub fn peek_status(&self) -> u8 {
+        (self.dmc.irq as u8) << 7
+            | (self.frame_irq as u8) << 6
+            | (self.dmc.is_active() as u8) << 4
+            | (self.noise.length_counter.is_active() as u8) << 3
+            | (self.triangle.length_counter.is_active() as u8) << 2
+            | (self.pulse_2.length_counter.is_active() as u8) << 1
+            | self.pulse_1.length_counter.is_active() as u8
+    }
+
+    /// Address the DMC needs to read, when its sample buffer is empty.
+    /// The bus must answer with `dmc_fill_sample_buffer`
+    pub fn dmc_fetch_address(&self) -> Option<u16> {
+        self.dmc.fetch_address()
+    }
+
+    pub fn dmc_fill_sample_buffer(&mut self, data: u8) {
+        self.dmc.fill_sample_buffer(data);
+    }
+
+    /// Runs 1 CPU cycle
+    pub fn tick(&mut self) {
+        self.clock_frame_counter();
+
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+        if self.odd_cycle {
+            self.pulse_1.clock_timer();
+            self.pulse_2.clock_timer();
+        }
+        self.odd_cycle = !self.odd_cycle;
+
+        self.sample();
+    }
+
+    /// see: [APU Frame Counter](https://www.nesdev.org/wiki/APU_Frame_Counter)
+    fn clock_frame_counter(&mut self) {
+        self.frame_cycle += 1;
+
+        match (self.frame_counter_mode, self.frame_cycle) {
+            (_, STEP_1) | (_, STEP_3) => self.clock_quarter_frame(),
+            (_, STEP_2) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            },
+            (FrameCounterMode::FourStep, STEP_4) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                if !self.frame_irq_inhibit {
+                    self.frame_irq = true;
+                }
+                self.frame_cycle = 0;
+            },
+            (FrameCounterMode::FiveStep, STEP_5) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                self.frame_cycle = 0;
+            },
+            _ => ()
+        }
+    }
+
+    /// Envelopes and triangle linear counter
+    fn clock_quarter_frame(&mut self) {
+        self.pulse_1.envelope.clock();
+        self.pulse_2.envelope.clock();
+        self.noise.envelope.clock();
+        self.triangle.clock_linear_counter();
+    }
+
+    /// Length counters and sweep units
+    fn clock_half_frame(&mut self) {
+        self.pulse_1.length_counter.clock();
+        self.pulse_2.length_counter.clock();
+        self.triangle.length_counter.clock();
+        self.noise.length_counter.clock();
+        self.pulse_1.clock_sweep();
+        self.pulse_2.clock_sweep();
+    }
+
+    fn sample(&mut self) {
+        self.sample_sum += self.mix();
+        self.sample_count += 1;
+
+        self.sample_clock += self.sample_rate;
+        if self.sample_clock >= CPU_FREQUENCY {
+            self.sample_clock -= CPU_FREQUENCY;
+            self.samples.push(self.sample_sum / self.sample_count as f32);
+            self.sample_sum = 0.0;
+            self.sample_count = 0;
+        }
+    }
+
+    /// Non-linear mix of the channels, between 0.0 and 1.0
+    ///
+    /// see: [APU Mixer](https://www.nesdev.org/wiki/APU_Mixer)
+    pub fn mix(&self) -> f32 {
+        mix(self.pulse_1.output(), self.pulse_2.output(), self.triangle.output(), self.noise.output(), self.dmc.output())
+    }
+}
+
+fn mix(pulse_1: u8, pulse_2: u8, triangle: u8, noise: u8, dmc: u8) -> f32 {
+    let pulse_sum = (pulse_1 + pulse_2) as f32;
+    let pulse_out = if pulse_sum == 0.0 { 0.0 } else { 95.88 / (8128.0 / pulse_sum + 100.0) };
+
+    let tnd_sum = triangle as f32 / 8227.0 + noise as f32 / 12241.0 + dmc as f32 / 22638.0;
+    let tnd_out = if tnd_sum == 0.0 { 0.0 } else { 159.79 / (1.0 / tnd_sum + 100.0) };
+
+    pulse_out + tnd_out
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use super::units::{Envelope, LengthCounter};
+
+/// Timer periods (NTSC), in CPU cycles
+const PERIOD_TABLE: [u16; 16] = [4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068];
+
+/// Pseudo-random noise channel ($400C-$400F)
+///
+/// | Register | Bits      | Description                                 |
+/// |----------|-----------|---------------------------------------------|
+/// | 0        | --LC VVVV | Length counter halt, constant volume, volume |
+/// | 2        | M--- PPPP | Mode, period index                          |
+/// | 3        | LLLL L--- | Length counter load                         |
+///
+/// see: [APU Noise](https://www.nesdev.org/wiki/APU_Noise)
+#[derive(Debug)]
+pub(super) struct Noise {
+    /// Mode 1 takes the feedback from bit 6 instead of bit 1, producing a short (93 steps) sequence
+    short_mode: bool,
+    /// 15-bit linear feedback shift register
+    shift_register: u16,
+    timer_period: u16,
+    timer: u16,
+    pub(super) envelope: Envelope,
+    pub(super) length_counter: LengthCounter
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self {
+            short_mode: false,
+            shift_register: 1,
+            timer_period: PERIOD_TABLE[0],
+            timer: 0,
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default()
+        }
+    }
+}
+
+impl Noise {
+    pub(super) fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.length_counter.set_halted(data & 0b0010_0000 != 0);
+                self.envelope.write_control(data);
+            },
+            1 => (),
+            2 => {
+                self.short_mode = data & 0b1000_0000 != 0;
+                self.timer_period = PERIOD_TABLE[(data & 0b1111) as usize];
+            },
+            _ => {
+                self.length_counter.load(data);
+                self.envelope.restart();
+            }
+        }
+    }
+
+    /// Clocked every CPU cycle
+    pub(super) fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period - 1;
+            self.clock_shift_register();
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    fn clock_shift_register(&mut self) {
+        let tap = if self.short_mode { 6 } else { 1 };
+        let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 1;
+        self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+    }
+
+    /// Volume between 0 and 15. The channel is silenced while bit 0 of the shift register is set
+    pub(super) fn output(&self) -> u8 {
+        if self.shift_register & 1 != 0 || !self.length_counter.is_active() {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
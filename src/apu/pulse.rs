@@ -0,0 +1,144 @@
+use super::units::{Envelope, LengthCounter};
+
+const DUTY_SEQUENCES: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1]
+];
+
+/// Periods above this value, or below 8, mute the channel
+const MAX_TIMER_PERIOD: u16 = 0x07FF;
+const MIN_TIMER_PERIOD: u16 = 8;
+
+/// Both pulse channels are the same, except for the sweep negation:
+/// pulse 1 adds the ones' complement of the change, pulse 2 the two's complement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseChannel {
+    One,
+    Two
+}
+
+/// Periodically adjusts the pulse period
+///
+/// see: [APU Sweep](https://www.nesdev.org/wiki/APU_Sweep)
+#[derive(Debug, Default)]
+struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    reload: bool,
+    divider: u8
+}
+
+/// Square wave channel ($4000-$4003 and $4004-$4007)
+///
+/// | Register | Bits      | Description                                        |
+/// |----------|-----------|----------------------------------------------------|
+/// | 0        | DDLC VVVV | Duty, length counter halt, constant volume, volume |
+/// | 1        | EPPP NSSS | Sweep enabled, period, negate, shift               |
+/// | 2        | TTTT TTTT | Timer low                                          |
+/// | 3        | LLLL LTTT | Length counter load, timer high                    |
+///
+/// see: [APU Pulse](https://www.nesdev.org/wiki/APU_Pulse)
+#[derive(Debug)]
+pub(super) struct Pulse {
+    channel: PulseChannel,
+    duty: u8,
+    sequence_step: u8,
+    timer_period: u16,
+    timer: u16,
+    sweep: Sweep,
+    pub(super) envelope: Envelope,
+    pub(super) length_counter: LengthCounter
+}
+
+impl Pulse {
+    pub(super) fn new(channel: PulseChannel) -> Self {
+        Self {
+            channel,
+            duty: 0,
+            sequence_step: 0,
+            timer_period: 0,
+            timer: 0,
+            sweep: Sweep::default(),
+            envelope: Envelope::default(),
+            length_counter: LengthCounter::default()
+        }
+    }
+
+    pub(super) fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.duty = data >> 6;
+                self.length_counter.set_halted(data & 0b0010_0000 != 0);
+                self.envelope.write_control(data);
+            },
+            1 => {
+                self.sweep.enabled = data & 0b1000_0000 != 0;
+                self.sweep.period = (data >> 4) & 0b111;
+                self.sweep.negate = data & 0b0000_1000 != 0;
+                self.sweep.shift = data & 0b111;
+                self.sweep.reload = true;
+            },
+            2 => self.timer_period = (self.timer_period & 0xFF00) | data as u16,
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+                self.length_counter.load(data);
+                self.sequence_step = 0;
+                self.envelope.restart();
+            }
+        }
+    }
+
+    /// Clocked every APU cycle (2 CPU cycles)
+    pub(super) fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence_step = (self.sequence_step + 1) % 8;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Clocked by the frame counter half frames
+    pub(super) fn clock_sweep(&mut self) {
+        if self.sweep.divider == 0 && self.sweep.enabled && self.sweep.shift > 0 && !self.is_muted() {
+            self.timer_period = self.target_period();
+        }
+
+        if self.sweep.divider == 0 || self.sweep.reload {
+            self.sweep.divider = self.sweep.period;
+            self.sweep.reload = false;
+        } else {
+            self.sweep.divider -= 1;
+        }
+    }
+
+    /// The sweep unit computes the target period continuously, even when disabled
+    fn target_period(&self) -> u16 {
+        let change = self.timer_period >> self.sweep.shift;
+        if !self.sweep.negate {
+            self.timer_period + change
+        } else {
+            match self.channel {
+                PulseChannel::One => self.timer_period.saturating_sub(change + 1),
+                PulseChannel::Two => self.timer_period.saturating_sub(change)
+            }
+        }
+    }
+
+    fn is_muted(&self) -> bool {
+        self.timer_period < MIN_TIMER_PERIOD || self.target_period() > MAX_TIMER_PERIOD
+    }
+
+    /// Volume between 0 and 15
+    pub(super) fn output(&self) -> u8 {
+        if self.is_muted() || !self.length_counter.is_active() || DUTY_SEQUENCES[self.duty as usize][self.sequence_step as usize] == 0 {
+            0
+        } else {
+            self.envelope.output()
+        }
+    }
+}
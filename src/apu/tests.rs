@@ -0,0 +1,417 @@
+use super::*;
+use super::units::Envelope;
+
+/// Enables every channel but DMC and loads their length counters with 2 (index 3)
+fn apu_with_active_channels() -> Apu {
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_1111, STATUS);
+    for register in [0x4003, 0x4007, 0x400B, 0x400F] {
+        apu.write_register(0b0001_1000, register);
+    }
+    apu
+}
+
+fn run(apu: &mut Apu, cycles: u32) {
+    for _ in 0..cycles {
+        apu.tick();
+    }
+}
+
+#[test]
+fn status_reports_active_length_counters() {
+    // Given
+    let mut apu = apu_with_active_channels();
+
+    // When
+    let status = apu.read_status();
+
+    // Then
+    assert_eq!(0b0000_1111, status);
+}
+
+#[test]
+fn length_counter_is_not_loaded_when_channel_is_disabled() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0001, STATUS);
+
+    // When
+    apu.write_register(0b0001_1000, 0x4003);
+    apu.write_register(0b0001_1000, 0x4007);
+
+    // Then
+    assert_eq!(0b0000_0001, apu.peek_status());
+}
+
+#[test]
+fn disabling_channel_clears_length_counter() {
+    // Given
+    let mut apu = apu_with_active_channels();
+
+    // When
+    apu.write_register(0b0000_0101, STATUS);
+
+    // Then
+    assert_eq!(0b0000_0101, apu.peek_status());
+}
+
+#[test]
+fn length_counters_are_clocked_by_half_frames() {
+    // Given
+    let mut apu = apu_with_active_channels();
+    // Halt pulse 2 length counter
+    apu.write_register(0b0010_0000, 0x4004);
+
+    // When / Then
+    run(&mut apu, STEP_2);
+    assert_eq!(0b0000_1111, apu.peek_status() & 0x0F);
+    run(&mut apu, STEP_4 - STEP_2);
+    assert_eq!(0b0000_0010, apu.peek_status() & 0x0F);
+}
+
+#[test]
+fn five_step_mode_clocks_units_when_selected() {
+    // Given
+    let mut apu = apu_with_active_channels();
+
+    // When
+    apu.write_register(0b1000_0000, FRAME_COUNTER);
+    apu.write_register(0b1000_0000, FRAME_COUNTER);
+
+    // Then
+    assert_eq!(0b0000_0000, apu.peek_status());
+}
+
+#[test]
+fn frame_irq_is_raised_at_the_end_of_four_step_sequence() {
+    // Given
+    let mut apu = Apu::new();
+
+    // When
+    run(&mut apu, STEP_4 - 1);
+    assert!(!apu.irq());
+    run(&mut apu, 1);
+
+    // Then
+    assert!(apu.irq());
+    assert_eq!(0b0100_0000, apu.peek_status());
+    assert_eq!(0b0100_0000, apu.read_status());
+    assert!(!apu.irq());
+}
+
+#[test]
+fn frame_irq_can_be_inhibited() {
+    // Given
+    let mut apu = Apu::new();
+    run(&mut apu, STEP_4);
+
+    // When
+    apu.write_register(0b0100_0000, FRAME_COUNTER);
+    run(&mut apu, STEP_4);
+
+    // Then
+    assert!(!apu.irq());
+}
+
+#[test]
+fn five_step_mode_never_raises_frame_irq() {
+    // Given
+    let mut apu = Apu::new();
+
+    // When
+    apu.write_register(0b1000_0000, FRAME_COUNTER);
+    run(&mut apu, STEP_5 * 2);
+
+    // Then
+    assert!(!apu.irq());
+}
+
+#[test]
+fn envelope_decays_and_loops() {
+    // Given
+    let mut envelope = Envelope::default();
+    envelope.write_control(0b0010_0000);
+    envelope.restart();
+
+    // When / Then
+    envelope.clock();
+    assert_eq!(15, envelope.output());
+    for _ in 0..15 {
+        envelope.clock();
+    }
+    assert_eq!(0, envelope.output());
+    envelope.clock();
+    assert_eq!(15, envelope.output());
+}
+
+#[test]
+fn envelope_divider_uses_volume_as_period() {
+    // Given
+    let mut envelope = Envelope::default();
+    envelope.write_control(0b0000_0010);
+    envelope.restart();
+    envelope.clock();
+
+    // When
+    for _ in 0..3 {
+        envelope.clock();
+    }
+
+    // Then
+    assert_eq!(14, envelope.output());
+}
+
+#[test]
+fn envelope_constant_volume() {
+    // Given
+    let mut envelope = Envelope::default();
+
+    // When
+    envelope.write_control(0b0001_0111);
+    envelope.restart();
+    envelope.clock();
+
+    // Then
+    assert_eq!(7, envelope.output());
+}
+
+#[test]
+fn pulse_outputs_volume_when_sequence_is_high() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0001, STATUS);
+
+    // When
+    // 75% duty cycle (first step high), constant volume 9, period $100
+    apu.write_register(0b1101_1001, 0x4000);
+    apu.write_register(0x00, 0x4002);
+    apu.write_register(0b0000_1001, 0x4003);
+
+    // Then
+    assert_eq!(9, apu.pulse_1.output());
+    assert_eq!(0, apu.pulse_2.output());
+}
+
+#[test]
+fn sweep_updates_period_until_muted() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0001, STATUS);
+    apu.write_register(0b1101_1111, 0x4000);
+    // Period $300. Even with shift 0, the target period ($600) is computed
+    apu.write_register(0x00, 0x4002);
+    apu.write_register(0b0000_1011, 0x4003);
+    assert_eq!(15, apu.pulse_1.output());
+
+    // When
+    // Sweep enabled, divider period 0, shift 1
+    apu.write_register(0b1000_0001, 0x4001);
+    apu.clock_half_frame();
+    assert_eq!(15, apu.pulse_1.output());
+    apu.clock_half_frame();
+
+    // Then
+    // Period $6C0, target $A20 is out of range
+    assert_eq!(0, apu.pulse_1.output());
+}
+
+#[test]
+fn pulse_1_sweep_negation_subtracts_one_more_than_pulse_2() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0011, STATUS);
+    for (control, start) in [(0x4000, 0x4000), (0x4004, 0x4004)] {
+        apu.write_register(0b1101_1111, control);
+        // Period 9, sweep negated with shift 3: change is 1
+        apu.write_register(0b1000_1011, start + 1);
+        apu.write_register(0x09, start + 2);
+        apu.write_register(0b0000_1000, start + 3);
+    }
+
+    // When
+    apu.clock_half_frame();
+
+    // Then
+    // Pulse 1 period becomes 7 (muted), pulse 2 becomes 8
+    assert_eq!(0, apu.pulse_1.output());
+    assert_eq!(15, apu.pulse_2.output());
+}
+
+#[test]
+fn pulse_is_muted_with_period_lower_than_8() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0001, STATUS);
+    apu.write_register(0b1101_1111, 0x4000);
+
+    // When
+    apu.write_register(0x07, 0x4002);
+    apu.write_register(0b0000_1000, 0x4003);
+
+    // Then
+    assert_eq!(0, apu.pulse_1.output());
+}
+
+#[test]
+fn triangle_only_runs_while_linear_counter_is_active() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_0100, STATUS);
+    // Linear counter reload value 2, timer period 0
+    apu.write_register(0b0000_0010, 0x4008);
+    apu.write_register(0x00, 0x400A);
+    apu.write_register(0b0000_1000, 0x400B);
+    run(&mut apu, 10);
+    assert_eq!(15, apu.triangle.output());
+
+    // When / Then
+    apu.clock_quarter_frame();
+    run(&mut apu, 3);
+    assert_eq!(12, apu.triangle.output());
+
+    apu.clock_quarter_frame();
+    apu.clock_quarter_frame();
+    run(&mut apu, 3);
+    assert_eq!(12, apu.triangle.output());
+}
+
+/// Collects the noise channel output (volume 1 when audible) after every timer period
+fn noise_sequence(apu: &mut Apu, length: usize) -> Vec<u8> {
+    (0..length).map(|_| {
+        run(apu, 4);
+        apu.noise.output()
+    }).collect()
+}
+
+#[test]
+fn noise_short_mode_repeats_every_93_steps() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_1000, STATUS);
+    apu.write_register(0b0011_0001, 0x400C);
+    apu.write_register(0b1000_0000, 0x400E);
+    apu.write_register(0b0000_1000, 0x400F);
+    // Skip the first steps, until the shift register enters its loop
+    noise_sequence(&mut apu, 93);
+
+    // When
+    let sequence = noise_sequence(&mut apu, 93 * 2);
+
+    // Then
+    assert_eq!(sequence[..93], sequence[93..]);
+    assert_ne!(sequence[..31], sequence[31..62]);
+    assert!(sequence.contains(&1) && sequence.contains(&0));
+}
+
+#[test]
+fn noise_long_mode_matches_lfsr_with_bit_1_feedback() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0000_1000, STATUS);
+    apu.write_register(0b0011_0001, 0x400C);
+    apu.write_register(0b0000_0000, 0x400E);
+    apu.write_register(0b0000_1000, 0x400F);
+    let mut lfsr: u16 = 1;
+    let expected: Vec<u8> = (0..200).map(|_| {
+        let feedback = (lfsr ^ (lfsr >> 1)) & 1;
+        lfsr = (lfsr >> 1) | (feedback << 14);
+        (lfsr & 1 == 0) as u8
+    }).collect();
+
+    // When
+    let sequence = noise_sequence(&mut apu, 200);
+
+    // Then
+    assert_eq!(expected, sequence);
+}
+
+#[test]
+fn dmc_plays_sample_bits_as_deltas() {
+    // Given
+    let mut apu = Apu::new();
+    // Fastest rate (54 cycles), output level $40, sample of 1 byte
+    apu.write_register(0b0000_1111, 0x4010);
+    apu.write_register(0x40, 0x4011);
+    apu.write_register(0x00, 0x4013);
+    apu.write_register(0b0001_0000, STATUS);
+    assert_eq!(Some(0xC000), apu.dmc_fetch_address());
+
+    // When
+    apu.dmc_fill_sample_buffer(0b1111_0000);
+    // 8 silent bits, then the sample byte
+    run(&mut apu, 54 * 16);
+
+    // Then
+    // Bits are played from the lowest: 4 steps down, then 4 steps up
+    assert_eq!(0x40, apu.dmc.output());
+    assert_eq!(None, apu.dmc_fetch_address());
+    assert_eq!(0b0000_0000, apu.peek_status());
+}
+
+#[test]
+fn dmc_raises_irq_at_the_end_of_sample() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b0100_0000, FRAME_COUNTER);
+    apu.write_register(0b1000_0000, 0x4010);
+    apu.write_register(0x01, 0x4013);
+    apu.write_register(0b0001_0000, STATUS);
+
+    // When
+    for _ in 0..16 {
+        assert!(!apu.irq());
+        apu.dmc_fill_sample_buffer(0);
+        run(&mut apu, 428 * 8);
+    }
+    apu.dmc_fill_sample_buffer(0);
+
+    // Then
+    assert!(apu.irq());
+    assert_eq!(0b1000_0000, apu.peek_status() & 0b1001_0000);
+    apu.write_register(0b0000_0000, STATUS);
+    assert!(!apu.irq());
+}
+
+#[test]
+fn dmc_looping_sample_restarts() {
+    // Given
+    let mut apu = Apu::new();
+    apu.write_register(0b1100_0000, 0x4010);
+    apu.write_register(0x01, 0x4012);
+    apu.write_register(0x00, 0x4013);
+    apu.write_register(0b0001_0000, STATUS);
+
+    // When
+    apu.dmc_fill_sample_buffer(0);
+    run(&mut apu, 428 * 9);
+
+    // Then
+    assert!(!apu.irq());
+    assert_eq!(Some(0xC040), apu.dmc_fetch_address());
+}
+
+#[test]
+fn mixer_output_is_non_linear() {
+    assert_eq!(0.0, mix(0, 0, 0, 0, 0));
+    assert!((mix(15, 0, 0, 0, 0) - 0.1494).abs() < 0.0001);
+    // Doubling the input doesn't double the output
+    assert!(mix(15, 15, 0, 0, 0) < 2.0 * mix(15, 0, 0, 0, 0));
+    assert!((mix(15, 15, 15, 15, 127) - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn produces_samples_at_configured_rate() {
+    // Given
+    let mut apu = Apu::new();
+    apu.set_sample_rate(48_000);
+
+    // When
+    run(&mut apu, CPU_FREQUENCY / 10);
+    let samples = apu.take_samples();
+
+    // Then
+    assert_eq!(4799, samples.len());
+    // Silent channels keep a constant level
+    assert!(samples.windows(2).all(|pair| pair[0] == pair[1]));
+    assert!(apu.take_samples().is_empty());
+}
@@ -0,0 +1,76 @@
+use super::units::LengthCounter;
+
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15
+];
+
+/// Triangle wave channel ($4008-$400B)
+///
+/// | Register | Bits      | Description                                          |
+/// |----------|-----------|------------------------------------------------------|
+/// | 0        | CRRR RRRR | Length counter halt / linear counter control, reload |
+/// | 2        | TTTT TTTT | Timer low                                            |
+/// | 3        | LLLL LTTT | Length counter load, timer high                      |
+///
+/// see: [APU Triangle](https://www.nesdev.org/wiki/APU_Triangle)
+#[derive(Debug, Default)]
+pub(super) struct Triangle {
+    control: bool,
+    linear_counter_reload_value: u8,
+    linear_counter: u8,
+    linear_counter_reload: bool,
+    sequence_step: u8,
+    timer_period: u16,
+    timer: u16,
+    pub(super) length_counter: LengthCounter
+}
+
+impl Triangle {
+    pub(super) fn write_register(&mut self, register: u16, data: u8) {
+        match register {
+            0 => {
+                self.control = data & 0b1000_0000 != 0;
+                self.length_counter.set_halted(self.control);
+                self.linear_counter_reload_value = data & 0b0111_1111;
+            },
+            1 => (),
+            2 => self.timer_period = (self.timer_period & 0xFF00) | data as u16,
+            _ => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+                self.length_counter.load(data);
+                self.linear_counter_reload = true;
+            }
+        }
+    }
+
+    /// Clocked every CPU cycle. The sequencer only advances while both counters are active
+    pub(super) fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            if self.length_counter.is_active() && self.linear_counter > 0 {
+                self.sequence_step = (self.sequence_step + 1) % SEQUENCE.len() as u8;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Clocked by the frame counter quarter frames
+    pub(super) fn clock_linear_counter(&mut self) {
+        if self.linear_counter_reload {
+            self.linear_counter = self.linear_counter_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+
+        if !self.control {
+            self.linear_counter_reload = false;
+        }
+    }
+
+    /// Volume between 0 and 15. A silenced triangle keeps its last output level
+    pub(super) fn output(&self) -> u8 {
+        SEQUENCE[self.sequence_step as usize]
+    }
+}
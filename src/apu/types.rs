@@ -0,0 +1,18 @@
+/// NTSC CPU clock rate, in Hz
+pub const CPU_FREQUENCY: u32 = 1_789_773;
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+/// Length counter values, indexed by the 5 bits written to the length counter load
+pub const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30
+];
+
+/// Sequencer mode selected by bit 7 of $4017
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameCounterMode {
+    /// Quarter frame clocks at steps 1-4, half frame at steps 2 and 4, IRQ at step 4
+    FourStep,
+    /// Quarter frame clocks at steps 1, 2, 3 and 5, half frame at steps 2 and 5, no IRQ
+    FiveStep
+}
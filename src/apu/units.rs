@@ -0,0 +1,93 @@
+use super::types::LENGTH_TABLE;
+
+/// Generates a decaying volume (sawtooth) or a constant volume.
+/// Shared by pulse and noise channels
+///
+/// see: [APU Envelope](https://www.nesdev.org/wiki/APU_Envelope)
+#[derive(Debug, Default)]
+pub(super) struct Envelope {
+    start: bool,
+    looping: bool,
+    constant_volume: bool,
+    /// Constant volume, or divider period of the decay
+    volume: u8,
+    divider: u8,
+    decay_level: u8
+}
+
+impl Envelope {
+    /// Handles the --LC VVVV bits of the channel control register
+    pub(super) fn write_control(&mut self, data: u8) {
+        self.looping = data & 0b0010_0000 != 0;
+        self.constant_volume = data & 0b0001_0000 != 0;
+        self.volume = data & 0b0000_1111;
+    }
+
+    pub(super) fn restart(&mut self) {
+        self.start = true;
+    }
+
+    /// Clocked by the frame counter quarter frames
+    pub(super) fn clock(&mut self) {
+        if self.start {
+            self.start = false;
+            self.decay_level = 15;
+            self.divider = self.volume;
+        } else if self.divider == 0 {
+            self.divider = self.volume;
+            if self.decay_level > 0 {
+                self.decay_level -= 1;
+            } else if self.looping {
+                self.decay_level = 15;
+            }
+        } else {
+            self.divider -= 1;
+        }
+    }
+
+    pub(super) fn output(&self) -> u8 {
+        if self.constant_volume { self.volume } else { self.decay_level }
+    }
+}
+
+/// Silences the channel once the note duration is over
+///
+/// see: [APU Length Counter](https://www.nesdev.org/wiki/APU_Length_Counter)
+#[derive(Debug, Default)]
+pub(super) struct LengthCounter {
+    enabled: bool,
+    halted: bool,
+    counter: u8
+}
+
+impl LengthCounter {
+    /// Disabling the channel ($4015) clears the counter
+    pub(super) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.counter = 0;
+        }
+    }
+
+    pub(super) fn set_halted(&mut self, halted: bool) {
+        self.halted = halted;
+    }
+
+    /// Loads the counter from the table with the LLLL L... bits of the register. Ignored if the channel is disabled
+    pub(super) fn load(&mut self, data: u8) {
+        if self.enabled {
+            self.counter = LENGTH_TABLE[(data >> 3) as usize];
+        }
+    }
+
+    /// Clocked by the frame counter half frames
+    pub(super) fn clock(&mut self) {
+        if self.counter > 0 && !self.halted {
+            self.counter -= 1;
+        }
+    }
+
+    pub(super) fn is_active(&self) -> bool {
+        self.counter > 0
+    }
+}
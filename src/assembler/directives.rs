@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::assembler::expression;
+use crate::assembler::parser;
+use crate::assembler::types::{Command, ParseError, SourceLocation};
+
+/// Directives emitting data. All their arguments accept expressions
+///
+/// | Directive      | Arguments                  | Emits                                                   |
+/// |----------------|----------------------------|---------------------------------------------------------|
+/// | `.byte`, `.db` | values and strings         | 1 byte per value, the bytes of each string              |
+/// | `.word`, `.dw` | values                     | 2 bytes per value, little endian                        |
+/// | `.dword`       | values                     | 4 bytes per value, little endian                        |
+/// | `.res`, `.ds`  | count[, value]             | `count` times the value, 0 by default                   |
+/// | `.fill`        | count[, value]             | Same as `.res`                                          |
+/// | `.align`       | boundary[, value]          | The value until the address is a multiple of `boundary` |
+/// | `.text`        | strings and values         | Same as `.byte`                                         |
+/// | `.asciiz`      | strings and values         | Same as `.text`, followed by a 0                        |
+/// | `.incbin`      | "file"[, offset[, length]] | The bytes of the file, from `offset`                    |
+///
+/// Strings are written between double quotes and accept the escape sequences of `expression::unescape`.
+/// Files are searched like the ones of `.include`
+const DATA_DIRECTIVES: [&str; 12] = [".byte", ".db", ".word", ".dw", ".dword", ".res", ".ds", ".fill", ".align", ".text", ".asciiz", ".incbin"];
+
+/// Directives selecting the lines to assemble. Blocks can be nested, but must end in the file they start
+///
+/// | Directive            | Lines assembled until the next directive of the block             |
+/// |----------------------|-------------------------------------------------------------------|
+/// | `.if` expression     | When the expression is not 0                                      |
+/// | `.ifdef` symbol      | When the symbol is defined in a previous line                     |
+/// | `.ifndef` symbol     | When the symbol is not defined in a previous line                 |
+/// | `.elseif` expression | When no previous branch was assembled and the expression is not 0 |
+/// | `.else`              | When no previous branch was assembled                             |
+/// | `.endif`             | Ends the block                                                    |
+///
+/// Only the symbols defined in the previous lines can be used, as the lines to assemble must be known in the first pass
+const CONDITIONAL_DIRECTIVES: [&str; 6] = [".if", ".ifdef", ".ifndef", ".elseif", ".else", ".endif"];
+
+/// `.res`, `.fill` and `.align` can't emit more than the whole address space
+const MAX_DATA_SIZE: i64 = 0x10000;
+
+pub fn is_data_directive(name: &str) -> bool {
+    DATA_DIRECTIVES.contains(&name)
+}
+
+pub fn is_conditional_directive(name: &str) -> bool {
+    CONDITIONAL_DIRECTIVES.contains(&name)
+}
+
+/// Bytes emitted by a data directive placed at `address` (e.g. `.word RESET, $C000`).
+///
+/// Symbols without value are added to `undefined_symbols` and written as 0. The arguments setting the size
+/// (counts, boundaries and file ranges) can't be resolved later, so they must only use symbols already defined
+pub fn data_directive_bytes(name: &str, data: &str, address: u16, symbol_table: &HashMap<String, Command>, search_directories: &[PathBuf], undefined_symbols: &mut Vec<String>) -> Result<Vec<u8>, ParseError> {
+
+    let arguments = split_arguments(data);
+    let mut evaluator = ArgumentEvaluator { address, symbol_table, undefined_symbols };
+
+    match name {
+        ".byte" | ".db" | ".text" => evaluator.values(required_arguments(&arguments, name)?, 1),
+        ".asciiz" => {
+            let mut bytes = evaluator.values(required_arguments(&arguments, name)?, 1)?;
+            bytes.push(0);
+            Ok(bytes)
+        },
+        ".word" | ".dw" => evaluator.values(required_arguments(&arguments, name)?, 2),
+        ".dword" => evaluator.values(required_arguments(&arguments, name)?, 4),
+        ".res" | ".ds" | ".fill" => {
+            let count = evaluator.size(required_argument(&arguments, 0, name)?)?;
+            let value = evaluator.optional_byte(arguments.get(1))?;
+            Ok(vec![value; count])
+        },
+        ".align" => {
+            let boundary = evaluator.size(required_argument(&arguments, 0, name)?)?;
+            if boundary == 0 {
+                return Err(ParseError::InvalidNumber("Alignment must be greater than 0".to_string()));
+            }
+            let value = evaluator.optional_byte(arguments.get(1))?;
+            let padding = (boundary - address as usize % boundary) % boundary;
+            Ok(vec![value; padding])
+        },
+        ".incbin" => {
+            let path = find_file(&string_argument(required_argument(&arguments, 0, name)?)?, search_directories)?;
+            let file = fs::read(&path).map_err(|error| file_error(&path, error))?;
+            let offset = arguments.get(1).map(|argument| evaluator.size(argument)).transpose()?.unwrap_or(0);
+            let length = arguments.get(2).map(|argument| evaluator.size(argument)).transpose()?.unwrap_or(file.len().saturating_sub(offset));
+            file.get(offset..offset + length).map(<[u8]>::to_vec).ok_or_else(|| ParseError::FileError(
+                format!("Range {}..{} is out of {} ({} bytes)", offset, offset + length, path.display(), file.len())))
+        },
+        _ => Err(ParseError::SyntaxError(format!("Unknown directive {}", name)))
+    }
+}
+
+/// File of an `.include` (e.g. `"lib/math.asm"`)
+pub fn included_file(data: &str, search_directories: &[PathBuf]) -> Result<PathBuf, ParseError> {
+    find_file(&string_argument(data.trim())?, search_directories)
+}
+
+/// The file in the first directory containing it. Absolute paths are kept as they are
+fn find_file(name: &str, search_directories: &[PathBuf]) -> Result<PathBuf, ParseError> {
+    search_directories.iter()
+        .map(|directory| directory.join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| ParseError::FileError(format!("File {} not found", name)))
+}
+
+pub fn file_error(path: &Path, error: std::io::Error) -> ParseError {
+    ParseError::FileError(format!("Cannot read {}: {}", path.display(), error))
+}
+
+/// `.if` blocks around the current line, the outermost first
+#[derive(Debug, Default)]
+pub struct ConditionalBlocks {
+    blocks: Vec<ConditionalBlock>
+}
+
+#[derive(Debug)]
+struct ConditionalBlock {
+    /// Where the block starts, to report a missing `.endif`
+    location: SourceLocation,
+    /// The lines of the current branch are assembled
+    active: bool,
+    /// A branch was already assembled, or the whole block is skipped. The next branches are skipped
+    done: bool,
+    else_found: bool
+}
+
+impl ConditionalBlocks {
+
+    /// True when the current line must be assembled
+    pub fn is_active(&self) -> bool {
+        self.blocks.last().is_none_or(|block| block.active)
+    }
+
+    /// Start of the innermost block without `.endif`
+    pub fn unterminated(&self) -> Option<&SourceLocation> {
+        self.blocks.last().map(|block| &block.location)
+    }
+
+    /// Apply a conditional directive. The conditions are only evaluated when their branch could be assembled
+    pub fn process(&mut self, name: &str, data: &str, location: &SourceLocation, address: u16, symbol_table: &HashMap<String, Command>) -> Result<(), ParseError> {
+        match name {
+            ".if" | ".ifdef" | ".ifndef" => {
+                let enclosing_active = self.is_active();
+                let active = enclosing_active && condition(name, data, address, symbol_table)?;
+                self.blocks.push(ConditionalBlock { location: location.clone(), active, done: active || !enclosing_active, else_found: false });
+            },
+            ".elseif" | ".else" => {
+                let block = self.blocks.last_mut().ok_or_else(|| missing_if(name))?;
+                if block.else_found {
+                    return Err(ParseError::SyntaxError(format!("{} after .else", name)));
+                }
+                block.else_found = name == ".else";
+                block.active = !block.done && (name == ".else" || condition(".if", data, address, symbol_table)?);
+                block.done |= block.active;
+            },
+            ".endif" => {
+                self.blocks.pop().ok_or_else(|| missing_if(name))?;
+            },
+            _ => return Err(ParseError::SyntaxError(format!("Unknown directive {}", name)))
+        }
+        Ok(())
+    }
+}
+
+fn condition(name: &str, data: &str, address: u16, symbol_table: &HashMap<String, Command>) -> Result<bool, ParseError> {
+    if name == ".if" {
+        return expression::evaluate(data, address, |symbol| parser::symbol_value(symbol, symbol_table)).map(|value| value != 0);
+    }
+    let symbol = data.trim();
+    if symbol.is_empty() || symbol.contains(char::is_whitespace) {
+        return Err(ParseError::SyntaxError(format!("{} expects a symbol name", name)));
+    }
+    Ok(symbol_table.contains_key(symbol) == (name == ".ifdef"))
+}
+
+fn missing_if(name: &str) -> ParseError {
+    ParseError::SyntaxError(format!("{} without .if", name))
+}
+
+/// Split the arguments at the commas outside strings, character literals and parentheses
+fn split_arguments(data: &str) -> Vec<&str> {
+    let mut arguments: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut index = 0;
+
+    while let Some(c) = data[index..].chars().next() {
+        match c {
+            '"' | '\'' => {
+                // An unterminated string is reported when the argument is parsed
+                index += expression::quoted_length(&data[index..], c).unwrap_or(data.len() - index);
+                continue;
+            },
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(data[start..index].trim());
+                start = index + 1;
+            },
+            _ => ()
+        }
+        index += c.len_utf8();
+    }
+
+    let last = data[start..].trim();
+    if !last.is_empty() || !arguments.is_empty() {
+        arguments.push(last);
+    }
+    arguments
+}
+
+fn required_argument<'a>(arguments: &[&'a str], index: usize, directive: &str) -> Result<&'a str, ParseError> {
+    arguments.get(index).copied().filter(|argument| !argument.is_empty())
+        .ok_or_else(|| ParseError::SyntaxError(format!("Missing argument {} of {}", index + 1, directive)))
+}
+
+/// The arguments of a directive taking a list of values, which needs at least one
+fn required_arguments<'a, 'b>(arguments: &'b [&'a str], directive: &str) -> Result<&'b [&'a str], ParseError> {
+    required_argument(arguments, 0, directive)?;
+    Ok(arguments)
+}
+
+fn string_argument(argument: &str) -> Result<String, ParseError> {
+    string_bytes(argument).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Bytes of a string argument (e.g. `"Hello\n"`)
+fn string_bytes(argument: &str) -> Result<Vec<u8>, ParseError> {
+    if expression::quoted_length(argument, '"') != Some(argument.len()) || !argument.starts_with('"') {
+        return Err(ParseError::SyntaxError(format!("Invalid string: {}", argument)));
+    }
+    expression::unescape(&argument[1..argument.len() - 1])
+}
+
+struct ArgumentEvaluator<'a> {
+    address: u16,
+    symbol_table: &'a HashMap<String, Command>,
+    undefined_symbols: &'a mut Vec<String>
+}
+
+impl ArgumentEvaluator<'_> {
+
+    /// Each value written with `size` bytes, little endian. Strings are only accepted for bytes
+    fn values(&mut self, arguments: &[&str], size: usize) -> Result<Vec<u8>, ParseError> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(arguments.len() * size);
+        for argument in arguments {
+            if argument.starts_with('"') && size == 1 {
+                bytes.extend(string_bytes(argument)?);
+                continue;
+            }
+            let value = self.value(argument, size)?;
+            bytes.extend_from_slice(&value.to_le_bytes()[..size]);
+        }
+        Ok(bytes)
+    }
+
+    /// Value of an argument, which must fit in `size` bytes, as signed or unsigned
+    fn value(&mut self, argument: &str, size: usize) -> Result<i64, ParseError> {
+        let undefined_before = self.undefined_symbols.len();
+        let symbol_table = self.symbol_table;
+        let undefined_symbols = &mut *self.undefined_symbols;
+        let value = expression::evaluate(argument, self.address, |name| {
+            parser::symbol_value(name, symbol_table).or_else(|| {
+                undefined_symbols.push(name.to_string());
+                Some(1)
+            })
+        })?;
+        if self.undefined_symbols.len() > undefined_before {
+            return Ok(0);
+        }
+
+        let bits = size as u32 * 8;
+        if !(-(1 << (bits - 1))..(1 << bits)).contains(&value) {
+            return Err(ParseError::InvalidNumber(format!("Value {} doesn't fit in {} bits", value, bits)));
+        }
+        Ok(value)
+    }
+
+    fn optional_byte(&mut self, argument: Option<&&str>) -> Result<u8, ParseError> {
+        argument.map_or(Ok(0), |argument| self.value(argument, 1).map(|value| value as u8))
+    }
+
+    /// Count, boundary or file range, which can only use the symbols already defined
+    fn size(&self, argument: &str) -> Result<usize, ParseError> {
+        let value = expression::evaluate(argument, self.address, |name| parser::symbol_value(name, self.symbol_table))?;
+        if !(0..=MAX_DATA_SIZE).contains(&value) {
+            return Err(ParseError::InvalidNumber(format!("Invalid size: {}", value)));
+        }
+        Ok(value as usize)
+    }
+}
@@ -0,0 +1,293 @@
+use std::ops::Range;
+
+use crate::assembler::types::{NumericType, ParseError};
+
+/// Operators between two values, from the lowest to the highest precedence
+///
+/// | Precedence | Operators                     |
+/// |------------|-------------------------------|
+/// | 1          | `\|\|`                        |
+/// | 2          | `&&`                          |
+/// | 3          | `=` `<>` `<` `>` `<=` `>=`    |
+/// | 4          | `\|`                          |
+/// | 5          | `^`                           |
+/// | 6          | `&`                           |
+/// | 7          | `<<` `>>`                     |
+/// | 8          | `+` `-`                       |
+/// | 9          | `*` `/` `%`                   |
+///
+/// Comparisons and logical operators give 1 when true and 0 when false.
+/// Unary operators bind tighter than any of them: `-` (negation), `~` (bitwise not), `!` (logical not),
+/// `<` (low byte) and `>` (high byte). So `<TABLE+2` is `(<TABLE)+2`, use `<(TABLE+2)` for the low byte of the sum
+const BINARY_OPERATORS: [(&str, u8); 18] = [
+    ("||", 1), ("&&", 2),
+    ("=", 3), ("<>", 3), ("<", 3), (">", 3), ("<=", 3), (">=", 3),
+    ("|", 4), ("^", 5), ("&", 6),
+    ("<<", 7), (">>", 7),
+    ("+", 8), ("-", 8),
+    ("*", 9), ("/", 9), ("%", 9)
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Symbol(String),
+    /// `*` where a value is expected (e.g. `*+2`, but not `2*2`)
+    CurrentAddress,
+    Binary(&'static str),
+    Unary(char),
+    OpenParenthesis,
+    CloseParenthesis
+}
+
+/// Evaluate an expression (e.g. `<(TABLE+2)`, `BUFFER+1`, `*-2`, `'A'|$80`).
+///
+/// Numbers use the same prefixes as the literals (`$`/`0x`, `%`/`0b`, `@`/`0o`, decimal) and characters
+/// between quotes are their ASCII code (escape sequences as in `unescape`). `*` is the current address and symbols are resolved with `value_of`
+pub fn evaluate<F>(expression: &str, current_address: u16, value_of: F) -> Result<i64, ParseError>
+where F: FnMut(&str) -> Option<i64> {
+
+    let tokens = tokenize(expression)?;
+    let mut evaluator = Evaluator { tokens: &tokens, position: 0, current_address, value_of };
+    let value = evaluator.expression(1)?;
+    match evaluator.tokens.get(evaluator.position) {
+        None => Ok(value),
+        Some(_) => Err(invalid_expression(expression))
+    }
+}
+
+/// Replace `*` by the given address, leaving the rest of the expression as written.
+/// Constants use it, so `*` is the address where they are defined and not where they are used
+pub fn with_current_address(expression: &str, current_address: u16) -> String {
+    let Ok(tokens) = tokenize_with_spans(expression) else {
+        return expression.to_string();
+    };
+
+    let mut result = expression.to_string();
+    for (_, span) in tokens.iter().rev().filter(|(token, _)| *token == Token::CurrentAddress) {
+        result.replace_range(span.clone(), &format!("${:04X}", current_address));
+    }
+    result
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, ParseError> {
+    Ok(tokenize_with_spans(expression)?.into_iter().map(|(token, _)| token).collect())
+}
+
+fn tokenize_with_spans(expression: &str) -> Result<Vec<(Token, Range<usize>)>, ParseError> {
+
+    let mut tokens: Vec<(Token, Range<usize>)> = Vec::new();
+    let mut position = 0;
+    // A value is expected at the start and after an operator: `*` is then the address and `%` a binary number
+    let mut expect_value = true;
+
+    while let Some(current) = expression[position..].chars().next() {
+        let start = position;
+        let rest = &expression[position..];
+
+        let token = if current.is_whitespace() {
+            position += current.len_utf8();
+            continue;
+        } else if current == '(' {
+            position += 1;
+            Token::OpenParenthesis
+        } else if current == ')' {
+            position += 1;
+            Token::CloseParenthesis
+        } else if current == '\'' {
+            let length = quoted_length(rest, '\'').ok_or_else(|| invalid_expression(expression))?;
+            let [character] = unescape(&rest[1..length - 1])?[..] else {
+                return Err(invalid_expression(expression));
+            };
+            position += length;
+            Token::Number(character as i64)
+        } else if expect_value && current == '*' {
+            position += 1;
+            Token::CurrentAddress
+        } else if expect_value && "-+~!<>".contains(current) {
+            position += 1;
+            Token::Unary(current)
+        } else if current.is_ascii_alphanumeric() || current == '_' || (expect_value && "$%@".contains(current)) {
+            let length = rest[1..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).map_or(rest.len(), |length| length + 1);
+            let word = &rest[..length];
+            position += length;
+            if current.is_ascii_alphabetic() || current == '_' {
+                Token::Symbol(word.to_string())
+            } else {
+                Token::Number(parse_number(word)?)
+            }
+        } else if let Some((operator, _)) = BINARY_OPERATORS.iter().filter(|(operator, _)| rest.starts_with(operator)).max_by_key(|(operator, _)| operator.len()) {
+            // The longest operator wins, so `<=` is not read as `<` followed by `=`
+            position += operator.len();
+            Token::Binary(operator)
+        } else {
+            return Err(invalid_expression(expression));
+        };
+
+        expect_value = matches!(token, Token::OpenParenthesis | Token::Binary(_) | Token::Unary(_));
+        tokens.push((token, start..position));
+    }
+    Ok(tokens)
+}
+
+/// Length of the quoted text at the start of `text`, quotes included. A backslash escapes the next character.
+/// Returns None when the closing quote is missing
+pub fn quoted_length(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return Some(index + c.len_utf8()),
+            _ => ()
+        }
+    }
+    None
+}
+
+/// Bytes of a text with escape sequences (e.g. `Hi\n`), without the quotes
+///
+/// | Sequence         | Byte                   |
+/// |------------------|------------------------|
+/// | `\n`             | $0A, line feed         |
+/// | `\r`             | $0D, carriage return   |
+/// | `\t`             | $09, tab               |
+/// | `\0`             | $00                    |
+/// | `\xHH`           | $HH                    |
+/// | `\\` `\"` `\'`   | The character itself   |
+pub fn unescape(text: &str) -> Result<Vec<u8>, ParseError> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    let mut characters = text.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        let byte = match characters.next() {
+            Some('n') => b'\n',
+            Some('r') => b'\r',
+            Some('t') => b'\t',
+            Some('0') => 0,
+            Some(escaped @ ('\\' | '"' | '\'')) => escaped as u8,
+            Some('x') => {
+                let digits: String = characters.by_ref().take(2).collect();
+                u8::from_str_radix(&digits, 16).map_err(|_| invalid_escape(&format!("x{}", digits)))?
+            },
+            Some(other) => return Err(invalid_escape(&other.to_string())),
+            None => return Err(invalid_escape(""))
+        };
+        bytes.push(byte);
+    }
+    Ok(bytes)
+}
+
+fn invalid_escape(sequence: &str) -> ParseError {
+    ParseError::InvalidExpression(format!("Invalid escape sequence: \\{}", sequence))
+}
+
+fn parse_number(word: &str) -> Result<i64, ParseError> {
+    let (numeric_type, digits) = NumericType::detect_type_in_string(word);
+    i64::from_str_radix(digits, numeric_type.to_radix())
+        .map_err(|_| ParseError::InvalidNumber(format!("Invalid integer: {}", word)))
+}
+
+fn invalid_expression(expression: &str) -> ParseError {
+    ParseError::InvalidExpression(format!("Invalid expression: {}", expression))
+}
+
+/// Recursive descent evaluator, one level per operator precedence
+struct Evaluator<'a, F> {
+    tokens: &'a [Token],
+    position: usize,
+    current_address: u16,
+    value_of: F
+}
+
+impl<F> Evaluator<'_, F>
+where F: FnMut(&str) -> Option<i64> {
+
+    fn expression(&mut self, min_precedence: u8) -> Result<i64, ParseError> {
+        let mut value = self.unary()?;
+
+        while let Some(Token::Binary(operator)) = self.tokens.get(self.position) {
+            let precedence = precedence(operator);
+            if precedence < min_precedence {
+                break;
+            }
+            self.position += 1;
+            let rhs = self.expression(precedence + 1)?;
+            value = apply_binary(operator, value, rhs)?;
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<i64, ParseError> {
+        if let Some(Token::Unary(operator)) = self.tokens.get(self.position) {
+            self.position += 1;
+            let value = self.unary()?;
+            return Ok(match operator {
+                '-' => value.wrapping_neg(),
+                '~' => !value,
+                '!' => (value == 0) as i64,
+                '<' => value & 0xFF,
+                '>' => (value >> 8) & 0xFF,
+                _ => value
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<i64, ParseError> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        match token {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::CurrentAddress) => Ok(self.current_address as i64),
+            Some(Token::Symbol(name)) => (self.value_of)(&name)
+                .ok_or_else(|| ParseError::SymbolNotDefined(format!("Symbol [{}] not defined", name))),
+            Some(Token::OpenParenthesis) => {
+                let value = self.expression(1)?;
+                match self.tokens.get(self.position) {
+                    Some(Token::CloseParenthesis) => {
+                        self.position += 1;
+                        Ok(value)
+                    },
+                    _ => Err(ParseError::InvalidExpression("Missing closing parenthesis".to_string()))
+                }
+            },
+            _ => Err(ParseError::InvalidExpression("Value expected".to_string()))
+        }
+    }
+}
+
+fn precedence(operator: &str) -> u8 {
+    BINARY_OPERATORS.iter().find(|(binary_operator, _)| *binary_operator == operator).map_or(0, |(_, precedence)| *precedence)
+}
+
+fn apply_binary(operator: &str, lhs: i64, rhs: i64) -> Result<i64, ParseError> {
+    let invalid_shift = || ParseError::InvalidExpression(format!("Invalid shift: {}", rhs));
+    Ok(match operator {
+        "||" => (lhs != 0 || rhs != 0) as i64,
+        "&&" => (lhs != 0 && rhs != 0) as i64,
+        "=" => (lhs == rhs) as i64,
+        "<>" => (lhs != rhs) as i64,
+        "<" => (lhs < rhs) as i64,
+        ">" => (lhs > rhs) as i64,
+        "<=" => (lhs <= rhs) as i64,
+        ">=" => (lhs >= rhs) as i64,
+        "|" => lhs | rhs,
+        "^" => lhs ^ rhs,
+        "&" => lhs & rhs,
+        "<<" => lhs.checked_shl(u32::try_from(rhs).map_err(|_| invalid_shift())?).ok_or_else(invalid_shift)?,
+        ">>" => lhs.checked_shr(u32::try_from(rhs).map_err(|_| invalid_shift())?).ok_or_else(invalid_shift)?,
+        "+" => lhs.wrapping_add(rhs),
+        "-" => lhs.wrapping_sub(rhs),
+        "*" => lhs.wrapping_mul(rhs),
+        "/" | "%" if rhs == 0 => return Err(ParseError::InvalidExpression("Division by zero".to_string())),
+        "/" => lhs.checked_div(rhs).ok_or_else(|| ParseError::InvalidExpression(format!("Division overflow: {} / {}", lhs, rhs)))?,
+        "%" => lhs.checked_rem(rhs).ok_or_else(|| ParseError::InvalidExpression(format!("Division overflow: {} % {}", lhs, rhs)))?,
+        _ => return Err(ParseError::InvalidExpression(format!("Unknown operator: {}", operator)))
+    })
+}
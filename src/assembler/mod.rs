@@ -1,33 +1,31 @@
 pub mod types;
 pub mod parser;
+pub mod expression;
+pub mod directives;
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, Write};
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
 
-use types::{Command, SymbolType};
+use types::{AssemblerOptions, AssemblyError, Command, SourceLocation, SymbolType};
 
 use crate::constants;
 use crate::cpu::opcode;
-use crate::cpu::types::InstructionError;
+use crate::cpu::types::{CpuVariant, InstructionError};
 use crate::memory::types::AddressingMode;
 
 pub fn assemble(filename: &str, output_filename: Option<&str>) {
+    assemble_with_options(filename, output_filename, &AssemblerOptions::default());
+}
 
-    let mut line_number: usize = 1;
-    let mut address: u16 = 0;
-    let mut program_binary: Vec<u8> = Vec::with_capacity(200);
-    let mut symbol_table: HashMap<String, Command> = HashMap::with_capacity(50);
+pub fn assemble_with_options(filename: &str, output_filename: Option<&str>, options: &AssemblerOptions) {
 
-    if let Ok(lines) = read_lines(filename) {
-        for line in lines.map_while(Result::ok) {
-            if let Err(parse_error) = process_line(&line, &mut address, &mut program_binary, &mut symbol_table) {
-                panic!("Error in line {}: {:?}", line_number, parse_error);
-            }
-            line_number += 1;
-        }
-    }
+    let program_binary = match assemble_file(Path::new(filename), options) {
+        Ok(program_binary) => program_binary,
+        Err(error) => panic!("{}", error)
+    };
 
     let output = match output_filename {
         Some(name) => name,
@@ -39,47 +37,395 @@ pub fn assemble(filename: &str, output_filename: Option<&str>) {
     };
 }
 
+/// How the value of an operand expression is written, which selects between the zero page and absolute modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperandWidth {
+    /// 8 bits when the value fits and the instruction accepts it, 16 bits otherwise
+    Smallest,
+    /// Always 16 bits, for symbols not defined yet when the instruction is sized
+    Word,
+    /// Always 8 bits, for symbols not defined yet where only 8 bits are valid (e.g. `(POINTER),Y`)
+    Byte
+}
+
+/// Instruction or data directive found in the first pass, encoded in the second one
+struct Statement {
+    location: SourceLocation,
+    address: u16,
+    command: Command,
+    size: u16,
+    /// Width chosen when the instruction was sized, so the second pass gives the same size
+    operand_width: OperandWidth
+}
+
+/// Assemble a whole program in two passes, so labels can be used before their definition.
+///
+/// The first pass reads the included files, selects the lines of the conditional blocks, sizes every instruction
+/// and builds the symbol table. Operands using symbols not defined yet are assumed to be 16-bit addresses,
+/// unless only an 8-bit operand is valid (e.g. `(POINTER),Y`).
+/// The second pass evaluates the operands and encodes the instructions.
+/// Every undefined symbol is reported at once, with the line it is used.
+/// Included files are searched in the working directory, then in the include paths of the options
+pub fn assemble_source(source: &str, options: &AssemblerOptions) -> Result<Vec<u8>, AssemblyError> {
+    let mut first_pass = FirstPass::new(options);
+    first_pass.read_source(source, None, None)?;
+    second_pass(&first_pass.statements, &first_pass.symbol_table, options)
+}
+
+/// Same as `assemble_source`, for the source in a file. Included files are searched in its directory first
+pub fn assemble_file(filename: &Path, options: &AssemblerOptions) -> Result<Vec<u8>, AssemblyError> {
+    let mut first_pass = FirstPass::new(options);
+    first_pass.read_file(filename, None)?;
+    second_pass(&first_pass.statements, &first_pass.symbol_table, options)
+}
+
+/// State of the first pass, shared by the main source and the files it includes
+struct FirstPass<'a> {
+    options: &'a AssemblerOptions,
+    address: u16,
+    symbol_table: HashMap<String, Command>,
+    statements: Vec<Statement>,
+    /// Files being read, the outermost first, so a file including itself is detected
+    open_files: Vec<PathBuf>
+}
+
+impl<'a> FirstPass<'a> {
+    fn new(options: &'a AssemblerOptions) -> Self {
+        Self {
+            options,
+            address: 0,
+            symbol_table: HashMap::with_capacity(50),
+            statements: Vec::new(),
+            open_files: Vec::new()
+        }
+    }
+
+    /// Read a file, included at `included_from` or being the main source
+    fn read_file(&mut self, filename: &Path, included_from: Option<&SourceLocation>) -> Result<(), AssemblyError> {
+        let file_error = |error: types::ParseError| match included_from {
+            Some(location) => AssemblyError::Line(location.clone(), error),
+            None => AssemblyError::File(error.to_string())
+        };
+
+        let canonical_filename = fs::canonicalize(filename).map_err(|error| file_error(directives::file_error(filename, error)))?;
+        if self.open_files.contains(&canonical_filename) {
+            return Err(file_error(types::ParseError::FileError(format!("Circular inclusion of {}", filename.display()))));
+        }
+        let source = fs::read_to_string(filename).map_err(|error| file_error(directives::file_error(filename, error)))?;
+
+        self.open_files.push(canonical_filename);
+        self.read_source(&source, Some(filename), included_from)?;
+        self.open_files.pop();
+        Ok(())
+    }
+
+    fn read_source(&mut self, source: &str, filename: Option<&Path>, included_from: Option<&SourceLocation>) -> Result<(), AssemblyError> {
+        let search_directories = search_directories(filename, self.options);
+        let mut conditionals = directives::ConditionalBlocks::default();
+
+        for (index, line) in source.lines().enumerate() {
+            let location = SourceLocation {
+                file: filename.map(Path::to_path_buf),
+                line: index + 1,
+                included_from: included_from.cloned().map(Box::new)
+            };
+            let line_error = |error| AssemblyError::Line(location.clone(), error);
+
+            // Conditional directives are found even in the skipped lines, so nested blocks end where they should
+            if let Some((name, data)) = parser::leading_directive(line).filter(|(name, _)| directives::is_conditional_directive(name)) {
+                conditionals.process(name, data, &location, self.address, &self.symbol_table).map_err(line_error)?;
+                continue;
+            }
+            if !conditionals.is_active() {
+                continue;
+            }
+
+            let command = parser::parse_line_with_options(line, self.address, &mut self.symbol_table, self.options).map_err(line_error)?;
+            if command.symbol.symbol_type == SymbolType::DIRECTIVE {
+                if command.symbol.name == ".org" {
+                    self.address = parser::parse_org_data(command, &self.symbol_table).map_err(line_error)?;
+                } else if command.symbol.name == ".include" {
+                    let filename = directives::included_file(&command.data, &search_directories).map_err(line_error)?;
+                    self.read_file(&filename, Some(&location))?;
+                } else {
+                    // Only the size matters here, the values are evaluated again once every symbol is defined
+                    let data_binary = directives::data_directive_bytes(&command.symbol.name, &command.data, self.address, &self.symbol_table, &search_directories, &mut Vec::new())
+                        .map_err(line_error)?;
+                    self.add_statement(location, command, data_binary.len() as u16, OperandWidth::Smallest);
+                }
+            } else if command.symbol.symbol_type == SymbolType::MNEMONIC {
+                let (size, operand_width) = instruction_size(&command, self.address, &self.symbol_table, self.options).map_err(line_error)?;
+                self.add_statement(location, command, size, operand_width);
+            }
+        }
+
+        match conditionals.unterminated() {
+            Some(location) => Err(AssemblyError::Line(location.clone(), types::ParseError::SyntaxError("Missing .endif".to_string()))),
+            None => Ok(())
+        }
+    }
+
+    fn add_statement(&mut self, location: SourceLocation, command: Command, size: u16, operand_width: OperandWidth) {
+        self.statements.push(Statement { location, address: self.address, command, size, operand_width });
+        self.address = self.address.wrapping_add(size);
+    }
+}
+
+/// Directories searched for the files used by a source: its own directory (the working one for a text), then the include paths
+fn search_directories(filename: Option<&Path>, options: &AssemblerOptions) -> Vec<PathBuf> {
+    let directory = filename.and_then(Path::parent).map_or_else(PathBuf::new, Path::to_path_buf);
+    iter::once(directory).chain(options.include_paths.iter().cloned()).collect()
+}
+
+fn second_pass(statements: &[Statement], symbol_table: &HashMap<String, Command>, options: &AssemblerOptions) -> Result<Vec<u8>, AssemblyError> {
+    let mut program_binary: Vec<u8> = Vec::with_capacity(200);
+    let mut undefined_symbols: Vec<(SourceLocation, String)> = Vec::new();
+    for statement in statements {
+        match encode_statement(statement, symbol_table, options) {
+            Ok(mut statement_binary) => program_binary.append(&mut statement_binary),
+            Err(StatementError::UndefinedSymbols(names)) => {
+                undefined_symbols.extend(names.into_iter().map(|name| (statement.location.clone(), name)));
+            },
+            Err(StatementError::Parse(error)) => return Err(AssemblyError::Line(statement.location.clone(), error))
+        }
+    }
+
+    if undefined_symbols.is_empty() {
+        Ok(program_binary)
+    } else {
+        Err(AssemblyError::UndefinedSymbols(undefined_symbols))
+    }
+}
+
+enum StatementError {
+    UndefinedSymbols(Vec<String>),
+    Parse(types::ParseError)
+}
+
+/// Size of the instruction in bytes, and the operand width to keep in the second pass
+fn instruction_size(command: &Command, address: u16, symbol_table: &HashMap<String, Command>, options: &AssemblerOptions) -> Result<(u16, OperandWidth), types::ParseError> {
+    let operand = resolve_operand(command, address, symbol_table, OperandWidth::Smallest, options)?;
+    if operand.undefined_symbols.is_empty() || is_branch_mnemonic(&command.symbol.name, options.cpu_variant) {
+        let instruction_binary = assemble_first_valid(&command.symbol.name, &operand.candidates, options)?;
+        return Ok((instruction_binary.len() as u16, OperandWidth::Smallest));
+    }
+
+    let operand = resolve_operand(command, address, symbol_table, OperandWidth::Word, options)?;
+    match assemble_first_valid(&command.symbol.name, &operand.candidates, options) {
+        Ok(instruction_binary) => Ok((instruction_binary.len() as u16, OperandWidth::Word)),
+        Err(error) => {
+            let operand = resolve_operand(command, address, symbol_table, OperandWidth::Byte, options)?;
+            assemble_first_valid(&command.symbol.name, &operand.candidates, options)
+                .map(|instruction_binary| (instruction_binary.len() as u16, OperandWidth::Byte))
+                .map_err(|_| error)
+        }
+    }
+}
+
+fn encode_statement(statement: &Statement, symbol_table: &HashMap<String, Command>, options: &AssemblerOptions) -> Result<Vec<u8>, StatementError> {
+    let binary = if statement.command.symbol.symbol_type == SymbolType::DIRECTIVE {
+        let search_directories = search_directories(statement.location.file.as_deref(), options);
+        encode_data_directive(&statement.command, statement.address, symbol_table, &search_directories)?
+    } else {
+        encode_instruction(&statement.command, statement.address, symbol_table, statement.operand_width, options)?
+    };
+    if binary.len() as u16 != statement.size {
+        return Err(StatementError::Parse(types::ParseError::FatalError(
+            format!("Statement size changed from {} to {} bytes between passes", statement.size, binary.len()))));
+    }
+    Ok(binary)
+}
+
+fn encode_data_directive(command: &Command, address: u16, symbol_table: &HashMap<String, Command>, search_directories: &[PathBuf]) -> Result<Vec<u8>, StatementError> {
+    let mut undefined_symbols: Vec<String> = Vec::new();
+    let data_binary = directives::data_directive_bytes(&command.symbol.name, &command.data, address, symbol_table, search_directories, &mut undefined_symbols)
+        .map_err(StatementError::Parse)?;
+    if !undefined_symbols.is_empty() {
+        return Err(StatementError::UndefinedSymbols(undefined_symbols));
+    }
+    Ok(data_binary)
+}
+
+fn encode_instruction(command: &Command, address: u16, symbol_table: &HashMap<String, Command>, operand_width: OperandWidth, options: &AssemblerOptions) -> Result<Vec<u8>, StatementError> {
+    let operand = resolve_operand(command, address, symbol_table, operand_width, options).map_err(StatementError::Parse)?;
+    if !operand.undefined_symbols.is_empty() {
+        return Err(StatementError::UndefinedSymbols(operand.undefined_symbols));
+    }
+    assemble_first_valid(&command.symbol.name, &operand.candidates, options).map_err(StatementError::Parse)
+}
+
+/// Operand with its expression replaced by the value
+struct ResolvedOperand {
+    /// Data to assemble, in the order to try (e.g. `$10` then `$0010`)
+    candidates: Vec<String>,
+    /// Symbols without value. They are evaluated as 1, so the rest of the expression is still checked
+    undefined_symbols: Vec<String>
+}
+
+/// Evaluate the expression of the operand (e.g. `BUFFER+1,X`) and write its value as a literal the
+/// addressing mode regexes understand (e.g. `$0201,X`).
+/// An operand made of a single literal is kept as written, as its number of digits selects the addressing mode
+fn resolve_operand(command: &Command, address: u16, symbol_table: &HashMap<String, Command>, operand_width: OperandWidth, options: &AssemblerOptions) -> Result<ResolvedOperand, types::ParseError> {
+    let data = &command.data;
+    let is_branch = is_branch_mnemonic(&command.symbol.name, options.cpu_variant);
+    let as_written = || ResolvedOperand { candidates: vec![data.clone()], undefined_symbols: Vec::new() };
+
+    let Some(operand) = parser::split_operand(data) else {
+        return Ok(as_written());
+    };
+    // Branches still accept the displacement as an 8-bit literal (e.g. `BNE $FD`), see `branch_displacement`
+    let literal_kept = if is_branch { AddressingMode::Relative.regex().is_match(data) } else { parser::is_number_literal(&operand.expression) };
+    if literal_kept {
+        return Ok(as_written());
+    }
+
+    let mut undefined_symbols: Vec<String> = Vec::new();
+    let value = expression::evaluate(&operand.expression, address, |name| {
+        parser::symbol_value(name, symbol_table).or_else(|| {
+            undefined_symbols.push(name.to_string());
+            Some(1)
+        })
+    })?;
+    // Without all the symbols, the operand can only size the instruction. Any valid value does
+    let value = match (undefined_symbols.is_empty(), is_branch) {
+        (true, _) => value,
+        (false, true) => address as i64,
+        (false, false) => 0
+    };
+
+    let candidates = operand_candidates(&operand, value, address, is_branch, operand_width)?;
+    Ok(ResolvedOperand { candidates, undefined_symbols })
+}
+
+fn operand_candidates(operand: &parser::Operand, value: i64, address: u16, is_branch: bool, operand_width: OperandWidth) -> Result<Vec<String>, types::ParseError> {
+    if is_branch {
+        return Ok(vec![format!("${:02X}", branch_displacement(value, address)?)]);
+    }
+    if operand.prefix == "#" {
+        if !(i8::MIN as i64..=u8::MAX as i64).contains(&value) {
+            return Err(types::ParseError::InvalidNumber(format!("Value {} doesn't fit in 8 bits", value)));
+        }
+        return Ok(vec![operand.with_value(&format!("${:02X}", value as u8))]);
+    }
+
+    let value = u16::try_from(value).map_err(|_| types::ParseError::InvalidNumber(format!("Value {} doesn't fit in 16 bits", value)))?;
+    let byte = operand.with_value(&format!("${:02X}", value));
+    let word = operand.with_value(&format!("${:04X}", value));
+    match operand_width {
+        OperandWidth::Smallest if value <= u8::MAX as u16 => Ok(vec![byte, word]),
+        OperandWidth::Smallest | OperandWidth::Word => Ok(vec![word]),
+        OperandWidth::Byte if value <= u8::MAX as u16 => Ok(vec![byte]),
+        OperandWidth::Byte => Err(types::ParseError::InvalidNumber(format!("Value ${:04X} doesn't fit in 8 bits", value)))
+    }
+}
+
+/// Assemble the first candidate operand the instruction accepts. Returns the error of the first candidate otherwise
+fn assemble_first_valid(mnemonic: &str, candidates: &[String], options: &AssemblerOptions) -> Result<Vec<u8>, types::ParseError> {
+    let mut first_error = None;
+    for data in candidates {
+        match assemble_instruction_with_options(mnemonic, data, options) {
+            Ok(instruction_binary) => return Ok(instruction_binary),
+            Err(error) => { first_error.get_or_insert(error); }
+        }
+    }
+    Err(types::ParseError::InstructionError(first_error.unwrap_or_else(|| InstructionError::AddressingModeNotRecognized(String::new()))))
+}
+
 /// Process each line of the source code
 /// Could return a ParseError
-/// TODO Handle relative addresses (branch instructions)
-/// TODO Handle more directives
 pub fn process_line(line: &str, address: &mut u16, program_binary: &mut Vec<u8>, symbol_table: &mut HashMap<String, Command>) -> Result<(), types::ParseError> {
-    let mut command = parser::parse_line(line, *address, symbol_table)?;
-    if command.symbol.symbol_type != SymbolType::UNDEFINED {
-        // Try to replace any symbols we already capture at the first step
-        parser::replace_symbols(&mut command, &symbol_table);
-
-        // Parse commands
-        if command.symbol.symbol_type == SymbolType::DIRECTIVE {
-            if command.symbol.name == ".org" {
-                *address = parser::parse_org_data(command, &symbol_table)?;
-            }
-        } else if command.symbol.symbol_type == SymbolType::MNEMONIC {
-            let result = assemble_instruction(&command.symbol.name, &command.data).clone();
-            let Some(mut instruction_binary) = result.clone().ok() else {
-                return Err(types::ParseError::InstructionError(result.unwrap_err()));
+    process_line_with_options(line, address, program_binary, symbol_table, &AssemblerOptions::default())
+}
+
+/// Same as `process_line`, with the instruction set and include paths in the options
+pub fn process_line_with_options(line: &str, address: &mut u16, program_binary: &mut Vec<u8>, symbol_table: &mut HashMap<String, Command>, options: &AssemblerOptions) -> Result<(), types::ParseError> {
+    let command = parser::parse_line_with_options(line, *address, symbol_table, options)?;
+
+    // Parse commands
+    if command.symbol.symbol_type == SymbolType::DIRECTIVE {
+        if command.symbol.name == ".org" {
+            *address = parser::parse_org_data(command, symbol_table)?;
+        } else {
+            let mut data_binary = match encode_data_directive(&command, *address, symbol_table, &search_directories(None, options)) {
+                Ok(data_binary) => data_binary,
+                Err(error) => return Err(single_line_error(error))
             };
-            *address += instruction_binary.len() as u16;
-            program_binary.append(&mut instruction_binary);
+            *address = address.wrapping_add(data_binary.len() as u16);
+            program_binary.append(&mut data_binary);
         }
+    } else if command.symbol.symbol_type == SymbolType::MNEMONIC {
+        let mut instruction_binary = match encode_instruction(&command, *address, symbol_table, OperandWidth::Smallest, options) {
+            Ok(instruction_binary) => instruction_binary,
+            Err(error) => return Err(single_line_error(error))
+        };
+        *address = address.wrapping_add(instruction_binary.len() as u16);
+        program_binary.append(&mut instruction_binary);
     }
     Ok(())
 }
 
+/// A single line can't use symbols defined later, so the first undefined one is the error
+fn single_line_error(error: StatementError) -> types::ParseError {
+    match error {
+        StatementError::UndefinedSymbols(names) => types::ParseError::SymbolNotDefined(format!("Symbol [{}] not defined", names[0])),
+        StatementError::Parse(error) => error
+    }
+}
+
 /// Assemble a single instruction. Returns a Vec<u8> containing the machine code for that instruction
 /// Returns Err<InstructionError> if any error found during translation
 pub fn assemble_instruction(mnemonic: &str, data: &str) -> Result<Vec<u8>, InstructionError> {
+    assemble_instruction_with_options(mnemonic, data, &AssemblerOptions::default())
+}
+
+/// Same as `assemble_instruction`, for the CPU model in the options.
+/// Unofficial instructions are accepted when enabled in the options
+pub fn assemble_instruction_with_options(mnemonic: &str, data: &str, options: &AssemblerOptions) -> Result<Vec<u8>, InstructionError> {
+    let official = assemble_official_instruction(mnemonic, data, options.cpu_variant);
+    if official.is_ok() || (mnemonic != "NOP" && !opcode::is_unofficial_mnemonic(mnemonic)) {
+        return official;
+    }
+    if !options.illegal_opcodes || options.cpu_variant == CpuVariant::Cmos65C02 {
+        return if mnemonic == "NOP" { official } else { Err(InstructionError::UnofficialInstruction(mnemonic.to_string())) };
+    }
+
+    let addressing_modes = opcode::unofficial_addressing_modes_from_mnemonic(mnemonic)?;
+    let addressing_mode = define_addressing_mode(data, &addressing_modes)?;
+    let op = opcode::translate_unofficial_instruction_to_opcode(mnemonic, addressing_mode)?;
+    Ok(parser::parse_instruction_data(op, addressing_mode, data))
+}
+
+fn assemble_official_instruction(mnemonic: &str, data: &str, variant: CpuVariant) -> Result<Vec<u8>, InstructionError> {
     // Get all possible addressing modes for that mnemonic
-    let addressing_modes = opcode::addressing_modes_from_mnemonic(mnemonic)?;
+    let addressing_modes = opcode::addressing_modes_from_mnemonic(mnemonic, variant)?;
     // For each possible addressing mode, apply its regex to check which one is desired
     let addressing_mode = define_addressing_mode(data, &addressing_modes)?;
     // Given the mnemonic string and the addressing mode, get the opcode (8-bit integer)
-    let op = opcode::translate_instruction_to_opcode(mnemonic, addressing_mode)?;
+    let op = opcode::translate_instruction_to_opcode(mnemonic, addressing_mode, variant)?;
 
     // Parse data associated to the instruction
     Ok(parser::parse_instruction_data(op, addressing_mode, data))
 }
 
+fn is_branch_mnemonic(mnemonic: &str, variant: CpuVariant) -> bool {
+    opcode::addressing_modes_from_mnemonic(mnemonic, variant).is_ok_and(|modes| modes.contains(&AddressingMode::Relative))
+}
+
+/// Branches take a signed 8-bit displacement from the next instruction, which is still accepted as is
+/// when written as an 8-bit literal (e.g. `BNE $FD`).
+/// Any other operand (e.g. `BNE $0600`, `BNE LOOP`, `BNE *-2`) is the target address, converted to that displacement.
+///
+/// So a target in the zero page must be written with 4 digits or as a label: at `.org $0000`,
+/// `BNE $10` skips 16 bytes, while `BNE $0010` branches to $0010
+fn branch_displacement(target: i64, address: u16) -> Result<u8, types::ParseError> {
+    let target = u16::try_from(target).map_err(|_| types::ParseError::InvalidNumber(format!("Invalid branch target: {}", target)))?;
+    let distance = target as i32 - (address as i32 + AddressingMode::Relative.byte_size() as i32);
+    if !(i8::MIN as i32..=i8::MAX as i32).contains(&distance) {
+        return Err(types::ParseError::BranchOutOfRange(target, distance));
+    }
+    Ok(distance as u8)
+}
+
 // Given the instruction data and the list of possible addressing modes for the current instruction
 /// returns the desired addressing mode. It's done by applying each addressing mode associated regex
 /// and check if the data matches.
@@ -94,12 +440,6 @@ fn define_addressing_mode(data: &str, addressing_modes: &Vec<AddressingMode>) ->
     Err(InstructionError::AddressingModeNotRecognized(data.to_string()))
 }
 
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 fn write_file<P>(filename: P, binary_vec: Vec<u8>) -> io::Result<()>
 where P: AsRef<Path>, {
     let mut file = File::create(filename)?;
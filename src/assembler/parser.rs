@@ -2,7 +2,9 @@ use std::collections::HashMap;
 use regex::Regex;
 use lazy_static::lazy_static;
 
-use crate::assembler::types::{SymbolType, Command, NumericType, ParseError};
+use crate::assembler::{directives, expression};
+use crate::assembler::types::{AssemblerOptions, SymbolType, Command, NumericType, ParseError};
+use crate::cpu::types::CpuVariant;
 use crate::cpu::opcode;
 use crate::memory::types::AddressingMode;
 use crate::constants;
@@ -13,28 +15,46 @@ lazy_static! {
     static ref NUM_UP_TO_16_BIT_REGEX: Regex = Regex::new(&format!(r"^{}$", constants::NUM_UP_TO_16_BIT.as_str())).unwrap();
 }
 
+/// Addressing mode syntax around the expression of an operand, tried in this order.
+/// The parentheses must enclose the whole expression, `(TABLE+1)*2` is not indirect
+const INDIRECT_OPERANDS: [(&str, &str); 3] = [("(", ",X)"), ("(", "),Y"), ("(", ")")];
+const INDEXED_OPERANDS: [&str; 2] = [",X", ",Y"];
+/// Limit of constants defined from other constants, so circular definitions stop
+const MAX_SYMBOL_DEPTH: usize = 16;
+
 /// Parse 1 line of source code 
 /// It aggregates labels definitions with instructions.
 /// Creates a symbol table, so labels can be resolved into addresses later
 pub fn parse_line(line: &str, address_number: u16, symbol_table: &mut HashMap<String, Command>) -> Result<Command, ParseError> {
+    parse_line_with_options(line, address_number, symbol_table, &AssemblerOptions::default())
+}
+
+/// Same as `parse_line`, recognising the mnemonics of the instruction set selected in the options
+pub fn parse_line_with_options(line: &str, address_number: u16, symbol_table: &mut HashMap<String, Command>, options: &AssemblerOptions) -> Result<Command, ParseError> {
 
     let mut current_symbol_type = SymbolType::UNDEFINED;
     let mut expected_symbol_type = SymbolType::UNDEFINED;
     let mut current_symbol: String = String::new();
-    let mut data_tokens: Vec<&str> = Vec::with_capacity(50);
+    let mut data = "";
+    let mut remaining = strip_comment(line).trim();
+
+    while !remaining.is_empty() {
 
-    for token in line.trim().split(' ') {
+        let (token, rest) = remaining.split_once(char::is_whitespace).unwrap_or((remaining, ""));
 
-        // Ignore comments
-        if token.starts_with(';') {
+        // Right part is a VALUE or a DATA: the rest of the line, as strings can contain spaces
+        if (expected_symbol_type == SymbolType::VALUE || expected_symbol_type == SymbolType::DATA) && token != "=" {
+            data = remaining;
             break;
         }
-        
+        remaining = rest.trim_start();
+
         // Handle directive
         if token.starts_with('.') {
             
-            // We can't have a directive after any other type of symbol
-            if current_symbol_type != SymbolType::UNDEFINED {
+            // We can't have a directive after any other type of symbol, except data placed at a label
+            let labeled_data = current_symbol_type == SymbolType::LABEL && directives::is_data_directive(token);
+            if current_symbol_type != SymbolType::UNDEFINED && !labeled_data {
                 return Err(ParseError::cannot_define_directive_after_other_symbol());
             }
 
@@ -48,8 +68,8 @@ pub fn parse_line(line: &str, address_number: u16, symbol_table: &mut HashMap<St
             current_symbol = token[0..token.len()-1].to_string();
             current_symbol_type = SymbolType::LABEL;
             expected_symbol_type = SymbolType::MNEMONIC;
-            symbol_table.insert(current_symbol.clone(), 
-                Command::new(current_symbol.clone(), current_symbol_type, address_number.to_string()));
+            define_symbol(symbol_table,
+                Command::new(current_symbol.clone(), current_symbol_type, format!("${:04X}", address_number)))?;
 
         // Handle constants (left part)
         } else if token == "=" {
@@ -67,55 +87,167 @@ pub fn parse_line(line: &str, address_number: u16, symbol_table: &mut HashMap<St
             current_symbol_type = SymbolType::CONSTANT;
             expected_symbol_type = SymbolType::VALUE;
 
-        // Handle right part
+        // Let's try to figure out what current symbol is.
         } else {
-            
-            // Right part is a VALUE
-            if expected_symbol_type == SymbolType::VALUE {
-                
-                symbol_table.insert(current_symbol.clone(),
-                    Command::new(current_symbol.clone(), current_symbol_type, token.to_string()));
-            }
-            // Right part is a DATA
-            else if expected_symbol_type == SymbolType::DATA {
-    
-                // Accumulate tokens
-                if !token.is_empty() {
-                    data_tokens.push(token);
-                }
-                
-            // Let's try to figure out what current symbol is.
-            } else {
-    
-                current_symbol = token.to_string();
-
-                // Handle MNEMONIC
-                if (expected_symbol_type == SymbolType::MNEMONIC || expected_symbol_type == SymbolType::UNDEFINED) && opcode::is_valid_mnemonic(token) {    
-                    current_symbol_type = SymbolType::MNEMONIC;
-                    expected_symbol_type = SymbolType::DATA;
-                } else if expected_symbol_type == SymbolType::MNEMONIC {
-                    return Err(ParseError::mnemonic_expected(current_symbol));
-                }
+
+            current_symbol = token.to_string();
+
+            // Handle MNEMONIC
+            if (expected_symbol_type == SymbolType::MNEMONIC || expected_symbol_type == SymbolType::UNDEFINED) && is_mnemonic(token, remaining, options) {
+                current_symbol_type = SymbolType::MNEMONIC;
+                expected_symbol_type = SymbolType::DATA;
+            } else if expected_symbol_type == SymbolType::MNEMONIC {
+                return Err(ParseError::mnemonic_expected(current_symbol));
             }
         }
     }
 
+    // The value of a constant can be an expression. `*` is the address where it's defined
+    if expected_symbol_type == SymbolType::VALUE && !data.is_empty() {
+        let value = expression::with_current_address(data, address_number);
+        define_symbol(symbol_table, Command::new(current_symbol.clone(), current_symbol_type, value))?;
+    }
+
     if expected_symbol_type == SymbolType::DATA {
-        Ok(Command::new(current_symbol.to_string(), current_symbol_type, data_tokens.join(" ")))
+        Ok(Command::new(current_symbol.to_string(), current_symbol_type, data.to_string()))
     } else {
         Ok(Command::empty())
     }
 }
 
-pub fn replace_symbols(command: &mut Command, symbol_table: &HashMap<String, Command>) {
-    
-    for (key, value) in symbol_table {
+/// Unofficial and 65C02 mnemonics are only reserved when enabled in the options.
+/// Otherwise they are still taken as instructions, so using them reports that they aren't enabled,
+/// unless a value is assigned to them (e.g. `SAX = $10`)
+fn is_mnemonic(token: &str, rest: &str, options: &AssemblerOptions) -> bool {
+    let unofficial = opcode::is_unofficial_mnemonic(token);
+    let cmos = opcode::is_cmos_mnemonic(token);
+    let enabled = (unofficial && options.illegal_opcodes && options.cpu_variant != CpuVariant::Cmos65C02)
+        || (cmos && options.cpu_variant == CpuVariant::Cmos65C02);
+    opcode::is_valid_mnemonic(token) || enabled || ((unofficial || cmos) && !rest.starts_with('='))
+}
+
+/// Directive at the start of the line and the rest of the line (e.g. `.if` and `MAPPER = 1`).
+/// Lines skipped by conditional assembly are not parsed, this only finds where the blocks end
+pub fn leading_directive(line: &str) -> Option<(&str, &str)> {
+    let line = strip_comment(line).trim();
+    let (name, data) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    name.starts_with('.').then(|| (name, data.trim()))
+}
+
+/// The line without its comment. A `;` inside a string or a character literal doesn't start a comment
+fn strip_comment(line: &str) -> &str {
+    let mut index = 0;
+    while let Some(c) = line[index..].chars().next() {
+        match c {
+            ';' => return &line[..index],
+            '"' | '\'' => match expression::quoted_length(&line[index..], c) {
+                Some(length) => {
+                    index += length;
+                    continue;
+                },
+                None => return line
+            },
+            _ => ()
+        }
+        index += c.len_utf8();
+    }
+    line
+}
+
+fn define_symbol(symbol_table: &mut HashMap<String, Command>, symbol: Command) -> Result<(), ParseError> {
+    if symbol_table.contains_key(&symbol.symbol.name) {
+        return Err(ParseError::SymbolAlreadyDefined(format!("Symbol [{}] already defined", symbol.symbol.name)));
+    }
+    symbol_table.insert(symbol.symbol.name.clone(), symbol);
+    Ok(())
+}
+
+/// Operand of an instruction split around its expression (e.g. `(`, `POINTER+1` and `),Y` for `(POINTER+1),Y`)
+#[derive(Debug, PartialEq, Eq)]
+pub struct Operand {
+    pub prefix: &'static str,
+    pub expression: String,
+    pub suffix: &'static str
+}
+
+impl Operand {
+    fn new(prefix: &'static str, expression: &str, suffix: &'static str) -> Self {
+        Self {
+            prefix,
+            expression: expression.to_string(),
+            suffix
+        }
+    }
+
+    /// The operand with the expression replaced by a value (e.g. `($20),Y`)
+    pub fn with_value(&self, value: &str) -> String {
+        format!("{}{}{}", self.prefix, value, self.suffix)
+    }
+}
+
+/// Split the operand of an instruction around its expression.
+/// Returns None for the operands without expression (implicit and accumulator instructions)
+pub fn split_operand(data: &str) -> Option<Operand> {
 
-        // TODO Handle * as current address (PC)
+    // Spaces only matter inside character literals
+    let mut inside_quotes = false;
+    let data: String = data.chars()
+        .filter(|c| {
+            inside_quotes ^= *c == '\'';
+            inside_quotes || !c.is_whitespace()
+        })
+        .collect();
 
-        // Change occurrences of symbols present in our symbol table
-        command.data = command.data.replace(key, &value.data);
+    if data.is_empty() || data == "A" {
+        return None;
     }
+    if let Some(expression) = data.strip_prefix('#') {
+        return Some(Operand::new("#", expression, ""));
+    }
+    for (prefix, suffix) in INDIRECT_OPERANDS {
+        if let Some(expression) = data.strip_prefix(prefix).and_then(|data| data.strip_suffix(suffix)) {
+            if has_balanced_parentheses(expression) {
+                return Some(Operand::new(prefix, expression, suffix));
+            }
+        }
+    }
+    for suffix in INDEXED_OPERANDS {
+        if let Some(expression) = data.strip_suffix(suffix) {
+            return Some(Operand::new("", expression, suffix));
+        }
+    }
+    Some(Operand::new("", &data, ""))
+}
+
+fn has_balanced_parentheses(expression: &str) -> bool {
+    let mut depth = 0;
+    for c in expression.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return false,
+            ')' => depth -= 1,
+            _ => ()
+        }
+    }
+    depth == 0
+}
+
+/// Returns true if the data is a single number (e.g. `$0010`, `%00001111`, `255`)
+pub fn is_number_literal(data: &str) -> bool {
+    NUM_UP_TO_16_BIT_REGEX.is_match(data)
+}
+
+/// Value of a symbol. Constants defined from other symbols (e.g. `END = START+2`) are evaluated too
+pub fn symbol_value(name: &str, symbol_table: &HashMap<String, Command>) -> Option<i64> {
+    symbol_value_with_depth(name, symbol_table, 0)
+}
+
+fn symbol_value_with_depth(name: &str, symbol_table: &HashMap<String, Command>, depth: usize) -> Option<i64> {
+    let value = &symbol_table.get(name)?.data;
+    if depth == MAX_SYMBOL_DEPTH {
+        return None;
+    }
+    expression::evaluate(value, 0, |inner| symbol_value_with_depth(inner, symbol_table, depth + 1)).ok()
 }
 
 /// Parse the data associated with instruction (operand) and return the values associated with them
@@ -125,11 +257,12 @@ pub fn parse_instruction_data(op: u8, addressing_mode: AddressingMode, data: &st
     let mut result: Vec<u8> = Vec::with_capacity(3);
     result.push(op);
 
-    if data.is_empty() {
+    // Implicit and accumulator (e.g. ASL A) instructions have no operand bytes
+    if data.is_empty() || addressing_mode.byte_size() == 1 {
         return result;
     }
 
-    let number = parse_number(&addressing_mode.regex(), &data.to_string()).unwrap();    
+    let number = parse_number(addressing_mode.regex(), data).unwrap();    
     
     // If addressing mode accepts only 1-byte number, then save it as 8-bit number
     if addressing_mode.byte_size() < 3 {
@@ -144,34 +277,26 @@ pub fn parse_instruction_data(op: u8, addressing_mode: AddressingMode, data: &st
     result
 }
 
+/// The address of `.org` can be an expression of the symbols already defined
 pub fn parse_org_data(org: Command, symbol_table: &HashMap<String, Command>) -> Result<u16, ParseError> {
-
-    if let Ok(number) = parse_number(&NUM_UP_TO_16_BIT_REGEX, &org.data.clone()) {
-        return Ok(number);
-    } else {
-        if symbol_table.contains_key(&org.data) {
-            return Ok(parse_number(&NUM_UP_TO_16_BIT_REGEX, &symbol_table.get(&org.data).unwrap().data)?);
-        // If not a symbol, try to parse it to a immediate number
-        } else {
-            return Err(ParseError::SymbolNotDefined(format!("Symbol [{}] not defined", org.data)));
-        }
-    }
+    let address = expression::evaluate(&org.data, 0, |name| symbol_value(name, symbol_table))?;
+    u16::try_from(address).map_err(|_| ParseError::InvalidNumber(format!("Invalid address: {}", org.data)))
 }
 
-fn parse_number(number_regex: &Regex, data: &String) -> Result<u16, ParseError> {
+fn parse_number(number_regex: &Regex, data: &str) -> Result<u16, ParseError> {
 
     // Get the number capture of the regex
-    if let Some(capture) = &number_regex.captures(&data) {
+    if let Some(capture) = number_regex.captures(data) {
         let numeric_string = &capture["number"];
 
         // Check what is the numeric type (binary, octal, decimal or hexadecimal)
         let (numeric_type, value) = NumericType::detect_type_in_string(numeric_string);
         if let Ok(result) = u16::from_str_radix(value, numeric_type.to_radix()) {
-            return Ok(result);
+            Ok(result)
         } else {
-            return Err(ParseError::InvalidNumber(format!("Invalid integer: {}", numeric_string)));
+            Err(ParseError::InvalidNumber(format!("Invalid integer: {}", numeric_string)))
         }
     } else {
-        return Err(ParseError::FatalError("Error detecting number value".to_string()));
+        Err(ParseError::FatalError("Error detecting number value".to_string()))
     }
-}
\ No newline at end of file
+}
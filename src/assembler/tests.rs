@@ -1,6 +1,7 @@
 use std::fs;
 
 use super::*;
+use types::{AssemblyError, SourceLocation};
 
 #[test]
 fn parse_line_comment_only() {
@@ -91,7 +92,7 @@ fn parse_line_label_mnemonic_with_data_and_comments() {
     let mut symbol_table: HashMap<String, Command> = HashMap::new();
 
     let expected_command = Command::new("ADC".to_string(), SymbolType::MNEMONIC, "RESULT".to_string());
-    let expected_label = Command::new("LOOP".to_string(), SymbolType::LABEL, address.to_string());
+    let expected_label = Command::new("LOOP".to_string(), SymbolType::LABEL, "$00FF".to_string());
 
     // When
     let result = parser::parse_line(line, address, &mut symbol_table);
@@ -217,7 +218,7 @@ fn process_line_should_not_add_any_binary_when_process_label_only() {
     let line = "LABEL:";
     let mut symbol_table: HashMap<String, Command> = HashMap::new();
     let mut program_binary: Vec<u8> = Vec::new();
-    let expected_label = Command::new("LABEL".to_string(), SymbolType::LABEL, "10".to_string());
+    let expected_label = Command::new("LABEL".to_string(), SymbolType::LABEL, "$000A".to_string());
 
     // When
     if let Err(error) = process_line(line, &mut address, &mut program_binary, &mut symbol_table) {
@@ -289,6 +290,76 @@ fn process_line_should_append_binary_to_current_vector() {
     assert_eq!(0x000C, address);
 }
 
+#[test]
+fn process_line_should_wrap_address_at_end_of_memory() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let mut address: u16 = 0xFFFF;
+
+    // When
+    let result = process_line("LDA $1234", &mut address, &mut program_binary, &mut symbol_table);
+
+    // Then
+    assert_eq!(Ok(()), result);
+    assert_eq!(vec![0xAD, 0x34, 0x12], program_binary);
+    assert_eq!(0x0002, address);
+}
+
+#[test]
+fn process_line_should_convert_branch_target_address_to_displacement() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let mut address: u16 = 0x0600;
+
+    // When
+    process_line("BNE $0610", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+    process_line("BEQ $0600", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+    process_line("BCC $FD", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+
+    // Then
+    assert_eq!(vec![0xD0, 0x0E, 0xF0, 0xFC, 0x90, 0xFD], program_binary);
+    assert_eq!(0x0606, address);
+}
+
+#[test]
+fn assemble_source_should_take_8_bit_branch_operand_as_displacement_in_zero_page() {
+
+    // Given
+    // $10 is a displacement, while $0010 and a symbol are targets
+    let source = "
+        TARGET = $0010
+        .org $0000
+        BNE $10
+        BNE $0010
+        BNE TARGET";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xD0, 0x10, 0xD0, 0x0C, 0xD0, 0x0A]), result);
+}
+
+#[test]
+fn process_line_should_reject_branch_target_out_of_range() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let mut address: u16 = 0x0600;
+
+    // When
+    let result = process_line("BNE $0682", &mut address, &mut program_binary, &mut symbol_table);
+
+    // Then
+    assert_eq!(Err(types::ParseError::BranchOutOfRange(0x0682, 128)), result);
+    assert!(program_binary.is_empty());
+}
+
 #[test]
 pub fn test_assembly_sum_1_and_2_program() {
 
@@ -305,4 +376,885 @@ pub fn test_assembly_sum_1_and_2_program() {
 
     //Then
     assert_eq!(expected_binary, fs::read(output_filename).unwrap());
-}
\ No newline at end of file
+}
+
+#[test]
+fn assemble_instruction_should_reject_unofficial_instruction_by_default() {
+
+    // When
+    let result = assemble_instruction("LAX", "$10");
+
+    // Then
+    assert_eq!(Err(InstructionError::UnofficialInstruction("LAX".to_string())), result);
+    assert!(assemble_instruction("NOP", "#$01").is_err());
+}
+
+#[test]
+fn assemble_instruction_should_assemble_unofficial_instruction_when_enabled() {
+
+    // Given
+    let options = AssemblerOptions { illegal_opcodes: true, ..Default::default() };
+
+    // Then
+    assert_eq!(Ok(vec![0xB7, 0x10]), assemble_instruction_with_options("LAX", "$10,Y", &options));
+    assert_eq!(Ok(vec![0xDB, 0x00, 0x10]), assemble_instruction_with_options("DCP", "$1000,Y", &options));
+    assert_eq!(Ok(vec![0x80, 0x01]), assemble_instruction_with_options("NOP", "#$01", &options));
+    assert_eq!(Ok(vec![0xEA]), assemble_instruction_with_options("NOP", "", &options));
+    assert_eq!(Ok(vec![0x69, 0x01]), assemble_instruction_with_options("ADC", "#$01", &options));
+}
+
+#[test]
+fn process_line_should_assemble_unofficial_instruction_when_enabled() {
+
+    // Given
+    let mut address: u16 = 0;
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let options = AssemblerOptions { illegal_opcodes: true, ..Default::default() };
+
+    // When
+    let result = process_line_with_options("SLO $44", &mut address, &mut program_binary, &mut symbol_table, &options);
+
+    // Then
+    assert_eq!(Ok(()), result);
+    assert_eq!(vec![0x07, 0x44], program_binary);
+}
+
+#[test]
+fn assemble_source_should_accept_unofficial_mnemonic_as_constant_by_default() {
+
+    // Given
+    let source = "SAX = $10\nLDA SAX";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xA5, 0x10]), result);
+}
+
+#[test]
+fn assemble_source_should_reject_unofficial_mnemonic_as_constant_when_enabled() {
+
+    // Given
+    let options = AssemblerOptions { illegal_opcodes: true, ..Default::default() };
+
+    // When
+    let result = assemble_source("SAX = $10", &options);
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::cannot_assign_value_to_non_constant(SymbolType::MNEMONIC))), result);
+}
+
+#[test]
+fn process_line_should_report_unofficial_instruction_not_enabled() {
+
+    // Given
+    let mut address: u16 = 0;
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+
+    // When
+    let result = process_line("SLO $44", &mut address, &mut program_binary, &mut symbol_table);
+
+    // Then
+    assert_eq!(Err(types::ParseError::InstructionError(InstructionError::UnofficialInstruction("SLO".to_string()))), result);
+}
+
+#[test]
+fn assemble_instruction_should_assemble_65c02_instructions_when_selected() {
+
+    // Given
+    let options = AssemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    // Then
+    assert_eq!(Ok(vec![0xB2, 0x10]), assemble_instruction_with_options("LDA", "($10)", &options));
+    assert_eq!(Ok(vec![0x7C, 0x00, 0x10]), assemble_instruction_with_options("JMP", "($1000,X)", &options));
+    assert_eq!(Ok(vec![0x80, 0x02]), assemble_instruction_with_options("BRA", "$02", &options));
+    assert_eq!(Ok(vec![0x9E, 0x00, 0x02]), assemble_instruction_with_options("STZ", "$0200,X", &options));
+    assert_eq!(Ok(vec![0x1A]), assemble_instruction_with_options("INC", "A", &options));
+    assert_eq!(Ok(vec![0x89, 0x01]), assemble_instruction_with_options("BIT", "#$01", &options));
+    assert_eq!(Ok(vec![0xDA]), assemble_instruction_with_options("PHX", "", &options));
+    assert_eq!(Ok(vec![0xE6, 0x10]), assemble_instruction_with_options("INC", "$10", &options));
+}
+
+#[test]
+fn assemble_instruction_should_reject_65c02_instructions_on_2a03() {
+
+    // Then
+    assert!(assemble_instruction("LDA", "($10)").is_err());
+    assert!(assemble_instruction("STZ", "$10").is_err());
+    assert!(assemble_instruction("INC", "A").is_err());
+}
+
+#[test]
+fn assemble_source_should_accept_65c02_mnemonic_as_constant_on_2a03() {
+
+    // Given
+    let source = "STZ = $10\nLDA STZ";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xA5, 0x10]), result);
+}
+
+#[test]
+fn assemble_source_should_reject_65c02_mnemonic_as_constant_on_65c02() {
+
+    // Given
+    let options = AssemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    // When
+    let result = assemble_source("STZ = $10", &options);
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::cannot_assign_value_to_non_constant(SymbolType::MNEMONIC))), result);
+}
+
+#[test]
+fn assemble_source_should_report_65c02_instruction_on_2a03() {
+
+    // When
+    let result = assemble_source("STZ $10", &AssemblerOptions::default());
+
+    // Then
+    assert!(matches!(result, Err(AssemblyError::Line(_, types::ParseError::InstructionError(_)))), "{:?}", result);
+}
+
+#[test]
+fn assemble_instruction_should_reject_unofficial_instructions_on_65c02() {
+
+    // Given
+    let options = AssemblerOptions { illegal_opcodes: true, cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    // Then
+    assert_eq!(Err(InstructionError::UnofficialInstruction("LAX".to_string())), assemble_instruction_with_options("LAX", "$10", &options));
+}
+
+#[test]
+fn parse_line_should_reject_label_defined_twice() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    parser::parse_line("LOOP: INX", 0x0600, &mut symbol_table).unwrap();
+
+    // When
+    let result = parser::parse_line("LOOP: DEX", 0x0601, &mut symbol_table);
+
+    // Then
+    assert!(matches!(result, Err(types::ParseError::SymbolAlreadyDefined(_))));
+}
+
+#[test]
+fn process_line_should_only_replace_whole_symbol_names() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let mut address: u16 = 0;
+    process_line("TABLE = $0300", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+    process_line("TAB = $10", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+
+    // When
+    let result = process_line("LDA TABLE,X", &mut address, &mut program_binary, &mut symbol_table);
+
+    // Then
+    assert_eq!(Ok(()), result);
+    assert_eq!(vec![0xBD, 0x00, 0x03], program_binary);
+}
+
+#[test]
+fn assemble_source_should_resolve_forward_label() {
+
+    // Given
+    let source = "
+        .org $0600
+        JMP START
+        NOP
+        START: LDA #$01
+        JSR SUBROUTINE
+        BRK
+        SUBROUTINE: RTS";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x4C, 0x04, 0x06, 0xEA, 0xA9, 0x01, 0x20, 0x0A, 0x06, 0x00, 0x60]), result);
+}
+
+#[test]
+fn assemble_source_should_resolve_branch_labels() {
+
+    // Given
+    let source = "
+        .org $0600
+        LDX #$08
+        LOOP: DEX
+        BEQ DONE
+        BNE LOOP
+        DONE: BRK";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xA2, 0x08, 0xCA, 0xF0, 0x02, 0xD0, 0xFB, 0x00]), result);
+}
+
+#[test]
+fn assemble_source_should_size_forward_symbols_as_absolute() {
+
+    // Given
+    // ZP is only known in the second pass, the first one reserved an absolute operand
+    let source = "
+        LDA ZP
+        LDA (POINTER),Y
+        ZP = $10
+        POINTER = $20
+        LDA ZP";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xAD, 0x10, 0x00, 0xB1, 0x20, 0xA5, 0x10]), result);
+}
+
+#[test]
+fn assemble_source_should_resolve_constants_defined_from_other_symbols() {
+
+    // Given
+    let source = "
+        .org $8000
+        JMP LAST
+        START: NOP
+        LAST = START";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x4C, 0x03, 0x80, 0xEA]), result);
+}
+
+#[test]
+fn assemble_source_should_report_every_undefined_symbol_with_its_line() {
+
+    // Given
+    let source = "
+        JMP NOWHERE
+        LDA TABLE,X
+        BNE NOWHERE";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    let expected_symbols = vec![
+        (SourceLocation::new(None, 2), "NOWHERE".to_string()),
+        (SourceLocation::new(None, 3), "TABLE".to_string()),
+        (SourceLocation::new(None, 4), "NOWHERE".to_string())];
+    assert_eq!(Err(AssemblyError::UndefinedSymbols(expected_symbols)), result);
+    assert_eq!("Undefined symbols:\n  line 2: NOWHERE\n  line 3: TABLE\n  line 4: NOWHERE", result.unwrap_err().to_string());
+}
+
+#[test]
+fn assemble_source_should_report_line_of_invalid_instruction() {
+
+    // Given
+    let source = "NOP\nLDA ($1000),Y";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert!(matches!(result, Err(AssemblyError::Line(SourceLocation { line: 2, .. }, types::ParseError::InstructionError(_)))));
+}
+
+#[test]
+fn process_line_should_convert_backward_branch_label_to_displacement() {
+
+    // Given
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = Vec::new();
+    let mut address: u16 = 0x0010;
+
+    // When
+    process_line("LOOP: DEX", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+    process_line("BNE LOOP", &mut address, &mut program_binary, &mut symbol_table).unwrap();
+
+    // Then
+    // LOOP is $0010, a 16-bit address, not a displacement of 16 bytes
+    assert_eq!(vec![0xCA, 0xD0, 0xFD], program_binary);
+}
+
+#[test]
+fn assemble_source_should_convert_65c02_branch_label_to_displacement() {
+
+    // Given
+    let source = "
+        .org $0600
+        BRA SKIP
+        NOP
+        SKIP: RTS";
+    let options = AssemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    // When
+    let result = assemble_source(source, &options);
+
+    // Then
+    assert_eq!(Ok(vec![0x80, 0x01, 0xEA, 0x60]), result);
+}
+
+#[test]
+fn assemble_source_should_accept_branch_at_range_limits() {
+
+    // Given
+    // BEQ $0681 is +127 bytes from $0602, BNE $0584 is -128 bytes from $0604
+    let source = "
+        .org $0600
+        BEQ $0681
+        BNE $0584";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xF0, 0x7F, 0xD0, 0x80]), result);
+}
+
+#[test]
+fn assemble_source_should_report_distance_of_branch_out_of_range() {
+
+    // Given
+    let source = "
+        .org $0600
+        BACK: NOP
+        .org $0700
+        BNE BACK";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 5), types::ParseError::BranchOutOfRange(0x0600, -258))), result);
+    assert_eq!("Error in line 5: Branch target $0600 is out of range: -258 bytes from the next instruction, the limit is -128..+127",
+        result.unwrap_err().to_string());
+}
+
+#[test]
+fn evaluate_should_follow_operator_precedence() {
+
+    // Given
+    let no_symbols = |_: &str| None;
+
+    // Then
+    assert_eq!(Ok(14), expression::evaluate("2+3*4", 0, no_symbols));
+    assert_eq!(Ok(20), expression::evaluate("(2 + 3) * 4", 0, no_symbols));
+    assert_eq!(Ok(17), expression::evaluate("1<<4|1", 0, no_symbols));
+    assert_eq!(Ok(0x0F), expression::evaluate("$FF>>4&$0F", 0, no_symbols));
+    assert_eq!(Ok(0xF0), expression::evaluate("$FF&~$0F", 0, no_symbols));
+    assert_eq!(Ok(0x81), expression::evaluate("$80^1", 0, no_symbols));
+    assert_eq!(Ok(1), expression::evaluate("10%3", 0, no_symbols));
+    assert_eq!(Ok(-5), expression::evaluate("-10/2", 0, no_symbols));
+}
+
+#[test]
+fn evaluate_should_parse_numbers_characters_and_current_address() {
+
+    // Given
+    let no_symbols = |_: &str| None;
+
+    // Then
+    assert_eq!(Ok(10), expression::evaluate("%1010", 0, no_symbols));
+    assert_eq!(Ok(10), expression::evaluate("0b1010", 0, no_symbols));
+    assert_eq!(Ok(15), expression::evaluate("@17", 0, no_symbols));
+    assert_eq!(Ok(0x1F), expression::evaluate("0x1F", 0, no_symbols));
+    assert_eq!(Ok(0xC1), expression::evaluate("'A'|$80", 0, no_symbols));
+    assert_eq!(Ok(0x0602), expression::evaluate("*+2", 0x0600, no_symbols));
+    assert_eq!(Ok(0x0C00), expression::evaluate("**2", 0x0600, no_symbols));
+}
+
+#[test]
+fn evaluate_should_take_low_and_high_bytes() {
+
+    // Given
+    let symbols = |name: &str| (name == "TABLE").then_some(0x12FF);
+
+    // Then
+    assert_eq!(Ok(0xFF), expression::evaluate("<TABLE", 0, symbols));
+    assert_eq!(Ok(0x12), expression::evaluate(">TABLE", 0, symbols));
+    assert_eq!(Ok(0x100), expression::evaluate("<TABLE+1", 0, symbols));
+    assert_eq!(Ok(0x00), expression::evaluate("<(TABLE+1)", 0, symbols));
+    assert_eq!(Ok(0x13), expression::evaluate(">(TABLE+1)", 0, symbols));
+}
+
+#[test]
+fn evaluate_should_report_invalid_expressions() {
+
+    // Given
+    let no_symbols = |_: &str| None;
+
+    // Then
+    assert_eq!(Err(types::ParseError::InvalidExpression("Division by zero".to_string())), expression::evaluate("1/0", 0, no_symbols));
+    assert_eq!(Err(types::ParseError::InvalidExpression("Division overflow: -9223372036854775808 / -1".to_string())),
+        expression::evaluate("(-9223372036854775807-1)/-1", 0, no_symbols));
+    assert_eq!(Err(types::ParseError::InvalidExpression("Division overflow: -9223372036854775808 % -1".to_string())),
+        expression::evaluate("(-9223372036854775807-1)%-1", 0, no_symbols));
+    assert_eq!(Err(types::ParseError::InvalidExpression("Missing closing parenthesis".to_string())), expression::evaluate("(1+2", 0, no_symbols));
+    assert_eq!(Err(types::ParseError::InvalidExpression("Invalid expression: 1 2".to_string())), expression::evaluate("1 2", 0, no_symbols));
+    assert_eq!(Err(types::ParseError::SymbolNotDefined("Symbol [LOOP] not defined".to_string())), expression::evaluate("LOOP+1", 0, no_symbols));
+}
+
+#[test]
+fn split_operand_should_find_the_expression_of_each_addressing_mode() {
+
+    // Then
+    assert_eq!(None, parser::split_operand(""));
+    assert_eq!(None, parser::split_operand("A"));
+    assert_eq!(("#", "<(TABLE+2)".to_string(), ""), split("#<(TABLE+2)"));
+    assert_eq!(("", "BUFFER+1".to_string(), ",X"), split("BUFFER + 1,X"));
+    assert_eq!(("(", "POINTER".to_string(), "),Y"), split("(POINTER),Y"));
+    assert_eq!(("(", "POINTER+2".to_string(), ",X)"), split("(POINTER+2,X)"));
+    assert_eq!(("(", "VECTOR".to_string(), ")"), split("(VECTOR)"));
+    assert_eq!(("", "(TABLE+1)*2".to_string(), ""), split("(TABLE+1)*2"));
+    assert_eq!(("#", "' '".to_string(), ""), split("#' '"));
+    assert_eq!("($20),Y", parser::split_operand("(POINTER),Y").unwrap().with_value("$20"));
+}
+
+fn split(data: &str) -> (&'static str, String, &'static str) {
+    let operand = parser::split_operand(data).unwrap();
+    (operand.prefix, operand.expression, operand.suffix)
+}
+
+#[test]
+fn assemble_source_should_evaluate_operand_expressions() {
+
+    // Given
+    let source = "
+        .org $0600
+        BUFFER = $0200
+        LDA #<(TABLE+2)
+        LDX #>TABLE
+        STA BUFFER+1,X
+        LDA #'A'|$80
+        TABLE: NOP";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xA9, 0x0B, 0xA2, 0x06, 0x9D, 0x01, 0x02, 0xA9, 0xC1, 0xEA]), result);
+}
+
+#[test]
+fn assemble_source_should_use_zero_page_for_small_known_values() {
+
+    // Given
+    // A single literal keeps its width, an expression uses the smallest one the instruction accepts
+    let source = "
+        POINTER = $20
+        LDA (POINTER),Y
+        LDA POINTER+1
+        LDA $0021
+        JMP POINTER";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xB1, 0x20, 0xA5, 0x21, 0xAD, 0x21, 0x00, 0x4C, 0x20, 0x00]), result);
+}
+
+#[test]
+fn assemble_source_should_use_current_address() {
+
+    // Given
+    let source = "
+        .org $0600
+        LOOP: DEX
+        BNE *-1
+        JMP *
+        HERE = *
+        JMP HERE";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xCA, 0xD0, 0xFD, 0x4C, 0x03, 0x06, 0x4C, 0x06, 0x06]), result);
+}
+
+#[test]
+fn assemble_source_should_reject_immediate_value_above_8_bits() {
+
+    // Given
+    let source = "LDA #255+1";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::InvalidNumber("Value 256 doesn't fit in 8 bits".to_string()))), result);
+}
+
+#[test]
+fn assemble_source_should_reject_division_overflow() {
+
+    // Given
+    let source = "LDA #(-9223372036854775807-1)/-1";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert!(matches!(result, Err(AssemblyError::Line(_, types::ParseError::InvalidExpression(_)))), "{:?}", result);
+}
+
+#[test]
+fn parse_line_should_keep_spaces_and_semicolons_inside_strings() {
+
+    // Given
+    let line = "MESSAGE: .text \"Hello; world\", ';' ; Greeting";
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let expected_command = Command::new(".text".to_string(), SymbolType::DIRECTIVE, "\"Hello; world\", ';'".to_string());
+
+    // When
+    let result = parser::parse_line(line, 0x0600, &mut symbol_table);
+
+    // Then
+    assert_eq!(result, Ok(expected_command));
+    assert_eq!(Some(0x0600), parser::symbol_value("MESSAGE", &symbol_table));
+}
+
+#[test]
+fn assemble_source_should_emit_bytes_words_and_dwords() {
+
+    // Given
+    let source = "
+        VALUE = $1234
+        .byte 1, $FF, -1, <VALUE, 'A'
+        .db >VALUE
+        .word VALUE, $C000
+        .dw -2
+        .dword $12345678";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![
+        0x01, 0xFF, 0xFF, 0x34, 0x41,
+        0x12,
+        0x34, 0x12, 0x00, 0xC0,
+        0xFE, 0xFF,
+        0x78, 0x56, 0x34, 0x12]), result);
+}
+
+#[test]
+fn assemble_source_should_emit_strings_with_escape_sequences() {
+
+    // Given
+    let source = r#"
+        .text "Hi\n", "\"\x41\""
+        .asciiz "A\tB", $80"#;
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![b'H', b'i', 0x0A, b'"', b'A', b'"', b'A', 0x09, b'B', 0x80, 0x00]), result);
+}
+
+#[test]
+fn assemble_source_should_reserve_and_fill_space() {
+
+    // Given
+    let source = "
+        COUNT = 2
+        .res COUNT
+        .ds 1, $EA
+        .fill COUNT*2, 'x'";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x00, 0x00, 0xEA, b'x', b'x', b'x', b'x']), result);
+}
+
+#[test]
+fn assemble_source_should_pad_to_the_alignment() {
+
+    // Given
+    let source = "
+        .org $0601
+        NOP
+        .align 4, $FF
+        ALIGNED: .align 4
+        .word ALIGNED";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xEA, 0xFF, 0xFF, 0x04, 0x06]), result);
+}
+
+#[test]
+fn assemble_source_should_place_labels_and_forward_references_in_data() {
+
+    // Given
+    let source = "
+        .org $8000
+        VECTORS: .word RESET, NMI
+        TABLE: .byte <RESET, >RESET, END-TABLE
+        RESET: LDA TABLE+1
+        NMI: RTI
+        END:";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x07, 0x80, 0x0A, 0x80, 0x07, 0x80, 0x07, 0xAD, 0x05, 0x80, 0x40]), result);
+}
+
+#[test]
+fn assemble_source_should_report_data_errors() {
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::InvalidNumber("Value 256 doesn't fit in 8 bits".to_string()))),
+        assemble_source(".byte 255+1", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::SymbolNotDefined("Symbol [COUNT] not defined".to_string()))),
+        assemble_source(".res COUNT\nCOUNT = 2", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::SyntaxError("Invalid string: \"Hi".to_string()))),
+        assemble_source(".byte \"Hi", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::InvalidExpression("Invalid escape sequence: \\q".to_string()))),
+        assemble_source(r#".text "\q""#, &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::UndefinedSymbols(vec![(SourceLocation::new(None, 1), "NOWHERE".to_string())])),
+        assemble_source(".word NOWHERE", &AssemblerOptions::default()));
+}
+
+#[test]
+fn assemble_source_should_reject_data_directives_without_values() {
+
+    // Then
+    for directive in [".byte", ".db", ".word", ".dw", ".dword", ".text", ".asciiz"] {
+        let expected_error = types::ParseError::SyntaxError(format!("Missing argument 1 of {}", directive));
+        assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), expected_error)), assemble_source(directive, &AssemblerOptions::default()));
+    }
+}
+
+#[test]
+fn assemble_source_should_include_binary_file() {
+
+    // Given
+    let root_dir = env!("CARGO_MANIFEST_DIR");
+    let binary_filename = format!("{}/resources/test/incbin.bin", root_dir);
+    let source = format!("
+        .incbin \"{0}\"
+        .incbin \"{0}\", 3
+        .incbin \"{0}\", 1, 2
+        AFTER: .byte <AFTER", binary_filename);
+
+    // When
+    let result = assemble_source(&source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x10, 0x20, 0x30, 0x40, 0x50, 0x40, 0x50, 0x20, 0x30, 0x09]), result);
+}
+
+#[test]
+fn assemble_source_should_report_incbin_range_out_of_file() {
+
+    // Given
+    let root_dir = env!("CARGO_MANIFEST_DIR");
+    let binary_filename = format!("{}/resources/test/incbin.bin", root_dir);
+    let source = format!(".incbin \"{}\", 4, 2", binary_filename);
+
+    // When
+    let result = assemble_source(&source, &AssemblerOptions::default());
+
+    // Then
+    let expected_message = format!("Range 4..6 is out of {} (5 bytes)", binary_filename);
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::FileError(expected_message))), result);
+}
+
+#[test]
+fn process_line_should_append_data_and_update_address() {
+
+    // Given
+    let mut address: u16 = 0x0600;
+    let mut symbol_table: HashMap<String, Command> = HashMap::new();
+    let mut program_binary: Vec<u8> = vec![0xEA];
+
+    // When
+    if let Err(error) = process_line("DATA: .word $1234, DATA", &mut address, &mut program_binary, &mut symbol_table) {
+        panic!("Error: {:?}", error);
+    }
+
+    // Then
+    assert_eq!(vec![0xEA, 0x34, 0x12, 0x00, 0x06], program_binary);
+    assert_eq!(0x0604, address);
+}
+
+#[test]
+fn evaluate_should_compare_values() {
+
+    // Given
+    let symbols = |name: &str| (name == "MAPPER").then_some(4);
+
+    // Then
+    assert_eq!(Ok(1), expression::evaluate("MAPPER = 4", 0, symbols));
+    assert_eq!(Ok(0), expression::evaluate("MAPPER <> 4", 0, symbols));
+    assert_eq!(Ok(1), expression::evaluate("MAPPER >= 1 && MAPPER <= 4", 0, symbols));
+    assert_eq!(Ok(1), expression::evaluate("MAPPER < 1 || MAPPER > 3", 0, symbols));
+    assert_eq!(Ok(1), expression::evaluate("MAPPER = 2+2", 0, symbols));
+    assert_eq!(Ok(0), expression::evaluate("!MAPPER", 0, symbols));
+    assert_eq!(Ok(0x12), expression::evaluate(">$1234", 0, symbols));
+}
+
+#[test]
+fn assemble_source_should_assemble_selected_conditional_branches() {
+
+    // Given
+    let source = "
+        MAPPER = 2
+        .if MAPPER = 1
+            LDA #1
+        .elseif MAPPER = 2
+            LDA #2
+            .ifdef MAPPER
+                LDX #2
+            .else
+                LDX #0
+            .endif
+        .elseif MAPPER > 0
+            LDA #3
+        .else
+            LDA #0
+        .endif
+        .ifndef DEBUG
+            NOP
+        .endif";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0xA9, 0x02, 0xA2, 0x02, 0xEA]), result);
+}
+
+#[test]
+fn assemble_source_should_not_parse_skipped_lines() {
+
+    // Given
+    // Skipped lines can be invalid and can define symbols again
+    let source = "
+        .if 0
+            LDA ($1000),Y
+            LABEL:
+            .if 1
+                NOT AN INSTRUCTION
+            .endif
+        .endif
+        LABEL: .word LABEL";
+
+    // When
+    let result = assemble_source(source, &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(Ok(vec![0x00, 0x00]), result);
+}
+
+#[test]
+fn assemble_source_should_report_unbalanced_conditional_blocks() {
+
+    // Then
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 2), types::ParseError::SyntaxError("Missing .endif".to_string()))),
+        assemble_source("NOP\n.if 1\nNOP", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::SyntaxError(".else without .if".to_string()))),
+        assemble_source(".else", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 3), types::ParseError::SyntaxError(".elseif after .else".to_string()))),
+        assemble_source(".if 1\n.else\n.elseif 1\n.endif", &AssemblerOptions::default()));
+    assert_eq!(Err(AssemblyError::Line(SourceLocation::new(None, 1), types::ParseError::SymbolNotDefined("Symbol [LATER] not defined".to_string()))),
+        assemble_source(".if LATER\n.endif\nLATER = 1", &AssemblerOptions::default()));
+}
+
+#[test]
+fn assemble_file_should_read_included_files() {
+
+    // Given
+    let root_dir = env!("CARGO_MANIFEST_DIR");
+    let filename = format!("{}/resources/test/include/main.asm", root_dir);
+    let options = AssemblerOptions {
+        include_paths: vec![format!("{}/resources/test/include/lib", root_dir).into()],
+        ..Default::default()
+    };
+
+    // When
+    let result = assemble_file(Path::new(&filename), &options);
+
+    // Then
+    assert_eq!(Ok(vec![0xA9, 0x00, 0x8D, 0x00, 0x02, 0x60, 0x20, 0x00, 0x80, 0x00]), result);
+}
+
+#[test]
+fn assemble_file_should_report_included_file_not_found() {
+
+    // Given
+    let root_dir = env!("CARGO_MANIFEST_DIR");
+    let filename = format!("{}/resources/test/include/main.asm", root_dir);
+
+    // When
+    let result = assemble_file(Path::new(&filename), &AssemblerOptions::default());
+
+    // Then
+    let expected_location = SourceLocation::new(Some(filename.into()), 4);
+    assert_eq!(Err(AssemblyError::Line(expected_location, types::ParseError::FileError("File routines.asm not found".to_string()))), result);
+}
+
+#[test]
+fn assemble_file_should_report_the_include_chain_of_errors() {
+
+    // Given
+    let directory = format!("{}/resources/test/include", env!("CARGO_MANIFEST_DIR"));
+    let filename = format!("{}/error.asm", directory);
+
+    // When
+    let result = assemble_file(Path::new(&filename), &AssemblerOptions::default());
+
+    // Then
+    let error = result.unwrap_err();
+    assert!(matches!(&error, AssemblyError::Line(SourceLocation { line: 2, .. }, types::ParseError::InstructionError(_))));
+    assert!(error.to_string().starts_with(&format!("Error in line 2 of {0}/lib/error.asm, included from line 2 of {0}/error.asm: ", directory)));
+}
+
+#[test]
+fn assemble_file_should_reject_circular_inclusion() {
+
+    // Given
+    let directory = format!("{}/resources/test/include", env!("CARGO_MANIFEST_DIR"));
+    let filename = format!("{}/cycle_a.asm", directory);
+
+    // When
+    let result = assemble_file(Path::new(&filename), &AssemblerOptions::default());
+
+    // Then
+    assert_eq!(format!("Error in line 2 of {0}/cycle_b.asm, included from line 1 of {0}/cycle_a.asm: Circular inclusion of {0}/cycle_a.asm", directory),
+        result.unwrap_err().to_string());
+}
+
+#[test]
+fn assemble_file_should_report_missing_main_file() {
+
+    // When
+    let result = assemble_file(Path::new("missing.asm"), &AssemblerOptions::default());
+
+    // Then
+    assert!(matches!(result, Err(AssemblyError::File(message)) if message.starts_with("Cannot read missing.asm: ")));
+}
@@ -1,15 +1,104 @@
-use crate::cpu::types::InstructionError;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::cpu::types::{CpuVariant, InstructionError};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ParseError {
     SyntaxError(String),
     SymbolAlreadyDefined(String),
     InvalidNumber(String),
+    InvalidExpression(String),
     SymbolNotDefined(String),
     InstructionError(InstructionError),
+    /// The branch target (first field) is too far from the next instruction.
+    /// The second field is the distance in bytes, which must be in -128..=127
+    BranchOutOfRange(u16, i32),
+    /// A file used by the source (e.g. `.incbin`) can't be read
+    FileError(String),
     FatalError(String)
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::SyntaxError(ref message) |
+            ParseError::SymbolAlreadyDefined(ref message) |
+            ParseError::InvalidNumber(ref message) |
+            ParseError::InvalidExpression(ref message) |
+            ParseError::SymbolNotDefined(ref message) |
+            ParseError::FileError(ref message) => write!(f, "{}", message),
+            ParseError::InstructionError(ref error) => write!(f, "{}", error),
+            ParseError::BranchOutOfRange(target, distance) => {
+                write!(f, "Branch target ${:04X} is out of range: {:+} bytes from the next instruction, the limit is -128..+127", target, distance)
+            },
+            ParseError::FatalError(ref message) => write!(f, "Fatal error! Message: {}", message)
+        }
+    }
+}
+
+/// Line of the source, with the `.include` lines that led to its file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SourceLocation {
+    /// None for a source given as text
+    pub file: Option<PathBuf>,
+    /// Starts at 1
+    pub line: usize,
+    /// Location of the `.include` of this file, None for the main source
+    pub included_from: Option<Box<SourceLocation>>
+}
+
+impl SourceLocation {
+    /// Line of the main source
+    pub fn new(file: Option<PathBuf>, line: usize) -> Self {
+        Self {
+            file,
+            line,
+            included_from: None
+        }
+    }
+}
+
+/// The whole include chain, innermost first (e.g. `line 3 of lib.asm, included from line 12 of main.asm`)
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+        if let Some(file) = &self.file {
+            write!(f, " of {}", file.display())?;
+        }
+        if let Some(location) = &self.included_from {
+            write!(f, ", included from {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error assembling a whole program
+#[derive(Debug, PartialEq, Clone)]
+pub enum AssemblyError {
+    Line(SourceLocation, ParseError),
+    /// Every reference to a symbol that is never defined, as (location, symbol name)
+    UndefinedSymbols(Vec<(SourceLocation, String)>),
+    /// The main source file can't be read
+    File(String)
+}
+
+impl fmt::Display for AssemblyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblyError::Line(location, ref error) => write!(f, "Error in {}: {}", location, error),
+            AssemblyError::UndefinedSymbols(ref symbols) => {
+                write!(f, "Undefined symbols:")?;
+                for (location, name) in symbols {
+                    write!(f, "\n  {}: {}", location, name)?;
+                }
+                Ok(())
+            },
+            AssemblyError::File(ref message) => write!(f, "{}", message)
+        }
+    }
+}
+
 impl ParseError {
     pub fn cannot_define_directive_after_other_symbol() -> Self {
         ParseError::SyntaxError("Cannot define a DIRECTIVE after other types of symbols".to_string())
@@ -28,6 +117,18 @@ impl ParseError {
     }
 }
 
+/// Settings of the assembler. The defaults only accept the official instruction set
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AssemblerOptions {
+    /// Accepts the unofficial instructions (e.g. LAX, SLO, DCP) and NOPs with operands.
+    /// Ignored for the 65C02, which has no unofficial instructions
+    pub illegal_opcodes: bool,
+    /// CPU model the program is assembled for. The 65C02 adds instructions and addressing modes
+    pub cpu_variant: CpuVariant,
+    /// Directories searched by `.include` and `.incbin`, after the directory of the file using them
+    pub include_paths: Vec<PathBuf>
+}
+
 pub enum NumericType {
     BINARY,
     HEXADECIMAL,
@@ -114,18 +215,18 @@ impl NumericType {
     /// detect what is its numeric base.
     pub fn detect_type_in_string(numeric_string: &str) -> (Self, &str) {
 
-        if numeric_string.starts_with("$") {
-            (NumericType::HEXADECIMAL, &numeric_string[1..])
-        } else if numeric_string.starts_with("0x") {
-            (NumericType::HEXADECIMAL, &numeric_string[2..])
-        } else if numeric_string.starts_with("%") {
-            (NumericType::BINARY, &numeric_string[1..])
-        } else if numeric_string.starts_with("0b") {
-            (NumericType::BINARY, &numeric_string[2..])
-        } else if numeric_string.starts_with("@") {
-            (NumericType::OCTAL, &numeric_string[1..])
-        } else if numeric_string.starts_with("0o") {
-            (NumericType::OCTAL, &numeric_string[2..])
+        if let Some(value) = numeric_string.strip_prefix('$') {
+            (NumericType::HEXADECIMAL, value)
+        } else if let Some(value) = numeric_string.strip_prefix("0x") {
+            (NumericType::HEXADECIMAL, value)
+        } else if let Some(value) = numeric_string.strip_prefix('%') {
+            (NumericType::BINARY, value)
+        } else if let Some(value) = numeric_string.strip_prefix("0b") {
+            (NumericType::BINARY, value)
+        } else if let Some(value) = numeric_string.strip_prefix('@') {
+            (NumericType::OCTAL, value)
+        } else if let Some(value) = numeric_string.strip_prefix("0o") {
+            (NumericType::OCTAL, value)
         } else {
             (NumericType::DECIMAL, numeric_string)
         }
@@ -0,0 +1,56 @@
+pub mod nes_bus;
+
+/// System bus as seen by the CPU
+///
+/// Every memory access done by the CPU goes through the bus, so implementations are free to
+/// map addresses to RAM, hardware registers (PPU, APU, controllers) or cartridge banks.
+/// Reading some hardware registers has side effects (e.g. clearing a flag), so `read` takes
+/// `&mut self`. Debuggers, tracers and tests should use `peek` instead, which must never change state.
+pub trait Bus {
+    fn read(&mut self, address: u16) -> u8;
+
+    fn write(&mut self, data: u8, address: u16);
+
+    /// Reads a byte without any side effect
+    fn peek(&self, address: u16) -> u8;
+
+    /// Uses Little Endian (LE) approach to retrieve 2 bytes from the bus as a value
+    fn read_u16(&mut self, address: u16) -> u16 {
+        let lsb = self.read(address) as u16;
+        let msb = self.read(address.wrapping_add(1)) as u16;
+
+        msb << 8 | lsb
+    }
+
+    /// Same as `read_u16`, but without any side effect
+    fn peek_u16(&self, address: u16) -> u16 {
+        let lsb = self.peek(address) as u16;
+        let msb = self.peek(address.wrapping_add(1)) as u16;
+
+        msb << 8 | lsb
+    }
+
+    /// Lets the devices connected to the bus (e.g. PPU) run for the cycles the CPU just consumed
+    fn tick(&mut self, _cycles: u16) {}
+
+    /// Cycles the CPU has to wait before executing the next instruction,
+    /// because a device took over the bus (e.g. OAM DMA). Once returned, they are forgotten
+    fn take_stall_cycles(&mut self) -> u16 {
+        0
+    }
+
+    /// Level of the IRQ line driven by the devices connected to the bus (e.g. cartridge mappers).
+    /// The CPU polls it before every instruction
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// Level of the NMI line driven by the devices connected to the bus (e.g. PPU).
+    /// NMI is edge triggered, so the CPU only handles it when the line becomes active
+    fn nmi(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,234 @@
+use super::Bus;
+use crate::apu::{self, Apu};
+use crate::cartridge::mapper::Mapper;
+use crate::input::{Controller, ControllerPort, DATA_LINES_MASK};
+use crate::ppu::{Ppu, OAM_SIZE};
+use crate::ppu::types::DOTS_PER_CPU_CYCLE;
+
+const RAM_SIZE: usize = 0x0800;
+const RAM_END: u16 = 0x1FFF;
+/// The 2 KiB of internal RAM are mirrored 4 times across $0000-$1FFF
+const RAM_MIRROR_MASK: u16 = 0x07FF;
+
+const PPU_REGISTERS_END: u16 = 0x3FFF;
+/// The 8 PPU registers are mirrored every 8 bytes across $2000-$3FFF
+const PPU_REGISTERS_MIRROR_MASK: u16 = 0x0007;
+
+const APU_IO_REGISTERS_START: u16 = 0x4000;
+const APU_IO_REGISTERS_END: u16 = 0x4017;
+const CONTROLLER_PORT_1: u16 = 0x4016;
+const CONTROLLER_PORT_2: u16 = 0x4017;
+/// The upper bits of controller reads are open bus, usually $40 from the high byte of the address
+const CONTROLLER_OPEN_BUS: u8 = 0x40;
+
+/// Writing $XX to this register copies the page $XX00-$XXFF to the PPU OAM
+const OAM_DMA: u16 = 0x4014;
+/// 1 dummy cycle, then 256 reads and 256 writes.
+/// An extra alignment cycle is needed when the transfer starts on an odd CPU cycle
+const OAM_DMA_CYCLES: u16 = 513;
+/// The CPU is stalled for up to 4 cycles while the DMC fetches a sample byte
+const DMC_FETCH_CYCLES: u16 = 4;
+
+/// $4018-$401F is normally disabled APU/IO test functionality
+const TEST_MODE_END: u16 = 0x401F;
+
+const CARTRIDGE_START: u16 = 0x4020;
+
+/// NES CPU memory map
+///
+/// | Address range | Size   | Device                                  |
+/// |---------------|--------|-----------------------------------------|
+/// | $0000-$07FF   | $0800  | 2 KiB internal RAM                      |
+/// | $0800-$1FFF   | $1800  | Mirrors of $0000-$07FF                  |
+/// | $2000-$2007   | $0008  | PPU registers                           |
+/// | $2008-$3FFF   | $1FF8  | Mirrors of $2000-$2007 (every 8 bytes)  |
+/// | $4000-$4017   | $0018  | APU, OAM DMA and controller registers   |
+/// | $4018-$401F   | $0008  | APU and I/O test mode (disabled)        |
+/// | $4020-$FFFF   | $BFE0  | Cartridge space: PRG ROM, PRG RAM, mappers |
+///
+/// see: [CPU memory map](https://www.nesdev.org/wiki/CPU_memory_map)
+pub struct NesBus {
+    ram: [u8; RAM_SIZE],
+    ppu: Ppu,
+    apu: Apu,
+    cartridge: Option<Box<dyn Mapper>>,
+    controllers: [Option<Box<dyn Controller>>; 2],
+    /// CPU cycles elapsed since power on
+    cycles: u64,
+    oam_dma_requested: bool,
+    stall_cycles: u16,
+}
+
+impl Default for NesBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NesBus {
+    pub fn new() -> Self {
+        Self {
+            ram: [0; RAM_SIZE],
+            ppu: Ppu::new(),
+            apu: Apu::new(),
+            cartridge: None,
+            controllers: [None, None],
+            cycles: 0,
+            oam_dma_requested: false,
+            stall_cycles: 0,
+        }
+    }
+
+    /// Plugs a cartridge into the slot, replacing the previous one.
+    /// Without a cartridge, reads from the cartridge space return 0
+    pub fn insert_cartridge(&mut self, mapper: Box<dyn Mapper>) {
+        self.cartridge = Some(mapper);
+    }
+
+    pub fn cartridge(&self) -> Option<&dyn Mapper> {
+        self.cartridge.as_deref()
+    }
+
+    pub fn cartridge_mut(&mut self) -> Option<&mut (dyn Mapper + 'static)> {
+        self.cartridge.as_deref_mut()
+    }
+
+    /// Plugs a device into a controller port, replacing the previous one.
+    /// Without a device, the data lines of the port read as 0
+    pub fn connect_controller(&mut self, port: ControllerPort, controller: Box<dyn Controller>) {
+        self.controllers[port.index()] = Some(controller);
+    }
+
+    pub fn disconnect_controller(&mut self, port: ControllerPort) -> Option<Box<dyn Controller>> {
+        self.controllers[port.index()].take()
+    }
+
+    pub fn controller_mut(&mut self, port: ControllerPort) -> Option<&mut (dyn Controller + 'static)> {
+        self.controllers[port.index()].as_deref_mut()
+    }
+
+    /// Device plugged into the port, if it is a `T` (e.g. to press the buttons of a `Joypad`)
+    pub fn controller_as_mut<T: 'static>(&mut self, port: ControllerPort) -> Option<&mut T> {
+        self.controller_mut(port).and_then(|controller| controller.as_any_mut().downcast_mut::<T>())
+    }
+
+    pub fn ppu(&self) -> &Ppu {
+        &self.ppu
+    }
+
+    pub fn ppu_mut(&mut self) -> &mut Ppu {
+        &mut self.ppu
+    }
+
+    pub fn apu(&self) -> &Apu {
+        &self.apu
+    }
+
+    pub fn apu_mut(&mut self) -> &mut Apu {
+        &mut self.apu
+    }
+
+    /// Copies the page $XX00-$XXFF to OAM, starting at the current OAMADDR.
+    /// The CPU is suspended until the transfer is over
+    fn oam_dma(&mut self, page: u8) {
+        let start = (page as u16) << 8;
+        for offset in 0..OAM_SIZE as u16 {
+            let data = self.read(start + offset);
+            self.ppu.write_oam_data(data);
+        }
+        self.oam_dma_requested = true;
+    }
+}
+
+impl Bus for NesBus {
+    fn read(&mut self, address: u16) -> u8 {
+        match address {
+            0x2000..=PPU_REGISTERS_END => {
+                self.ppu.read_register(address & PPU_REGISTERS_MIRROR_MASK, inserted_cartridge(&mut self.cartridge))
+            },
+            apu::STATUS => self.apu.read_status(),
+            CONTROLLER_PORT_1 | CONTROLLER_PORT_2 => {
+                let data = self.controllers[controller_index(address)].as_mut().map_or(0, |controller| controller.read(&self.ppu));
+                CONTROLLER_OPEN_BUS | (data & DATA_LINES_MASK)
+            },
+            _ => self.peek(address)
+        }
+    }
+
+    fn write(&mut self, data: u8, address: u16) {
+        match address {
+            0x0000..=RAM_END => self.ram[(address & RAM_MIRROR_MASK) as usize] = data,
+            0x2000..=PPU_REGISTERS_END => {
+                self.ppu.write_register(data, address & PPU_REGISTERS_MIRROR_MASK, inserted_cartridge(&mut self.cartridge))
+            },
+            OAM_DMA => self.oam_dma(data),
+            // The strobe line is shared by both ports
+            CONTROLLER_PORT_1 => self.controllers.iter_mut().flatten().for_each(|controller| controller.write(data)),
+            APU_IO_REGISTERS_START..=APU_IO_REGISTERS_END => self.apu.write_register(data, address),
+            0x4018..=TEST_MODE_END => (),
+            CARTRIDGE_START..=0xFFFF => {
+                if let Some(cartridge) = self.cartridge.as_mut() {
+                    cartridge.cpu_write(data, address);
+                }
+            },
+        }
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        match address {
+            0x0000..=RAM_END => self.ram[(address & RAM_MIRROR_MASK) as usize],
+            0x2000..=PPU_REGISTERS_END => self.ppu.peek_register(address & PPU_REGISTERS_MIRROR_MASK, self.cartridge.as_deref()),
+            apu::STATUS => self.apu.peek_status(),
+            CONTROLLER_PORT_1 | CONTROLLER_PORT_2 => {
+                let data = self.controllers[controller_index(address)].as_ref().map_or(0, |controller| controller.peek(&self.ppu));
+                CONTROLLER_OPEN_BUS | (data & DATA_LINES_MASK)
+            },
+            // APU registers are write only
+            APU_IO_REGISTERS_START..=APU_IO_REGISTERS_END => 0,
+            0x4018..=TEST_MODE_END => 0,
+            CARTRIDGE_START..=0xFFFF => self.cartridge.as_ref().map_or(0, |cartridge| cartridge.cpu_read(address)),
+        }
+    }
+
+    fn tick(&mut self, cycles: u16) {
+        for _ in 0..cycles {
+            self.cycles += 1;
+            self.apu.tick();
+            if let Some(address) = self.apu.dmc_fetch_address() {
+                let data = self.read(address);
+                self.apu.dmc_fill_sample_buffer(data);
+                self.stall_cycles += DMC_FETCH_CYCLES;
+            }
+
+            for _ in 0..DOTS_PER_CPU_CYCLE {
+                self.ppu.tick(inserted_cartridge(&mut self.cartridge));
+            }
+        }
+    }
+
+    fn take_stall_cycles(&mut self) -> u16 {
+        let mut stall_cycles = std::mem::take(&mut self.stall_cycles);
+        if self.oam_dma_requested {
+            self.oam_dma_requested = false;
+            stall_cycles += OAM_DMA_CYCLES + (self.cycles & 1) as u16;
+        }
+        stall_cycles
+    }
+
+    fn irq(&self) -> bool {
+        self.apu.irq() || self.cartridge.as_ref().is_some_and(|cartridge| cartridge.irq())
+    }
+
+    fn nmi(&self) -> bool {
+        self.ppu.nmi()
+    }
+}
+
+fn controller_index(address: u16) -> usize {
+    (address - CONTROLLER_PORT_1) as usize
+}
+
+/// Borrows the cartridge for the PPU, which only needs it for the duration of the call
+fn inserted_cartridge(cartridge: &mut Option<Box<dyn Mapper>>) -> Option<&mut dyn Mapper> {
+    cartridge.as_mut().map(|cartridge| cartridge.as_mut() as &mut dyn Mapper)
+}
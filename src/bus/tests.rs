@@ -0,0 +1,233 @@
+use super::*;
+use super::nes_bus::NesBus;
+
+use crate::cartridge::Cartridge;
+use crate::cartridge::mapper;
+use crate::cpu::Cpu;
+
+/// Builds a bus with a 32 KiB NROM cartridge holding the program at $8000
+fn nrom_bus(program: &[u8], nmi_vector: u16, reset_vector: u16) -> NesBus {
+    let mut rom = vec![0x4E, 0x45, 0x53, 0x1A, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut prg_rom = vec![0; 0x8000];
+    prg_rom[..program.len()].copy_from_slice(program);
+    prg_rom[0x7FFA] = nmi_vector as u8;
+    prg_rom[0x7FFB] = (nmi_vector >> 8) as u8;
+    prg_rom[0x7FFC] = reset_vector as u8;
+    prg_rom[0x7FFD] = (reset_vector >> 8) as u8;
+    rom.extend(prg_rom);
+    rom.extend(vec![0; 0x2000]);
+
+    let mut bus = NesBus::new();
+    bus.insert_cartridge(mapper::new_mapper(Cartridge::from_bytes(&rom).unwrap()).unwrap());
+    bus
+}
+
+#[test]
+fn nes_bus_ram_is_mirrored() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    bus.write(0x69, 0x0042);
+
+    // Then
+    assert_eq!(0x69, bus.read(0x0842));
+    assert_eq!(0x69, bus.read(0x1042));
+    assert_eq!(0x69, bus.read(0x1842));
+}
+
+#[test]
+fn nes_bus_write_to_ram_mirror_updates_internal_ram() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    bus.write(0x96, 0x1FFF);
+
+    // Then
+    assert_eq!(0x96, bus.read(0x07FF));
+}
+
+#[test]
+fn nes_bus_ppu_registers_are_mirrored_every_8_bytes() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    // PPUADDR = $3F00, then PPUDATA through different mirrors
+    bus.write(0x3F, 0x3FFE);
+    bus.write(0x00, 0x200E);
+    bus.write(0x2A, 0x2FFF);
+    bus.write(0x3F, 0x2006);
+    bus.write(0x00, 0x2006);
+
+    // Then
+    assert_eq!(0x2A, bus.read(0x3FFF));
+}
+
+#[test]
+fn nes_bus_apu_registers_are_not_mirrored() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    // Enable pulse 1 and load its length counter
+    bus.write(0x01, 0x4015);
+    bus.write(0x08, 0x4003);
+    bus.write(0x08, 0x4023);
+
+    // Then
+    assert_eq!(0x01, bus.peek(0x4015));
+    assert_eq!(0x00, bus.peek(0x4035));
+}
+
+#[test]
+fn nes_bus_test_mode_registers_are_ignored() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    bus.write(0xFF, 0x4018);
+
+    // Then
+    assert_eq!(0x00, bus.peek(0x4018));
+}
+
+#[test]
+fn nes_bus_forwards_upper_addresses_to_cartridge() {
+    // Given
+    let mut bus = nrom_bus(&[0x42], 0x8000, 0x8000);
+
+    // When
+    bus.write(0x69, 0x6000);
+
+    // Then
+    assert_eq!(0x8000, bus.peek_u16(0xFFFC));
+    assert_eq!(0x42, bus.peek(0x8000));
+    assert_eq!(0x69, bus.peek(0x6000));
+}
+
+#[test]
+fn nes_bus_without_cartridge_reads_open_bus() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    bus.write(0x42, 0x8000);
+
+    // Then
+    assert!(bus.cartridge().is_none());
+    assert_eq!(0x00, bus.peek(0x8000));
+}
+
+#[test]
+fn cpu_sees_mirrored_ram_through_nes_bus() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $8000    a9 42     LDA #$42
+    // $8002    85 10     STA $10
+    // $8004    ae 10 08  LDX $0810
+    // $8007    ac 10 18  LDY $1810
+    let bus = nrom_bus(&[0xA9, 0x42, 0x85, 0x10, 0xAE, 0x10, 0x08, 0xAC, 0x10, 0x18], 0x8000, 0x8000);
+    let mut cpu = Cpu::new_with_bus(bus);
+    cpu.reset();
+
+    for _ in 0..4 {
+        cpu.step().unwrap();
+    }
+
+    assert_eq!(0x42, cpu.bus().peek(0x0010));
+    assert_eq!(0x42, cpu.bus().peek(0x1010));
+}
+
+#[test]
+fn ppu_vblank_triggers_cpu_nmi() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $8000    a9 80     LDA #$80
+    // $8002    8d 00 20  STA $2000
+    // $8005    4c 05 80  JMP $8005
+    // NMI handler
+    // $8008    e6 10     INC $10
+    // $800a    40        RTI
+    let bus = nrom_bus(&[0xA9, 0x80, 0x8D, 0x00, 0x20, 0x4C, 0x05, 0x80, 0xE6, 0x10, 0x40], 0x8008, 0x8000);
+    let mut cpu = Cpu::new_with_bus(bus);
+    cpu.reset();
+
+    // When
+    while cpu.bus().ppu().frame() < 3 {
+        cpu.step().unwrap();
+    }
+
+    // Then
+    // 1 NMI per frame
+    assert_eq!(3, cpu.bus().peek(0x0010));
+}
+
+#[test]
+fn run_frame_runs_cpu_until_next_ppu_frame() {
+    // Given
+    // $8000    4c 00 80  JMP $8000
+    let bus = nrom_bus(&[0x4C, 0x00, 0x80], 0x8000, 0x8000);
+    let mut cpu = Cpu::new_with_bus(bus);
+    cpu.reset();
+
+    // When
+    let first_frame = cpu.run_frame();
+    let second_frame = cpu.run_frame();
+
+    // Then
+    // 341 x 262 dots per frame, 3 dots per CPU cycle: ~29780.7 cycles
+    assert_eq!(2, cpu.bus().ppu().frame());
+    assert!(first_frame.unwrap() < 29780);
+    assert!((29780..=29782).contains(&second_frame.unwrap()));
+}
+
+#[test]
+fn oam_dma_copies_page_to_oam_and_stalls_cpu() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $8000    a9 02     LDA #$02
+    // $8002    8d 14 40  STA $4014
+    // $8005    a5 00     LDA $00
+    // $8007    8d 14 40  STA $4014
+    let bus = nrom_bus(&[0xA9, 0x02, 0x8D, 0x14, 0x40, 0xA5, 0x00, 0x8D, 0x14, 0x40], 0x8000, 0x8000);
+    let mut cpu = Cpu::new_with_bus(bus);
+    for i in 0..=255 {
+        cpu.bus_mut().write(i, 0x0200 + i as u16);
+    }
+    cpu.bus_mut().write(0x02, 0x0000);
+    cpu.reset();
+    cpu.step().unwrap();
+
+    // When / Then
+    // 7 (RESET) + 2 (LDA) + 4 (STA): the transfer starts on an odd cycle
+    assert_eq!(Ok(4 + 514), cpu.step().map(|step| step.cycles));
+    cpu.step().unwrap();
+    assert_eq!(Ok(4 + 513), cpu.step().map(|step| step.cycles));
+
+    cpu.bus_mut().write(0x05, 0x2003);
+    assert_eq!(0x05, cpu.bus().peek(0x2004));
+    cpu.bus_mut().write(0xFF, 0x2003);
+    assert_eq!(0xFF, cpu.bus().peek(0x2004));
+}
+
+#[test]
+fn dmc_fetches_samples_through_bus_and_stalls_cpu() {
+    // Given
+    let mut bus = nrom_bus(&[], 0x8000, 0x8000);
+    // IRQ enabled, sample of 1 byte at $C000
+    bus.write(0b1000_0000, 0x4010);
+    bus.write(0x00, 0x4012);
+    bus.write(0x00, 0x4013);
+
+    // When
+    bus.write(0b0001_0000, 0x4015);
+    bus.tick(1);
+
+    // Then
+    assert_eq!(4, bus.take_stall_cycles());
+    assert_eq!(0, bus.take_stall_cycles());
+    assert!(bus.irq());
+    assert_eq!(0b1000_0000, bus.peek(0x4015));
+}
@@ -0,0 +1,54 @@
+use super::{CartridgeMemory, Mapper, PRG_RAM_END, PRG_RAM_START, PRG_ROM_START};
+use crate::cartridge::Cartridge;
+use crate::cartridge::types::Mirroring;
+
+const PRG_BANK_SIZE: usize = 0x8000;
+const CHR_BANK_SIZE: usize = 0x2000;
+
+/// Mapper 3 (CNROM): PRG ROM is fixed like NROM,
+/// 8 KiB CHR bank is selected by writing to $8000-$FFFF.
+///
+/// see: [CNROM](https://www.nesdev.org/wiki/CNROM)
+pub struct Cnrom {
+    memory: CartridgeMemory,
+    chr_bank: usize
+}
+
+impl Cnrom {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Self {
+            memory: CartridgeMemory::new(cartridge),
+            chr_bank: 0
+        }
+    }
+}
+
+impl Mapper for Cnrom {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END => self.memory.read_prg_ram(address),
+            PRG_ROM_START..=0xFFFF => self.memory.read_prg_rom(PRG_BANK_SIZE, 0, address - PRG_ROM_START),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, data: u8, address: u16) {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END => self.memory.write_prg_ram(data, address),
+            PRG_ROM_START..=0xFFFF => self.chr_bank = data as usize,
+            _ => ()
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        self.memory.read_chr(CHR_BANK_SIZE, self.chr_bank, address)
+    }
+
+    fn ppu_write(&mut self, data: u8, address: u16) {
+        self.memory.write_chr(data, CHR_BANK_SIZE, self.chr_bank, address);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.memory.mirroring()
+    }
+}
@@ -0,0 +1,138 @@
+use super::{CartridgeMemory, Mapper, PRG_RAM_END, PRG_RAM_START, PRG_ROM_START};
+use crate::cartridge::Cartridge;
+use crate::cartridge::types::Mirroring;
+
+const PRG_BANK_SIZE: usize = 0x4000;
+const CHR_BANK_SIZE: usize = 0x1000;
+
+const SHIFT_RESET: u8 = 0b1000_0000;
+const SHIFT_REGISTER_INITIAL: u8 = 0b1_0000;
+const CONTROL_INITIAL: u8 = 0b0_1100;
+const PRG_RAM_DISABLE: u8 = 0b1_0000;
+
+/// Mapper 1 (MMC1): registers are loaded serially, 1 bit per write (LSB first) to $8000-$FFFF.
+/// On the fifth write, the value is copied to the register selected by address bits 13-14:
+///
+/// | Address     | Register                                                      |
+/// |-------------|---------------------------------------------------------------|
+/// | $8000-$9FFF | Control: mirroring (bits 0-1), PRG mode (2-3), CHR mode (4)   |
+/// | $A000-$BFFF | CHR bank 0                                                    |
+/// | $C000-$DFFF | CHR bank 1                                                    |
+/// | $E000-$FFFF | PRG bank (bits 0-3), PRG RAM disable (bit 4)                  |
+///
+/// Writing a value with bit 7 set clears the shift register and locks the PRG mode
+/// with the last bank fixed at $C000.
+///
+/// see: [MMC1](https://www.nesdev.org/wiki/MMC1)
+pub struct Mmc1 {
+    memory: CartridgeMemory,
+    shift_register: u8,
+    control: u8,
+    chr_bank_0: u8,
+    chr_bank_1: u8,
+    prg_bank: u8
+}
+
+impl Mmc1 {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Self {
+            memory: CartridgeMemory::new(cartridge),
+            shift_register: SHIFT_REGISTER_INITIAL,
+            control: CONTROL_INITIAL,
+            chr_bank_0: 0,
+            chr_bank_1: 0,
+            prg_bank: 0
+        }
+    }
+
+    fn write_register(&mut self, data: u8, address: u16) {
+        if data & SHIFT_RESET != 0 {
+            self.shift_register = SHIFT_REGISTER_INITIAL;
+            self.control |= CONTROL_INITIAL;
+            return;
+        }
+
+        // The initial 1 reaching bit 0 means this is the fifth write
+        let is_last_write = self.shift_register & 1 == 1;
+        self.shift_register = (self.shift_register >> 1) | ((data & 1) << 4);
+
+        if is_last_write {
+            let value = self.shift_register;
+            match address {
+                0x8000..=0x9FFF => self.control = value,
+                0xA000..=0xBFFF => self.chr_bank_0 = value,
+                0xC000..=0xDFFF => self.chr_bank_1 = value,
+                _ => self.prg_bank = value
+            }
+            self.shift_register = SHIFT_REGISTER_INITIAL;
+        }
+    }
+
+    fn prg_ram_enabled(&self) -> bool {
+        self.prg_bank & PRG_RAM_DISABLE == 0
+    }
+
+    /// Returns the 16 KiB bank mapped at the given CPU address
+    fn prg_bank_at(&self, address: u16) -> usize {
+        let bank = (self.prg_bank & 0x0F) as usize;
+        let is_upper_half = address >= 0xC000;
+
+        match (self.control >> 2) & 0b11 {
+            // 32 KiB mode: ignores the low bit of the bank number
+            0 | 1 => (bank & !1) | is_upper_half as usize,
+            // First bank fixed at $8000, switchable bank at $C000
+            2 => if is_upper_half { bank } else { 0 },
+            // Switchable bank at $8000, last bank fixed at $C000
+            _ => if is_upper_half { self.memory.prg_bank_count(PRG_BANK_SIZE) - 1 } else { bank }
+        }
+    }
+
+    /// Returns the 4 KiB bank mapped at the given PPU address
+    fn chr_bank_at(&self, address: u16) -> usize {
+        let is_upper_half = address >= 0x1000;
+
+        // 8 KiB mode: ignores the low bit of CHR bank 0
+        if self.control & 0b1_0000 == 0 {
+            (self.chr_bank_0 as usize & !1) | is_upper_half as usize
+        } else if is_upper_half {
+            self.chr_bank_1 as usize
+        } else {
+            self.chr_bank_0 as usize
+        }
+    }
+}
+
+impl Mapper for Mmc1 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END if self.prg_ram_enabled() => self.memory.read_prg_ram(address),
+            PRG_ROM_START..=0xFFFF => self.memory.read_prg_rom(PRG_BANK_SIZE, self.prg_bank_at(address), address),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, data: u8, address: u16) {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END if self.prg_ram_enabled() => self.memory.write_prg_ram(data, address),
+            PRG_ROM_START..=0xFFFF => self.write_register(data, address),
+            _ => ()
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        self.memory.read_chr(CHR_BANK_SIZE, self.chr_bank_at(address), address)
+    }
+
+    fn ppu_write(&mut self, data: u8, address: u16) {
+        self.memory.write_chr(data, CHR_BANK_SIZE, self.chr_bank_at(address), address);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        match self.control & 0b11 {
+            0 => Mirroring::SingleScreenLower,
+            1 => Mirroring::SingleScreenUpper,
+            2 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use super::{CartridgeMemory, Mapper, PRG_RAM_END, PRG_RAM_START, PRG_ROM_START};
+use crate::cartridge::Cartridge;
+use crate::cartridge::types::Mirroring;
+
+const PRG_BANK_SIZE: usize = 0x2000;
+const CHR_BANK_SIZE: usize = 0x0400;
+
+const PRG_ROM_MODE: u8 = 0b0100_0000;
+const CHR_INVERSION: u8 = 0b1000_0000;
+const PRG_RAM_ENABLE: u8 = 0b1000_0000;
+const PRG_RAM_WRITE_PROTECT: u8 = 0b0100_0000;
+
+/// PPU A12 (pattern table $1000-$1FFF) selects the upper pattern table
+const PPU_A12: u16 = 0x1000;
+/// Amount of consecutive PPU accesses with A12 low required before a rise clocks the counter.
+/// It filters the rises seen between sprite pattern fetches of the same scanline,
+/// which are separated by 2 nametable fetches only
+const A12_LOW_FILTER: u8 = 3;
+
+/// Mapper 4 (MMC3): 8 KiB PRG banks, 1 KiB/2 KiB CHR banks and a scanline counter.
+///
+/// Registers are selected by address range and parity:
+///
+/// | Address     | Even                  | Odd                    |
+/// |-------------|-----------------------|------------------------|
+/// | $8000-$9FFF | Bank select           | Bank data              |
+/// | $A000-$BFFF | Mirroring             | PRG RAM protect        |
+/// | $C000-$DFFF | IRQ latch             | IRQ reload             |
+/// | $E000-$FFFF | IRQ disable           | IRQ enable             |
+///
+/// The scanline counter is clocked by rises of PPU A12, which happen once per scanline when
+/// background and sprites use different pattern tables. When it reaches 0 with IRQ enabled,
+/// the cartridge asserts the CPU IRQ line until IRQ disable is written.
+///
+/// see: [MMC3](https://www.nesdev.org/wiki/MMC3)
+pub struct Mmc3 {
+    memory: CartridgeMemory,
+    bank_select: u8,
+    /// R0-R7 bank registers
+    bank_registers: [u8; 8],
+    mirroring: Mirroring,
+    prg_ram_protect: u8,
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq_pending: bool,
+    a12_low_count: u8
+}
+
+impl Mmc3 {
+    pub fn new(cartridge: Cartridge) -> Self {
+        let memory = CartridgeMemory::new(cartridge);
+        let mirroring = memory.mirroring();
+        Self {
+            memory,
+            bank_select: 0,
+            bank_registers: [0; 8],
+            mirroring,
+            prg_ram_protect: 0,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq_pending: false,
+            a12_low_count: 0
+        }
+    }
+
+    fn write_register(&mut self, data: u8, address: u16) {
+        let is_even = address & 1 == 0;
+        match (address, is_even) {
+            (0x8000..=0x9FFF, true) => self.bank_select = data,
+            (0x8000..=0x9FFF, false) => self.bank_registers[(self.bank_select & 0b111) as usize] = data,
+            (0xA000..=0xBFFF, true) => {
+                // Hardwired four screen boards ignore the mirroring register
+                if self.mirroring != Mirroring::FourScreen {
+                    self.mirroring = if data & 1 == 0 { Mirroring::Vertical } else { Mirroring::Horizontal };
+                }
+            },
+            (0xA000..=0xBFFF, false) => self.prg_ram_protect = data,
+            (0xC000..=0xDFFF, true) => self.irq_latch = data,
+            (0xC000..=0xDFFF, false) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            },
+            (_, true) => {
+                self.irq_enabled = false;
+                self.irq_pending = false;
+            },
+            (_, false) => self.irq_enabled = true
+        }
+    }
+
+    /// Returns the 8 KiB bank mapped at the given CPU address
+    fn prg_bank_at(&self, address: u16) -> usize {
+        let last = self.memory.prg_bank_count(PRG_BANK_SIZE) - 1;
+        let second_last = last.saturating_sub(1);
+        let swap_8000_c000 = self.bank_select & PRG_ROM_MODE != 0;
+
+        match address {
+            0x8000..=0x9FFF if swap_8000_c000 => second_last,
+            0x8000..=0x9FFF => self.bank_registers[6] as usize,
+            0xA000..=0xBFFF => self.bank_registers[7] as usize,
+            0xC000..=0xDFFF if swap_8000_c000 => self.bank_registers[6] as usize,
+            0xC000..=0xDFFF => second_last,
+            _ => last
+        }
+    }
+
+    /// Returns the 1 KiB bank mapped at the given PPU address
+    fn chr_bank_at(&self, address: u16) -> usize {
+        // With CHR inversion, 2 KiB banks are mapped at $1000-$1FFF instead of $0000-$0FFF
+        let address = if self.bank_select & CHR_INVERSION != 0 { address ^ PPU_A12 } else { address };
+        let slot = (address as usize & 0x1FFF) / CHR_BANK_SIZE;
+
+        match slot {
+            // 2 KiB banks ignore the low bit of R0 and R1
+            0..=3 => (self.bank_registers[slot / 2] as usize & !1) | (slot & 1),
+            _ => self.bank_registers[slot - 2] as usize
+        }
+    }
+
+    fn prg_ram_readable(&self) -> bool {
+        self.prg_ram_protect & PRG_RAM_ENABLE != 0
+    }
+
+    fn prg_ram_writable(&self) -> bool {
+        self.prg_ram_readable() && self.prg_ram_protect & PRG_RAM_WRITE_PROTECT == 0
+    }
+
+    fn clock_irq_counter(&mut self) {
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+
+        if self.irq_counter == 0 && self.irq_enabled {
+            self.irq_pending = true;
+        }
+    }
+}
+
+impl Mapper for Mmc3 {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END if self.prg_ram_readable() => self.memory.read_prg_ram(address),
+            PRG_ROM_START..=0xFFFF => self.memory.read_prg_rom(PRG_BANK_SIZE, self.prg_bank_at(address), address),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, data: u8, address: u16) {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END if self.prg_ram_writable() => self.memory.write_prg_ram(data, address),
+            PRG_ROM_START..=0xFFFF => self.write_register(data, address),
+            _ => ()
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        self.memory.read_chr(CHR_BANK_SIZE, self.chr_bank_at(address), address)
+    }
+
+    fn ppu_write(&mut self, data: u8, address: u16) {
+        self.memory.write_chr(data, CHR_BANK_SIZE, self.chr_bank_at(address), address);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn notify_ppu_address(&mut self, address: u16) {
+        if address & PPU_A12 == 0 {
+            self.a12_low_count = self.a12_low_count.saturating_add(1);
+        } else {
+            if self.a12_low_count >= A12_LOW_FILTER {
+                self.clock_irq_counter();
+            }
+            self.a12_low_count = 0;
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.irq_pending
+    }
+}
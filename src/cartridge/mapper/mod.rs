@@ -0,0 +1,132 @@
+pub mod cnrom;
+pub mod mmc1;
+pub mod mmc3;
+pub mod nrom;
+pub mod uxrom;
+
+use super::Cartridge;
+use super::types::{CartridgeError, Mirroring};
+
+pub const PRG_RAM_START: u16 = 0x6000;
+pub const PRG_RAM_END: u16 = 0x7FFF;
+pub const PRG_ROM_START: u16 = 0x8000;
+
+/// Cartridge board logic: translates CPU and PPU addresses into PRG/CHR memory offsets
+/// and handles writes to bank switching registers.
+///
+/// The CPU sees the cartridge at $4020-$FFFF and the PPU sees the pattern tables at $0000-$1FFF.
+///
+/// see: [Mapper](https://www.nesdev.org/wiki/Mapper)
+pub trait Mapper {
+    fn cpu_read(&self, address: u16) -> u8;
+
+    fn cpu_write(&mut self, data: u8, address: u16);
+
+    fn ppu_read(&self, address: u16) -> u8;
+
+    fn ppu_write(&mut self, data: u8, address: u16);
+
+    /// Nametable mirroring. Some mappers can change it at runtime
+    fn mirroring(&self) -> Mirroring;
+
+    /// Called by the PPU for every address it puts on its bus.
+    /// Mappers like MMC3 watch these addresses to count scanlines
+    fn notify_ppu_address(&mut self, _address: u16) {}
+
+    /// Level of the IRQ line driven by the cartridge
+    fn irq(&self) -> bool {
+        false
+    }
+}
+
+/// Creates the mapper declared in the cartridge header
+pub fn new_mapper(cartridge: Cartridge) -> Result<Box<dyn Mapper>, CartridgeError> {
+    match cartridge.header.mapper {
+        0 => Ok(Box::new(nrom::Nrom::new(cartridge))),
+        1 => Ok(Box::new(mmc1::Mmc1::new(cartridge))),
+        2 => Ok(Box::new(uxrom::Uxrom::new(cartridge))),
+        3 => Ok(Box::new(cnrom::Cnrom::new(cartridge))),
+        4 => Ok(Box::new(mmc3::Mmc3::new(cartridge))),
+        mapper => Err(CartridgeError::UnsupportedMapper(mapper))
+    }
+}
+
+/// PRG ROM, CHR ROM (or RAM) and PRG RAM found in cartridge boards.
+/// Banks are selected by index and wrap around the memory size,
+/// as boards usually ignore the upper bits of bank numbers.
+pub(crate) struct CartridgeMemory {
+    prg_rom: Vec<u8>,
+    chr: Vec<u8>,
+    chr_is_ram: bool,
+    prg_ram: Vec<u8>,
+    mirroring: Mirroring
+}
+
+impl CartridgeMemory {
+    pub(crate) fn new(cartridge: Cartridge) -> Self {
+        let header = cartridge.header;
+        let chr_is_ram = cartridge.chr_rom.is_empty();
+        let chr = if chr_is_ram {
+            vec![0; (header.chr_ram_size + header.chr_nvram_size).max(super::CHR_ROM_BANK_SIZE)]
+        } else {
+            cartridge.chr_rom
+        };
+
+        Self {
+            prg_rom: cartridge.prg_rom,
+            chr,
+            chr_is_ram,
+            prg_ram: vec![0; header.prg_ram_size + header.prg_nvram_size],
+            mirroring: header.mirroring
+        }
+    }
+
+    pub(crate) fn prg_bank_count(&self, bank_size: usize) -> usize {
+        (self.prg_rom.len() / bank_size).max(1)
+    }
+
+    pub(crate) fn read_prg_rom(&self, bank_size: usize, bank: usize, offset: u16) -> u8 {
+        let bank = bank % self.prg_bank_count(bank_size);
+        self.prg_rom[(bank * bank_size + offset as usize % bank_size) % self.prg_rom.len()]
+    }
+
+    pub(crate) fn read_chr(&self, bank_size: usize, bank: usize, offset: u16) -> u8 {
+        self.chr[self.chr_index(bank_size, bank, offset)]
+    }
+
+    /// Writes are ignored when the cartridge has CHR ROM
+    pub(crate) fn write_chr(&mut self, data: u8, bank_size: usize, bank: usize, offset: u16) {
+        if self.chr_is_ram {
+            let index = self.chr_index(bank_size, bank, offset);
+            self.chr[index] = data;
+        }
+    }
+
+    fn chr_index(&self, bank_size: usize, bank: usize, offset: u16) -> usize {
+        let bank_count = (self.chr.len() / bank_size).max(1);
+        ((bank % bank_count) * bank_size + offset as usize % bank_size) % self.chr.len()
+    }
+
+    /// Reads PRG RAM ($6000-$7FFF). Returns 0 (open bus) if the cartridge doesn't have it
+    pub(crate) fn read_prg_ram(&self, address: u16) -> u8 {
+        if self.prg_ram.is_empty() {
+            return 0;
+        }
+        self.prg_ram[(address - PRG_RAM_START) as usize % self.prg_ram.len()]
+    }
+
+    pub(crate) fn write_prg_ram(&mut self, data: u8, address: u16) {
+        if !self.prg_ram.is_empty() {
+            let index = (address - PRG_RAM_START) as usize % self.prg_ram.len();
+            self.prg_ram[index] = data;
+        }
+    }
+
+    /// Mirroring hardwired on the board (defined by the header)
+    pub(crate) fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+}
+
+#[cfg(test)]
+mod tests;
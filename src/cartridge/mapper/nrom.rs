@@ -0,0 +1,50 @@
+use super::{CartridgeMemory, Mapper, PRG_RAM_END, PRG_RAM_START, PRG_ROM_START};
+use crate::cartridge::Cartridge;
+use crate::cartridge::types::Mirroring;
+
+const PRG_BANK_SIZE: usize = 0x8000;
+const CHR_BANK_SIZE: usize = 0x2000;
+
+/// Mapper 0 (NROM): no bank switching.
+/// 16 KiB PRG ROM is mirrored at $8000 and $C000; 32 KiB PRG ROM fills $8000-$FFFF.
+///
+/// see: [NROM](https://www.nesdev.org/wiki/NROM)
+pub struct Nrom {
+    memory: CartridgeMemory
+}
+
+impl Nrom {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Self {
+            memory: CartridgeMemory::new(cartridge)
+        }
+    }
+}
+
+impl Mapper for Nrom {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END => self.memory.read_prg_ram(address),
+            PRG_ROM_START..=0xFFFF => self.memory.read_prg_rom(PRG_BANK_SIZE, 0, address - PRG_ROM_START),
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, data: u8, address: u16) {
+        if let PRG_RAM_START..=PRG_RAM_END = address {
+            self.memory.write_prg_ram(data, address);
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        self.memory.read_chr(CHR_BANK_SIZE, 0, address)
+    }
+
+    fn ppu_write(&mut self, data: u8, address: u16) {
+        self.memory.write_chr(data, CHR_BANK_SIZE, 0, address);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.memory.mirroring()
+    }
+}
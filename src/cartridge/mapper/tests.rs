@@ -0,0 +1,505 @@
+use super::*;
+use crate::bus::Bus;
+use crate::bus::nes_bus::NesBus;
+use crate::cartridge::{CHR_ROM_BANK_SIZE, PRG_ROM_BANK_SIZE};
+use crate::cpu::Cpu;
+
+/// Builds a cartridge where every 16 KiB PRG bank is filled with its bank number
+/// and every 1 KiB CHR bank is filled with its bank number
+fn cartridge(mapper: u8, prg_banks: u8, chr_banks: u8) -> Cartridge {
+    let mut rom = vec![0x4E, 0x45, 0x53, 0x1A, prg_banks, chr_banks, (mapper & 0x0F) << 4, mapper & 0xF0, 0, 0, 0, 0, 0, 0, 0, 0];
+    for bank in 0..prg_banks {
+        rom.extend(vec![bank; PRG_ROM_BANK_SIZE]);
+    }
+    for bank in 0..chr_banks as usize * 8 {
+        rom.extend(vec![bank as u8; CHR_ROM_BANK_SIZE / 8]);
+    }
+    Cartridge::from_bytes(&rom).unwrap()
+}
+
+fn bus_with(cartridge: Cartridge) -> NesBus {
+    let mut bus = NesBus::new();
+    bus.insert_cartridge(new_mapper(cartridge).unwrap());
+    bus
+}
+
+fn ppu_read(bus: &NesBus, address: u16) -> u8 {
+    bus.cartridge().unwrap().ppu_read(address)
+}
+
+/// Loads a MMC1 register serially, 1 bit per write
+fn mmc1_write(bus: &mut NesBus, value: u8, address: u16) {
+    for bit in 0..5 {
+        bus.write((value >> bit) & 1, address);
+    }
+}
+
+#[test]
+fn new_mapper_rejects_unsupported_mapper() {
+    // Given
+    let cartridge = cartridge(0xFF, 1, 1);
+
+    // When
+    let result = new_mapper(cartridge);
+
+    // Then
+    assert!(matches!(result, Err(CartridgeError::UnsupportedMapper(0xFF))));
+}
+
+#[test]
+fn nrom_128_mirrors_prg_rom() {
+    // Given
+    let mut rom = cartridge(0, 1, 1);
+    rom.prg_rom[0x0123] = 0x42;
+
+    // When
+    let bus = bus_with(rom);
+
+    // Then
+    assert_eq!(0x42, bus.peek(0x8123));
+    assert_eq!(0x42, bus.peek(0xC123));
+}
+
+#[test]
+fn nrom_256_maps_both_banks() {
+    // Given
+    let bus = bus_with(cartridge(0, 2, 1));
+
+    // Then
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(1, bus.peek(0xC000));
+    assert_eq!(1, bus.peek(0xFFFF));
+}
+
+#[test]
+fn nrom_ignores_writes_to_prg_rom_and_chr_rom() {
+    // Given
+    let mut bus = bus_with(cartridge(0, 1, 1));
+
+    // When
+    bus.write(0x42, 0x8000);
+    bus.cartridge_mut().unwrap().ppu_write(0x42, 0x0000);
+
+    // Then
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(0, ppu_read(&bus, 0x0000));
+}
+
+#[test]
+fn nrom_without_chr_rom_has_chr_ram() {
+    // Given
+    let mut bus = bus_with(cartridge(0, 1, 0));
+
+    // When
+    bus.cartridge_mut().unwrap().ppu_write(0x42, 0x1FFF);
+
+    // Then
+    assert_eq!(0x42, ppu_read(&bus, 0x1FFF));
+}
+
+#[test]
+fn nrom_has_prg_ram() {
+    // Given
+    let mut bus = bus_with(cartridge(0, 1, 1));
+
+    // When
+    bus.write(0x69, 0x7FFF);
+
+    // Then
+    assert_eq!(0x69, bus.peek(0x7FFF));
+}
+
+#[test]
+fn mmc1_powers_up_with_last_bank_fixed() {
+    // Given
+    let bus = bus_with(cartridge(1, 8, 1));
+
+    // Then
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(7, bus.peek(0xC000));
+}
+
+#[test]
+fn mmc1_switches_prg_bank_at_8000() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 8, 1));
+
+    // When
+    mmc1_write(&mut bus, 3, 0xE000);
+
+    // Then
+    assert_eq!(3, bus.peek(0x8000));
+    assert_eq!(7, bus.peek(0xFFFF));
+}
+
+#[test]
+fn mmc1_fixes_first_bank_and_switches_bank_at_c000() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 8, 1));
+
+    // When
+    mmc1_write(&mut bus, 0b0_1000, 0x8000);
+    mmc1_write(&mut bus, 5, 0xE000);
+
+    // Then
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(5, bus.peek(0xC000));
+}
+
+#[test]
+fn mmc1_32k_mode_ignores_low_bank_bit() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 8, 1));
+
+    // When
+    mmc1_write(&mut bus, 0b0_0000, 0x8000);
+    mmc1_write(&mut bus, 5, 0xE000);
+
+    // Then
+    assert_eq!(4, bus.peek(0x8000));
+    assert_eq!(5, bus.peek(0xC000));
+}
+
+#[test]
+fn mmc1_reset_bit_clears_shift_register() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 8, 1));
+    bus.write(1, 0xE000);
+    bus.write(1, 0xE000);
+
+    // When
+    bus.write(0x80, 0xE000);
+    mmc1_write(&mut bus, 2, 0xE000);
+
+    // Then
+    assert_eq!(2, bus.peek(0x8000));
+}
+
+#[test]
+fn mmc1_register_is_selected_by_fifth_write_address() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 8, 1));
+    bus.write(0x42, 0x6000);
+
+    // When
+    for _ in 0..4 {
+        bus.write(0, 0x8000);
+    }
+    bus.write(1, 0xE000);
+
+    // Then
+    // $10 went to the PRG bank register (PRG RAM disabled), control is untouched
+    assert_eq!(0x00, bus.peek(0x6000));
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(7, bus.peek(0xC000));
+}
+
+#[test]
+fn mmc1_controls_mirroring() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 2, 1));
+
+    // When / Then
+    mmc1_write(&mut bus, 0b0_1101, 0x8000);
+    assert_eq!(Mirroring::SingleScreenUpper, bus.cartridge().unwrap().mirroring());
+    mmc1_write(&mut bus, 0b0_1110, 0x8000);
+    assert_eq!(Mirroring::Vertical, bus.cartridge().unwrap().mirroring());
+    mmc1_write(&mut bus, 0b0_1111, 0x8000);
+    assert_eq!(Mirroring::Horizontal, bus.cartridge().unwrap().mirroring());
+}
+
+#[test]
+fn mmc1_switches_4k_chr_banks() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 2, 2));
+
+    // When
+    mmc1_write(&mut bus, 0b1_1100, 0x8000);
+    mmc1_write(&mut bus, 3, 0xA000);
+    mmc1_write(&mut bus, 1, 0xC000);
+
+    // Then
+    // Each 4 KiB bank holds 4 CHR test banks of 1 KiB
+    assert_eq!(12, ppu_read(&bus, 0x0000));
+    assert_eq!(4, ppu_read(&bus, 0x1000));
+}
+
+#[test]
+fn mmc1_switches_8k_chr_bank() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 2, 2));
+
+    // When
+    mmc1_write(&mut bus, 0b0_1100, 0x8000);
+    mmc1_write(&mut bus, 3, 0xA000);
+
+    // Then
+    assert_eq!(8, ppu_read(&bus, 0x0000));
+    assert_eq!(12, ppu_read(&bus, 0x1000));
+}
+
+#[test]
+fn mmc1_prg_ram_can_be_disabled() {
+    // Given
+    let mut bus = bus_with(cartridge(1, 2, 1));
+    bus.write(0x42, 0x6000);
+
+    // When
+    mmc1_write(&mut bus, 0b1_0000, 0xE000);
+    bus.write(0x69, 0x6000);
+
+    // Then
+    assert_eq!(0x00, bus.peek(0x6000));
+    mmc1_write(&mut bus, 0b0_0000, 0xE000);
+    assert_eq!(0x42, bus.peek(0x6000));
+}
+
+#[test]
+fn uxrom_switches_bank_at_8000_and_fixes_last_bank() {
+    // Given
+    let mut bus = bus_with(cartridge(2, 8, 0));
+
+    // When
+    bus.write(5, 0x8000);
+
+    // Then
+    assert_eq!(5, bus.peek(0x8000));
+    assert_eq!(5, bus.peek(0xBFFF));
+    assert_eq!(7, bus.peek(0xC000));
+}
+
+#[test]
+fn uxrom_bank_number_wraps_around_rom_size() {
+    // Given
+    let mut bus = bus_with(cartridge(2, 4, 0));
+
+    // When
+    bus.write(6, 0xFFFF);
+
+    // Then
+    assert_eq!(2, bus.peek(0x8000));
+}
+
+#[test]
+fn cnrom_switches_chr_bank() {
+    // Given
+    let mut bus = bus_with(cartridge(3, 2, 4));
+
+    // When
+    bus.write(2, 0x8000);
+
+    // Then
+    assert_eq!(16, ppu_read(&bus, 0x0000));
+    assert_eq!(23, ppu_read(&bus, 0x1FFF));
+    assert_eq!(0, bus.peek(0x8000));
+    assert_eq!(1, bus.peek(0xC000));
+}
+
+/// Builds a MMC3 cartridge where every 8 KiB PRG bank is filled with its bank number
+fn mmc3_cartridge(prg_banks: u8, chr_banks: u8) -> Cartridge {
+    let mut cartridge = cartridge(4, prg_banks, chr_banks);
+    for (bank, chunk) in cartridge.prg_rom.chunks_mut(0x2000).enumerate() {
+        chunk.fill(bank as u8);
+    }
+    cartridge
+}
+
+/// Simulates the PPU fetches of a scanline with background at $0000 and sprites at $1000
+fn mmc3_scanline(bus: &mut NesBus) {
+    let mapper = bus.cartridge_mut().unwrap();
+    for _ in 0..4 {
+        mapper.notify_ppu_address(0x0000);
+    }
+    mapper.notify_ppu_address(0x1000);
+}
+
+#[test]
+fn mmc3_switches_prg_banks() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(8, 1));
+
+    // When
+    bus.write(6, 0x8000);
+    bus.write(3, 0x8001);
+    bus.write(7, 0x8000);
+    bus.write(5, 0x8001);
+
+    // Then
+    assert_eq!(3, bus.peek(0x8000));
+    assert_eq!(5, bus.peek(0xA000));
+    assert_eq!(14, bus.peek(0xC000));
+    assert_eq!(15, bus.peek(0xE000));
+}
+
+#[test]
+fn mmc3_prg_mode_swaps_8000_and_c000() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(8, 1));
+
+    // When
+    bus.write(0b0100_0110, 0x8000);
+    bus.write(3, 0x8001);
+
+    // Then
+    assert_eq!(14, bus.peek(0x8000));
+    assert_eq!(3, bus.peek(0xC000));
+    assert_eq!(15, bus.peek(0xE000));
+}
+
+#[test]
+fn mmc3_switches_chr_banks() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 4));
+    let registers = [(0, 5), (1, 8), (2, 20), (3, 21), (4, 22), (5, 23)];
+
+    // When
+    for (register, bank) in registers {
+        bus.write(register, 0x8000);
+        bus.write(bank, 0x8001);
+    }
+
+    // Then
+    // 2 KiB banks ignore the low bit
+    assert_eq!(4, ppu_read(&bus, 0x0000));
+    assert_eq!(5, ppu_read(&bus, 0x0400));
+    assert_eq!(8, ppu_read(&bus, 0x0800));
+    assert_eq!(9, ppu_read(&bus, 0x0C00));
+    assert_eq!(20, ppu_read(&bus, 0x1000));
+    assert_eq!(23, ppu_read(&bus, 0x1FFF));
+}
+
+#[test]
+fn mmc3_chr_inversion_swaps_pattern_tables() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 4));
+    bus.write(0, 0x8000);
+    bus.write(6, 0x8001);
+    bus.write(2, 0x8000);
+    bus.write(20, 0x8001);
+
+    // When
+    bus.write(0b1000_0000, 0x8000);
+
+    // Then
+    assert_eq!(20, ppu_read(&bus, 0x0000));
+    assert_eq!(6, ppu_read(&bus, 0x1000));
+    assert_eq!(7, ppu_read(&bus, 0x1400));
+}
+
+#[test]
+fn mmc3_controls_mirroring() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 1));
+
+    // When / Then
+    bus.write(1, 0xA000);
+    assert_eq!(Mirroring::Horizontal, bus.cartridge().unwrap().mirroring());
+    bus.write(0, 0xBFFE);
+    assert_eq!(Mirroring::Vertical, bus.cartridge().unwrap().mirroring());
+}
+
+#[test]
+fn mmc3_prg_ram_protection() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 1));
+
+    // When / Then
+    bus.write(0x42, 0x6000);
+    assert_eq!(0x00, bus.peek(0x6000));
+
+    bus.write(0b1000_0000, 0xA001);
+    bus.write(0x42, 0x6000);
+    assert_eq!(0x42, bus.peek(0x6000));
+
+    bus.write(0b1100_0000, 0xA001);
+    bus.write(0x69, 0x6000);
+    assert_eq!(0x42, bus.peek(0x6000));
+}
+
+#[test]
+fn mmc3_irq_counter_asserts_irq_after_latch_scanlines() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 1));
+    bus.write(2, 0xC000);
+    bus.write(0, 0xC001);
+    bus.write(0, 0xE001);
+
+    // When / Then
+    // First clock reloads the counter with the latch
+    mmc3_scanline(&mut bus);
+    mmc3_scanline(&mut bus);
+    assert!(!bus.irq());
+    mmc3_scanline(&mut bus);
+    assert!(bus.irq());
+
+    bus.write(0, 0xE000);
+    assert!(!bus.irq());
+}
+
+#[test]
+fn mmc3_irq_counter_ignores_unfiltered_a12_rises() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 1));
+    bus.write(0, 0xC000);
+    bus.write(0, 0xC001);
+    bus.write(0, 0xE001);
+
+    // When
+    // Sprite pattern fetches: 2 nametable fetches between every pattern fetch
+    let mapper = bus.cartridge_mut().unwrap();
+    mapper.notify_ppu_address(0x1000);
+    for _ in 0..8 {
+        mapper.notify_ppu_address(0x2000);
+        mapper.notify_ppu_address(0x2000);
+        mapper.notify_ppu_address(0x1000);
+    }
+
+    // Then
+    assert!(!bus.irq());
+}
+
+#[test]
+fn mmc3_irq_is_not_asserted_when_disabled() {
+    // Given
+    let mut bus = bus_with(mmc3_cartridge(2, 1));
+    bus.write(0, 0xC000);
+    bus.write(0, 0xC001);
+
+    // When
+    mmc3_scanline(&mut bus);
+
+    // Then
+    assert!(!bus.irq());
+}
+
+#[test]
+fn mmc3_irq_interrupts_cpu() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $E000    58        CLI
+    // $E001    4c 01 e0  JMP $E001
+    // IRQ handler
+    // $E004    a9 42     LDA #$42
+    // $E006    85 10     STA $10
+    let mut cartridge = mmc3_cartridge(2, 1);
+    let last_bank = cartridge.prg_rom.len() - 0x2000;
+    cartridge.prg_rom[last_bank..last_bank + 8].copy_from_slice(&[0x58, 0x4C, 0x01, 0xE0, 0xA9, 0x42, 0x85, 0x10]);
+    cartridge.prg_rom[last_bank + 0x1FFC..].copy_from_slice(&[0x00, 0xE0, 0x04, 0xE0]);
+    let mut bus = bus_with(cartridge);
+    bus.write(0, 0xC000);
+    bus.write(0, 0xC001);
+    bus.write(0, 0xE001);
+    let mut cpu = Cpu::new_with_bus(bus);
+    cpu.reset();
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+    assert_eq!(0x00, cpu.bus().peek(0x0010));
+
+    // When
+    mmc3_scanline(cpu.bus_mut());
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    // Then
+    assert_eq!(0x42, cpu.bus().peek(0x0010));
+}
@@ -0,0 +1,59 @@
+use super::{CartridgeMemory, Mapper, PRG_RAM_END, PRG_RAM_START, PRG_ROM_START};
+use crate::cartridge::Cartridge;
+use crate::cartridge::types::Mirroring;
+
+const PRG_BANK_SIZE: usize = 0x4000;
+const CHR_BANK_SIZE: usize = 0x2000;
+const FIXED_BANK_START: u16 = 0xC000;
+
+/// Mapper 2 (UxROM): switchable 16 KiB PRG bank at $8000-$BFFF,
+/// last 16 KiB PRG bank fixed at $C000-$FFFF. Bank is selected by writing to $8000-$FFFF.
+///
+/// see: [UxROM](https://www.nesdev.org/wiki/UxROM)
+pub struct Uxrom {
+    memory: CartridgeMemory,
+    prg_bank: usize
+}
+
+impl Uxrom {
+    pub fn new(cartridge: Cartridge) -> Self {
+        Self {
+            memory: CartridgeMemory::new(cartridge),
+            prg_bank: 0
+        }
+    }
+}
+
+impl Mapper for Uxrom {
+    fn cpu_read(&self, address: u16) -> u8 {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END => self.memory.read_prg_ram(address),
+            PRG_ROM_START..=0xBFFF => self.memory.read_prg_rom(PRG_BANK_SIZE, self.prg_bank, address - PRG_ROM_START),
+            FIXED_BANK_START..=0xFFFF => {
+                let last_bank = self.memory.prg_bank_count(PRG_BANK_SIZE) - 1;
+                self.memory.read_prg_rom(PRG_BANK_SIZE, last_bank, address - FIXED_BANK_START)
+            },
+            _ => 0
+        }
+    }
+
+    fn cpu_write(&mut self, data: u8, address: u16) {
+        match address {
+            PRG_RAM_START..=PRG_RAM_END => self.memory.write_prg_ram(data, address),
+            PRG_ROM_START..=0xFFFF => self.prg_bank = data as usize,
+            _ => ()
+        }
+    }
+
+    fn ppu_read(&self, address: u16) -> u8 {
+        self.memory.read_chr(CHR_BANK_SIZE, 0, address)
+    }
+
+    fn ppu_write(&mut self, data: u8, address: u16) {
+        self.memory.write_chr(data, CHR_BANK_SIZE, 0, address);
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.memory.mirroring()
+    }
+}
@@ -0,0 +1,183 @@
+pub mod mapper;
+pub mod types;
+
+use std::fs;
+use std::path::Path;
+
+use types::{CartridgeError, Header, HeaderFormat, Mirroring, TimingRegion};
+
+pub const HEADER_SIZE: usize = 16;
+pub const TRAINER_SIZE: usize = 512;
+pub const PRG_ROM_BANK_SIZE: usize = 0x4000;
+pub const CHR_ROM_BANK_SIZE: usize = 0x2000;
+/// iNES files that don't specify PRG RAM size assume 8 KiB for compatibility
+const DEFAULT_PRG_RAM_SIZE: usize = 0x2000;
+
+const SIGNATURE: [u8; 4] = [b'N', b'E', b'S', 0x1A];
+
+/// Game cartridge loaded from an iNES or NES 2.0 (.nes) file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cartridge {
+    pub header: Header,
+    pub trainer: Option<Vec<u8>>,
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>
+}
+
+impl Cartridge {
+    pub fn from_file<P: AsRef<Path>>(filename: P) -> Result<Self, CartridgeError> {
+        let data = fs::read(filename).map_err(|error| CartridgeError::IoError(error.to_string()))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Parses the content of a .nes file: header, optional trainer, PRG ROM and CHR ROM
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CartridgeError> {
+        let header = parse_header(data)?;
+        let mut offset = HEADER_SIZE;
+
+        let trainer = if header.trainer {
+            Some(read_section(data, &mut offset, TRAINER_SIZE, "Trainer")?)
+        } else {
+            None
+        };
+        let prg_rom = read_section(data, &mut offset, header.prg_rom_size, "PRG ROM")?;
+        let chr_rom = read_section(data, &mut offset, header.chr_rom_size, "CHR ROM")?;
+
+        Ok(Self {
+            header,
+            trainer,
+            prg_rom,
+            chr_rom
+        })
+    }
+}
+
+fn read_section(data: &[u8], offset: &mut usize, size: usize, name: &str) -> Result<Vec<u8>, CartridgeError> {
+    let available = data.len().saturating_sub(*offset);
+    if available < size {
+        return Err(CartridgeError::TruncatedData(name.to_string(), size, available));
+    }
+    let section = data[*offset..*offset + size].to_vec();
+    *offset += size;
+    Ok(section)
+}
+
+/// Parses the 16 bytes header, detecting if it's an iNES or a NES 2.0 one
+pub fn parse_header(data: &[u8]) -> Result<Header, CartridgeError> {
+    if data.len() < HEADER_SIZE {
+        return Err(CartridgeError::TruncatedHeader(data.len()));
+    }
+
+    let signature: [u8; 4] = [data[0], data[1], data[2], data[3]];
+    if signature != SIGNATURE {
+        return Err(CartridgeError::InvalidSignature(signature));
+    }
+
+    let flags_6 = data[6];
+    let flags_7 = data[7];
+
+    let mirroring = if flags_6 & 0b0000_1000 != 0 {
+        Mirroring::FourScreen
+    } else if flags_6 & 0b0000_0001 != 0 {
+        Mirroring::Vertical
+    } else {
+        Mirroring::Horizontal
+    };
+    let battery = flags_6 & 0b0000_0010 != 0;
+    let trainer = flags_6 & 0b0000_0100 != 0;
+
+    // NES 2.0 is identified by bits 2-3 of flags 7 being 0b10
+    let header = if flags_7 & 0b0000_1100 == 0b0000_1000 {
+        parse_nes_2_0_fields(data, mirroring, battery, trainer)?
+    } else {
+        parse_ines_fields(data, mirroring, battery, trainer)
+    };
+
+    if header.prg_rom_size == 0 {
+        return Err(CartridgeError::InvalidHeader("PRG ROM size is zero".to_string()));
+    }
+
+    Ok(header)
+}
+
+fn parse_ines_fields(data: &[u8], mirroring: Mirroring, battery: bool, trainer: bool) -> Header {
+    let flags_6 = data[6];
+    // Old ROM dumping tools wrote garbage (e.g. "DiskDude!") in bytes 7-15.
+    // In that case, the upper nibble of the mapper number can't be trusted
+    let flags_7 = if data[12..HEADER_SIZE].iter().any(|&byte| byte != 0) { 0 } else { data[7] };
+
+    let chr_rom_size = data[5] as usize * CHR_ROM_BANK_SIZE;
+    let prg_ram_size = match data[8] {
+        0 => DEFAULT_PRG_RAM_SIZE,
+        banks => banks as usize * DEFAULT_PRG_RAM_SIZE
+    };
+
+    Header {
+        format: HeaderFormat::INes,
+        prg_rom_size: data[4] as usize * PRG_ROM_BANK_SIZE,
+        chr_rom_size,
+        mapper: ((flags_7 & 0xF0) | (flags_6 >> 4)) as u16,
+        submapper: 0,
+        mirroring,
+        battery,
+        trainer,
+        prg_ram_size: if battery { 0 } else { prg_ram_size },
+        prg_nvram_size: if battery { prg_ram_size } else { 0 },
+        // Cartridges without CHR ROM have 8 KiB of CHR RAM
+        chr_ram_size: if chr_rom_size == 0 { CHR_ROM_BANK_SIZE } else { 0 },
+        chr_nvram_size: 0,
+        timing: if data[9] & 0x01 != 0 { TimingRegion::Pal } else { TimingRegion::Ntsc }
+    }
+}
+
+fn parse_nes_2_0_fields(data: &[u8], mirroring: Mirroring, battery: bool, trainer: bool) -> Result<Header, CartridgeError> {
+    let mapper = (data[8] as u16 & 0x0F) << 8 | (data[7] & 0xF0) as u16 | (data[6] >> 4) as u16;
+
+    let prg_rom_size = nes_2_0_rom_size(data[4], data[9] & 0x0F, PRG_ROM_BANK_SIZE, "PRG ROM")?;
+    let chr_rom_size = nes_2_0_rom_size(data[5], data[9] >> 4, CHR_ROM_BANK_SIZE, "CHR ROM")?;
+
+    let timing = match data[12] & 0x03 {
+        0 => TimingRegion::Ntsc,
+        1 => TimingRegion::Pal,
+        2 => TimingRegion::MultipleRegion,
+        _ => TimingRegion::Dendy
+    };
+
+    Ok(Header {
+        format: HeaderFormat::Nes20,
+        prg_rom_size,
+        chr_rom_size,
+        mapper,
+        submapper: data[8] >> 4,
+        mirroring,
+        battery,
+        trainer,
+        prg_ram_size: nes_2_0_ram_size(data[10] & 0x0F),
+        prg_nvram_size: nes_2_0_ram_size(data[10] >> 4),
+        chr_ram_size: nes_2_0_ram_size(data[11] & 0x0F),
+        chr_nvram_size: nes_2_0_ram_size(data[11] >> 4),
+        timing
+    })
+}
+
+/// ROM sizes in NES 2.0 use a 12-bit bank count, unless the most significant nibble is $F.
+/// In that case, the least significant byte is in the form EEEEEEMM and the size is 2^E * (MM * 2 + 1) bytes
+fn nes_2_0_rom_size(lsb: u8, msb: u8, bank_size: usize, name: &str) -> Result<usize, CartridgeError> {
+    if msb == 0x0F {
+        let exponent = (lsb >> 2) as u32;
+        let multiplier = (lsb & 0x03) as usize * 2 + 1;
+        1usize.checked_shl(exponent)
+            .and_then(|size| size.checked_mul(multiplier))
+            .ok_or_else(|| CartridgeError::InvalidHeader(format!("{} size 2^{} * {} is too big", name, exponent, multiplier)))
+    } else {
+        Ok(((msb as usize) << 8 | lsb as usize) * bank_size)
+    }
+}
+
+/// RAM sizes in NES 2.0 are stored as a shift count: size = 64 << shift. Zero means no RAM
+fn nes_2_0_ram_size(shift: u8) -> usize {
+    if shift == 0 { 0 } else { 64 << shift }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,215 @@
+use super::*;
+
+fn ines_header(prg_banks: u8, chr_banks: u8, flags_6: u8, flags_7: u8) -> Vec<u8> {
+    vec![0x4E, 0x45, 0x53, 0x1A, prg_banks, chr_banks, flags_6, flags_7, 0, 0, 0, 0, 0, 0, 0, 0]
+}
+
+fn rom_file(header: &[u8], trainer_size: usize, prg_size: usize, chr_size: usize) -> Vec<u8> {
+    let mut data = header.to_vec();
+    data.extend(vec![0x7A; trainer_size]);
+    data.extend(vec![0x11; prg_size]);
+    data.extend(vec![0x22; chr_size]);
+    data
+}
+
+#[test]
+fn parse_ines_header() {
+    // Given
+    // 2 PRG banks, 1 CHR bank, vertical mirroring, battery, mapper 0x31
+    let header = ines_header(2, 1, 0b0001_0011, 0b0011_0000);
+
+    // When
+    let result = parse_header(&header);
+
+    // Then
+    assert_eq!(result, Ok(Header {
+        format: HeaderFormat::INes,
+        prg_rom_size: 0x8000,
+        chr_rom_size: 0x2000,
+        mapper: 0x31,
+        submapper: 0,
+        mirroring: Mirroring::Vertical,
+        battery: true,
+        trainer: false,
+        prg_ram_size: 0,
+        prg_nvram_size: 0x2000,
+        chr_ram_size: 0,
+        chr_nvram_size: 0,
+        timing: TimingRegion::Ntsc
+    }));
+}
+
+#[test]
+fn parse_ines_header_without_chr_rom_has_chr_ram() {
+    // Given
+    let header = ines_header(1, 0, 0, 0);
+
+    // When
+    let result = parse_header(&header).unwrap();
+
+    // Then
+    assert_eq!(result.mirroring, Mirroring::Horizontal);
+    assert_eq!(result.chr_rom_size, 0);
+    assert_eq!(result.chr_ram_size, 0x2000);
+    assert_eq!(result.prg_ram_size, 0x2000);
+}
+
+#[test]
+fn parse_ines_header_four_screen_mirroring() {
+    // Given
+    let header = ines_header(1, 1, 0b0000_1001, 0);
+
+    // When
+    let result = parse_header(&header).unwrap();
+
+    // Then
+    assert_eq!(result.mirroring, Mirroring::FourScreen);
+}
+
+#[test]
+fn parse_ines_header_ignores_mapper_high_nibble_with_garbage_in_header() {
+    // Given
+    let mut header = ines_header(1, 1, 0b0001_0000, 0b0100_0000);
+    header[7..16].copy_from_slice(b"DiskDude!");
+
+    // When
+    let result = parse_header(&header).unwrap();
+
+    // Then
+    assert_eq!(result.mapper, 1);
+}
+
+#[test]
+fn parse_nes_2_0_header() {
+    // Given
+    let mut header = ines_header(0x02, 0x01, 0b0100_0100, 0b1010_1000);
+    // Mapper bits 8-11 = 1, submapper = 3
+    header[8] = 0x31;
+    // PRG ROM MSB = 1, CHR ROM MSB = 0
+    header[9] = 0x01;
+    // PRG RAM 64 << 7 = 8 KiB, PRG NVRAM 64 << 6 = 4 KiB
+    header[10] = 0x67;
+    // CHR RAM 64 << 7 = 8 KiB
+    header[11] = 0x07;
+    // Dendy
+    header[12] = 0x03;
+
+    // When
+    let result = parse_header(&header);
+
+    // Then
+    assert_eq!(result, Ok(Header {
+        format: HeaderFormat::Nes20,
+        prg_rom_size: 0x102 * 0x4000,
+        chr_rom_size: 0x2000,
+        mapper: 0x1A4,
+        submapper: 3,
+        mirroring: Mirroring::Horizontal,
+        battery: false,
+        trainer: true,
+        prg_ram_size: 0x2000,
+        prg_nvram_size: 0x1000,
+        chr_ram_size: 0x2000,
+        chr_nvram_size: 0,
+        timing: TimingRegion::Dendy
+    }));
+}
+
+#[test]
+fn parse_nes_2_0_header_with_exponent_multiplier_rom_size() {
+    // Given
+    let mut header = ines_header(0b0000_1001, 0, 0, 0b0000_1000);
+    // PRG ROM size in exponent-multiplier notation: 2^2 * (1 * 2 + 1) = 12 bytes
+    header[9] = 0x0F;
+
+    // When
+    let result = parse_header(&header).unwrap();
+
+    // Then
+    assert_eq!(result.prg_rom_size, 12);
+    assert_eq!(result.timing, TimingRegion::Ntsc);
+}
+
+#[test]
+fn parse_header_should_fail_when_file_is_too_small() {
+    // Given
+    let data = [0x4E, 0x45, 0x53];
+
+    // When
+    let result = parse_header(&data);
+
+    // Then
+    assert_eq!(result, Err(CartridgeError::TruncatedHeader(3)));
+}
+
+#[test]
+fn parse_header_should_fail_when_signature_is_invalid() {
+    // Given
+    let mut header = ines_header(1, 1, 0, 0);
+    header[3] = 0x00;
+
+    // When
+    let result = parse_header(&header);
+
+    // Then
+    assert_eq!(result, Err(CartridgeError::InvalidSignature([0x4E, 0x45, 0x53, 0x00])));
+}
+
+#[test]
+fn parse_header_should_fail_when_prg_rom_is_empty() {
+    // Given
+    let header = ines_header(0, 1, 0, 0);
+
+    // When
+    let result = parse_header(&header);
+
+    // Then
+    assert_eq!(result, Err(CartridgeError::InvalidHeader("PRG ROM size is zero".to_string())));
+}
+
+#[test]
+fn load_cartridge_with_trainer() {
+    // Given
+    let data = rom_file(&ines_header(1, 1, 0b0000_0100, 0), TRAINER_SIZE, 0x4000, 0x2000);
+
+    // When
+    let cartridge = Cartridge::from_bytes(&data).unwrap();
+
+    // Then
+    assert_eq!(cartridge.trainer, Some(vec![0x7A; TRAINER_SIZE]));
+    assert_eq!(cartridge.prg_rom, vec![0x11; 0x4000]);
+    assert_eq!(cartridge.chr_rom, vec![0x22; 0x2000]);
+}
+
+#[test]
+fn load_cartridge_should_fail_when_prg_rom_is_truncated() {
+    // Given
+    let data = rom_file(&ines_header(2, 1, 0, 0), 0, 0x4000, 0);
+
+    // When
+    let result = Cartridge::from_bytes(&data);
+
+    // Then
+    assert_eq!(result, Err(CartridgeError::TruncatedData("PRG ROM".to_string(), 0x8000, 0x4000)));
+}
+
+#[test]
+fn load_cartridge_should_fail_when_chr_rom_is_truncated() {
+    // Given
+    let data = rom_file(&ines_header(1, 1, 0, 0), 0, 0x4000, 0x100);
+
+    // When
+    let result = Cartridge::from_bytes(&data);
+
+    // Then
+    assert_eq!(result, Err(CartridgeError::TruncatedData("CHR ROM".to_string(), 0x2000, 0x100)));
+}
+
+#[test]
+fn load_cartridge_should_fail_when_file_does_not_exist() {
+    // When
+    let result = Cartridge::from_file("this/file/does/not/exist.nes");
+
+    // Then
+    assert!(matches!(result, Err(CartridgeError::IoError(_))));
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CartridgeError {
+    /// File is smaller than the 16 bytes header
+    TruncatedHeader(usize),
+    /// File doesn't start with "NES" followed by MS-DOS end-of-file ($1A)
+    InvalidSignature([u8; 4]),
+    /// Header fields have values that don't make sense
+    InvalidHeader(String),
+    /// File is smaller than what the header declares. Holds the section name, expected and available sizes
+    TruncatedData(String, usize, usize),
+    UnsupportedMapper(u16),
+    IoError(String)
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartridgeError::TruncatedHeader(size) => {
+                write!(f, "File too small to contain an iNES header: {} bytes (expected at least 16)", size)
+            },
+            CartridgeError::InvalidSignature(signature) => {
+                write!(f, "Invalid iNES signature: {:02X?} (expected [4E, 45, 53, 1A])", signature)
+            },
+            CartridgeError::InvalidHeader(message) => {
+                write!(f, "Invalid iNES header: {}", message)
+            },
+            CartridgeError::TruncatedData(section, expected, available) => {
+                write!(f, "Truncated file: {} expects {} bytes, but only {} are available", section, expected, available)
+            },
+            CartridgeError::UnsupportedMapper(mapper) => {
+                write!(f, "Mapper {} is not supported", mapper)
+            },
+            CartridgeError::IoError(message) => {
+                write!(f, "Error reading ROM file: {}", message)
+            }
+        }
+    }
+}
+
+/// Nametable mirroring arrangement
+///
+/// see: [Mirroring](https://www.nesdev.org/wiki/Mirroring)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirroring {
+    /// $2000 = $2400 and $2800 = $2C00 (vertical arrangement, used by vertical scrolling games)
+    Horizontal,
+    /// $2000 = $2800 and $2400 = $2C00 (horizontal arrangement, used by horizontal scrolling games)
+    Vertical,
+    /// Cartridge provides extra VRAM, so each nametable is unique
+    FourScreen,
+    /// All nametables point to the first 1 KiB of VRAM (mapper controlled)
+    SingleScreenLower,
+    /// All nametables point to the second 1 KiB of VRAM (mapper controlled)
+    SingleScreenUpper
+}
+
+/// Television system the ROM was designed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingRegion {
+    Ntsc,
+    Pal,
+    /// Works on both NTSC and PAL machines
+    MultipleRegion,
+    Dendy
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFormat {
+    INes,
+    Nes20
+}
+
+/// Information extracted from the 16 bytes header of a .nes file
+/// All sizes are in bytes.
+///
+/// see: [iNES](https://www.nesdev.org/wiki/INES) and [NES 2.0](https://www.nesdev.org/wiki/NES_2.0)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub format: HeaderFormat,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub mapper: u16,
+    pub submapper: u8,
+    pub mirroring: Mirroring,
+    /// Cartridge has battery-backed PRG RAM (or other persistent memory)
+    pub battery: bool,
+    /// 512 bytes trainer between the header and the PRG ROM
+    pub trainer: bool,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub timing: TimingRegion
+}
@@ -1,4 +1,5 @@
-use crate::cpu::types::CpuFlags;
+use crate::cpu::types::{CpuFlags, CpuVariant};
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait Arithmetic {
@@ -9,33 +10,15 @@ pub trait Arithmetic {
     fn cpy(&mut self, data: u8);
 }
 
-impl Arithmetic for Cpu {
+impl<B: Bus> Arithmetic for Cpu<B> {
     /// Implementation of ADC (Add with Carry) instruction
+    /// The NMOS 6502 and the 65C02 add in BCD when DECIMAL_MODE is set, the 2A03 always adds in binary
     fn adc(&mut self, data: u8) {
-        let carry: u8 = if self.registers.status.contains(CpuFlags::CARRY) {1} else {0};
-
-        let (result, carry_1) = self.registers.accumulator.overflowing_add(data);
-        let (result, carry_2) = result.overflowing_add(carry);
-
-        self.registers
-            .status
-            .set(CpuFlags::CARRY, carry_1 || carry_2);
-        self.registers
-            .status
-            .set(CpuFlags::ZERO, result == 0);
-        self.registers
-            .status
-            .set(CpuFlags::NEGATIVE, result & 0x80 == 0x80);
-
-        // For overflow, we need to check if the sign bit of the accumulator and the sign bit of the data
-        // are different from the sign bit of the result
-        // For detailed explanation, see https://www.righto.com/2012/12/the-6502-overflow-flag-explained.html
-        self.registers.status.set(
-            CpuFlags::OVERFLOW,
-            (self.registers.accumulator ^ result) & (data ^ result) & 0x80 != 0,
-        );
-
-        self.registers.accumulator = result;
+        if is_decimal_mode(self) {
+            adc_decimal(self, data);
+        } else {
+            adc_binary(self, data);
+        }
     }
 
     /// Implementation of SBC (Subtract with Carry) instruction
@@ -47,10 +30,14 @@ impl Arithmetic for Cpu {
     /// So, we need to also remove the carry from the result, because it is an inverse borrow
     /// See [SBC docs](https://www.nesdev.org/obelisk-6502-guide/reference.html#SBC)
     fn sbc(&mut self, data: u8) {
-        // SBC is the same as ADC but with the data inverted,
-        // A-M-(1-C) => A+(-M-1+C) => A+(-M-1)+C
-        let negative_data = data.wrapping_neg().wrapping_sub(1);
-        self.adc(negative_data);
+        if is_decimal_mode(self) {
+            sbc_decimal(self, data);
+        } else {
+            // SBC is the same as ADC but with the data inverted,
+            // A-M-(1-C) => A+(-M-1+C) => A+(-M-1)+C
+            let negative_data = data.wrapping_neg().wrapping_sub(1);
+            adc_binary(self, negative_data);
+        }
     }
 
     /// Implementation of CMP (Compare with Accumulator) instruction
@@ -69,10 +56,123 @@ impl Arithmetic for Cpu {
     }
 }
 
-fn compare(cpu: &mut Cpu, register: u8, data: u8) {
+fn is_decimal_mode<B: Bus>(cpu: &Cpu<B>) -> bool {
+    cpu.variant != CpuVariant::Ricoh2A03 && cpu.registers.status.contains(CpuFlags::DECIMAL_MODE)
+}
+
+fn carry<B: Bus>(cpu: &Cpu<B>) -> u8 {
+    if cpu.registers.status.contains(CpuFlags::CARRY) {1} else {0}
+}
+
+fn adc_binary<B: Bus>(cpu: &mut Cpu<B>, data: u8) {
+    let carry = carry(cpu);
+
+    let (result, carry_1) = cpu.registers.accumulator.overflowing_add(data);
+    let (result, carry_2) = result.overflowing_add(carry);
+
+    cpu.registers
+        .status
+        .set(CpuFlags::CARRY, carry_1 || carry_2);
+    cpu.registers
+        .status
+        .set(CpuFlags::ZERO, result == 0);
+    cpu.registers
+        .status
+        .set(CpuFlags::NEGATIVE, result & 0x80 == 0x80);
+
+    // For overflow, we need to check if the sign bit of the accumulator and the sign bit of the data
+    // are different from the sign bit of the result
+    // For detailed explanation, see https://www.righto.com/2012/12/the-6502-overflow-flag-explained.html
+    cpu.registers.status.set(
+        CpuFlags::OVERFLOW,
+        (cpu.registers.accumulator ^ result) & (data ^ result) & 0x80 != 0,
+    );
+
+    cpu.registers.accumulator = result;
+}
+
+/// BCD addition. Each nibble is adjusted by 6 when it exceeds 9.
+/// On the NMOS 6502, Z is set from the binary sum, while N and V come from the intermediate result,
+/// after adjusting the low nibble but before adjusting the high one.
+/// The 65C02 sets N and Z from the BCD result.
+///
+/// see: [Decimal mode, appendix A](http://www.6502.org/tutorials/decimal_mode.html#A)
+fn adc_decimal<B: Bus>(cpu: &mut Cpu<B>, data: u8) {
+    let accumulator = cpu.registers.accumulator;
+    let carry = carry(cpu);
+
+    let mut low = (accumulator & 0x0F) as u16 + (data & 0x0F) as u16 + carry as u16;
+    if low >= 0x0A {
+        low = ((low + 0x06) & 0x0F) + 0x10;
+    }
+    let intermediate = (accumulator & 0xF0) as u16 + (data & 0xF0) as u16 + low;
+    // Same sum, with the high nibbles taken as signed (two's complement)
+    let signed = (accumulator & 0xF0) as i8 as i16 + (data & 0xF0) as i8 as i16 + low as i16;
+
+    let mut result = intermediate;
+    if result >= 0xA0 {
+        result += 0x60;
+    }
+
+    let binary_result = accumulator.wrapping_add(data).wrapping_add(carry);
+    cpu.registers.status.set(CpuFlags::CARRY, result >= 0x100);
+    cpu.registers.status.set(CpuFlags::OVERFLOW, !(-128..=127).contains(&signed));
+    if cpu.variant == CpuVariant::Cmos65C02 {
+        cpu.registers.status.set(CpuFlags::ZERO, result as u8 == 0);
+        cpu.registers.status.set(CpuFlags::NEGATIVE, result & 0x80 == 0x80);
+    } else {
+        cpu.registers.status.set(CpuFlags::ZERO, binary_result == 0);
+        cpu.registers.status.set(CpuFlags::NEGATIVE, intermediate & 0x80 == 0x80);
+    }
+
+    cpu.registers.accumulator = result as u8;
+}
+
+/// BCD subtraction. Each nibble is adjusted by 6 when it borrows.
+/// On the NMOS 6502, every flag is set as in a binary subtraction.
+/// The 65C02 sets N and Z from the BCD result.
+///
+/// see: [Decimal mode, appendix A](http://www.6502.org/tutorials/decimal_mode.html#A)
+fn sbc_decimal<B: Bus>(cpu: &mut Cpu<B>, data: u8) {
+    let accumulator = cpu.registers.accumulator;
+    let carry = carry(cpu) as i16;
+
+    if cpu.variant == CpuVariant::Cmos65C02 {
+        // The 65C02 adjusts the whole result, then the low nibble (sequence 4 of the appendix)
+        let low = (accumulator & 0x0F) as i16 - (data & 0x0F) as i16 + carry - 1;
+        let mut result = accumulator as i16 - data as i16 + carry - 1;
+        if result < 0 {
+            result -= 0x60;
+        }
+        if low < 0 {
+            result -= 0x06;
+        }
+
+        adc_binary(cpu, !data);
+        let result = result as u8;
+        cpu.registers.status.set(CpuFlags::ZERO, result == 0);
+        cpu.registers.status.set(CpuFlags::NEGATIVE, result & 0x80 == 0x80);
+        cpu.registers.accumulator = result;
+        return;
+    }
+
+    let mut low = (accumulator & 0x0F) as i16 - (data & 0x0F) as i16 + carry - 1;
+    if low < 0 {
+        low = ((low - 0x06) & 0x0F) - 0x10;
+    }
+    let mut result = (accumulator & 0xF0) as i16 - (data & 0xF0) as i16 + low;
+    if result < 0 {
+        result -= 0x60;
+    }
+
+    adc_binary(cpu, !data);
+    cpu.registers.accumulator = result as u8;
+}
+
+fn compare<B: Bus>(cpu: &mut Cpu<B>, register: u8, data: u8) {
     cpu.registers.status.set(CpuFlags::CARRY, register >= data);
     cpu.registers.status.set(CpuFlags::ZERO, register == data);
-    cpu.registers.status.set(CpuFlags::NEGATIVE, register & 0x80 == 0x80);  
+    cpu.registers.status.set(CpuFlags::NEGATIVE, register.wrapping_sub(data) & 0x80 == 0x80);
 }
 
 #[cfg(test)]
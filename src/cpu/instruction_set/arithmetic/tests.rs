@@ -1,6 +1,7 @@
 use super::Arithmetic;
 use super::Cpu;
 use super::CpuFlags;
+use crate::cpu::types::CpuVariant;
 
 #[test]
 fn test_adc_no_carry() {
@@ -249,6 +250,301 @@ fn test_cpy_flag_carry_and_negative() {
         Cpu::cpy);
 }
 
+#[test]
+fn test_cmp_flag_negative_from_difference() {
+    // $40 - $41 = $FF: N comes from the difference, not from the register
+    run_arithmetic_test(
+        0x40,
+        0x41,
+        CpuFlags::empty(),
+        0x40,
+        CpuFlags::NEGATIVE,
+        |cpu| cpu.registers.accumulator,
+        |cpu, value| cpu.registers.accumulator = value,
+        Cpu::cmp);
+}
+
+#[test]
+fn test_cmp_flag_carry_without_negative() {
+    // $80 - $01 = $7F
+    run_arithmetic_test(
+        0x80,
+        0x01,
+        CpuFlags::empty(),
+        0x80,
+        CpuFlags::CARRY,
+        |cpu| cpu.registers.accumulator,
+        |cpu, value| cpu.registers.accumulator = value,
+        Cpu::cmp);
+}
+
+#[test]
+fn test_cpx_flag_negative_from_difference() {
+    // $40 - $41 = $FF: N comes from the difference, not from the register
+    run_arithmetic_test(
+        0x40,
+        0x41,
+        CpuFlags::empty(),
+        0x40,
+        CpuFlags::NEGATIVE,
+        |cpu| cpu.registers.x_register,
+        |cpu, value| cpu.registers.x_register = value,
+        Cpu::cpx);
+}
+
+#[test]
+fn test_cpx_flag_carry_without_negative() {
+    // $80 - $01 = $7F
+    run_arithmetic_test(
+        0x80,
+        0x01,
+        CpuFlags::empty(),
+        0x80,
+        CpuFlags::CARRY,
+        |cpu| cpu.registers.x_register,
+        |cpu, value| cpu.registers.x_register = value,
+        Cpu::cpx);
+}
+
+#[test]
+fn test_cpy_flag_negative_from_difference() {
+    // $40 - $41 = $FF: N comes from the difference, not from the register
+    run_arithmetic_test(
+        0x40,
+        0x41,
+        CpuFlags::empty(),
+        0x40,
+        CpuFlags::NEGATIVE,
+        |cpu| cpu.registers.y_register,
+        |cpu, value| cpu.registers.y_register = value,
+        Cpu::cpy);
+}
+
+#[test]
+fn test_cpy_flag_carry_without_negative() {
+    // $80 - $01 = $7F
+    run_arithmetic_test(
+        0x80,
+        0x01,
+        CpuFlags::empty(),
+        0x80,
+        CpuFlags::CARRY,
+        |cpu| cpu.registers.y_register,
+        |cpu, value| cpu.registers.y_register = value,
+        Cpu::cpy);
+}
+
+#[test]
+fn test_adc_ignores_decimal_mode_on_2a03() {
+    run_arithmetic_test(
+        0x09,
+        0x01,
+        CpuFlags::DECIMAL_MODE,
+        0x0a,
+        CpuFlags::DECIMAL_MODE,
+        |cpu| cpu.registers.accumulator,
+        |cpu, value| cpu.registers.accumulator = value,
+        Cpu::adc);
+}
+
+#[test]
+fn test_adc_decimal_nmos_flags_come_from_intermediate_result() {
+    // Given
+    let mut cpu = nmos_cpu(0x79, CpuFlags::DECIMAL_MODE | CpuFlags::CARRY);
+
+    // When
+    cpu.adc(0x00);
+
+    // Then
+    assert_eq!(0x80, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::NEGATIVE | CpuFlags::OVERFLOW, cpu.registers.status);
+}
+
+#[test]
+fn test_adc_decimal_nmos_zero_flag_comes_from_binary_result() {
+    // Given
+    let mut cpu = nmos_cpu(0x99, CpuFlags::DECIMAL_MODE);
+
+    // When
+    cpu.adc(0x01);
+
+    // Then
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::NEGATIVE | CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_sbc_decimal_nmos_borrows() {
+    // Given
+    let mut cpu = nmos_cpu(0x00, CpuFlags::DECIMAL_MODE | CpuFlags::CARRY);
+
+    // When
+    cpu.sbc(0x01);
+
+    // Then
+    assert_eq!(0x99, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::NEGATIVE, cpu.registers.status);
+}
+
+#[test]
+fn test_adc_decimal_65c02_flags_come_from_result() {
+    // Given
+    let mut cpu = cmos_cpu(0x99, CpuFlags::DECIMAL_MODE);
+
+    // When
+    cpu.adc(0x01);
+
+    // Then
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::ZERO | CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_sbc_decimal_65c02_flags_come_from_result() {
+    // Given
+    let mut cpu = cmos_cpu(0x01, CpuFlags::DECIMAL_MODE | CpuFlags::CARRY);
+
+    // When
+    cpu.sbc(0x01);
+
+    // Then
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::ZERO | CpuFlags::CARRY, cpu.registers.status);
+
+    // When
+    cpu.sbc(0x01);
+
+    // Then
+    assert_eq!(0x99, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::DECIMAL_MODE | CpuFlags::NEGATIVE, cpu.registers.status);
+}
+
+/// Every valid BCD operand pair must give the decimal result, with N and Z matching it
+#[test]
+fn test_adc_sbc_decimal_65c02_exhaustive() {
+    let mut cpu = cmos_cpu(0, CpuFlags::empty());
+    for accumulator in (0..=0x99u8).filter(|value| from_bcd(*value).is_some()) {
+        for data in (0..=0x99u8).filter(|value| from_bcd(*value).is_some()) {
+            for carry in [false, true] {
+                let (a, b) = (from_bcd(accumulator).unwrap() as i16, from_bcd(data).unwrap() as i16);
+                let status = if carry { CpuFlags::DECIMAL_MODE | CpuFlags::CARRY } else { CpuFlags::DECIMAL_MODE };
+                let expected_sum = to_bcd(((a + b + carry as i16) % 100) as u8);
+                let expected_difference = to_bcd((a - b - !carry as i16).rem_euclid(100) as u8);
+
+                for (operation, expected) in [(Cpu::adc as fn(&mut Cpu, u8), expected_sum), (Cpu::sbc, expected_difference)] {
+                    // Given
+                    cpu.registers.accumulator = accumulator;
+                    cpu.registers.status = status;
+
+                    // When
+                    operation(&mut cpu, data);
+
+                    // Then
+                    assert_eq!(expected, cpu.registers.accumulator, "{:02X} {:02X} {}", accumulator, data, carry);
+                    assert_eq!(expected == 0, cpu.registers.status.contains(CpuFlags::ZERO), "{:02X} {:02X} {}", accumulator, data, carry);
+                    assert_eq!(expected & 0x80 != 0, cpu.registers.status.contains(CpuFlags::NEGATIVE), "{:02X} {:02X} {}", accumulator, data, carry);
+                }
+            }
+        }
+    }
+}
+
+/// Every valid BCD operand pair, with and without carry, must give the decimal sum.
+/// For every operand pair (valid or not), Z must match the binary sum. N and V must match a binary addition
+/// of the high digits, carrying in when the low digits add up to more than 9
+/// (appendix A in [Decimal Mode](http://www.6502.org/tutorials/decimal_mode.html#A))
+#[test]
+fn test_adc_decimal_nmos_exhaustive() {
+    let mut cpu = nmos_cpu(0, CpuFlags::empty());
+    let mut binary_cpu = Cpu::new();
+    for accumulator in 0..=0xFFu8 {
+        for data in 0..=0xFFu8 {
+            for carry in [false, true] {
+                // Given
+                let status = if carry { CpuFlags::DECIMAL_MODE | CpuFlags::CARRY } else { CpuFlags::DECIMAL_MODE };
+                cpu.registers.accumulator = accumulator;
+                cpu.registers.status = status;
+
+                // When
+                cpu.adc(data);
+
+                // Then
+                let binary_result = accumulator.wrapping_add(data).wrapping_add(carry as u8);
+                assert_eq!(binary_result == 0, cpu.registers.status.contains(CpuFlags::ZERO), "{:02X}+{:02X}+{}", accumulator, data, carry);
+
+                // $F in the low nibble turns the carry in into a carry to the high digits
+                let low_digit_carry = (accumulator & 0x0F) + (data & 0x0F) + carry as u8 > 0x09;
+                binary_cpu.registers.accumulator = accumulator | 0x0F;
+                binary_cpu.registers.status = if low_digit_carry { CpuFlags::CARRY } else { CpuFlags::empty() };
+                binary_cpu.adc(data & 0xF0);
+                let high_digits_flags = CpuFlags::NEGATIVE | CpuFlags::OVERFLOW;
+                assert_eq!(binary_cpu.registers.status & high_digits_flags, cpu.registers.status & high_digits_flags, "{:02X}+{:02X}+{}", accumulator, data, carry);
+                if let (Some(a), Some(b)) = (from_bcd(accumulator), from_bcd(data)) {
+                    let sum = a + b + carry as u8;
+                    assert_eq!(to_bcd(sum % 100), cpu.registers.accumulator, "{:02X}+{:02X}+{}", accumulator, data, carry);
+                    assert_eq!(sum > 99, cpu.registers.status.contains(CpuFlags::CARRY), "{:02X}+{:02X}+{}", accumulator, data, carry);
+                }
+            }
+        }
+    }
+}
+
+/// Every valid BCD operand pair, with and without borrow, must give the decimal difference.
+/// For every operand pair (valid or not), flags must match the binary subtraction
+#[test]
+fn test_sbc_decimal_nmos_exhaustive() {
+    let mut cpu = nmos_cpu(0, CpuFlags::empty());
+    let mut binary_cpu = Cpu::new();
+    for accumulator in 0..=0xFFu8 {
+        for data in 0..=0xFFu8 {
+            for carry in [false, true] {
+                // Given
+                let status = if carry { CpuFlags::DECIMAL_MODE | CpuFlags::CARRY } else { CpuFlags::DECIMAL_MODE };
+                cpu.registers.accumulator = accumulator;
+                cpu.registers.status = status;
+                binary_cpu.registers.accumulator = accumulator;
+                binary_cpu.registers.status = status;
+
+                // When
+                cpu.sbc(data);
+                binary_cpu.sbc(data);
+
+                // Then
+                assert_eq!(binary_cpu.registers.status, cpu.registers.status, "{:02X}-{:02X}-{}", accumulator, data, !carry as u8);
+                if let (Some(a), Some(b)) = (from_bcd(accumulator), from_bcd(data)) {
+                    let difference = a as i16 - b as i16 - !carry as i16;
+                    assert_eq!(to_bcd(difference.rem_euclid(100) as u8), cpu.registers.accumulator, "{:02X}-{:02X}-{}", accumulator, data, !carry as u8);
+                }
+            }
+        }
+    }
+}
+
+fn nmos_cpu(accumulator: u8, status: CpuFlags) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Nmos6502);
+    cpu.registers.accumulator = accumulator;
+    cpu.registers.status = status;
+    cpu
+}
+
+fn cmos_cpu(accumulator: u8, status: CpuFlags) -> Cpu {
+    let mut cpu = nmos_cpu(accumulator, status);
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu
+}
+
+/// Returns none when any nibble is not a decimal digit
+fn from_bcd(value: u8) -> Option<u8> {
+    let (high, low) = (value >> 4, value & 0x0F);
+    (high <= 9 && low <= 9).then_some(high * 10 + low)
+}
+
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn run_arithmetic_test<F, G, H>(
     operand_1: u8,
     operand_2: u8,
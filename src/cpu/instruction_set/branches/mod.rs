@@ -1,56 +1,65 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait Branches {
-    fn bcc(&mut self, data: u8);
-    fn bcs(&mut self, data: u8);
-    fn beq(&mut self, data: u8);
-    fn bmi(&mut self, address: u8);
-    fn bne(&mut self, address: u8);
-    fn bpl(&mut self, address: u8);
-    fn bvc(&mut self, address: u8);
-    fn bvs(&mut self, address: u8);
+    fn bcc(&mut self, data: u8) -> bool;
+    fn bcs(&mut self, data: u8) -> bool;
+    fn beq(&mut self, data: u8) -> bool;
+    fn bmi(&mut self, address: u8) -> bool;
+    fn bne(&mut self, address: u8) -> bool;
+    fn bpl(&mut self, address: u8) -> bool;
+    fn bvc(&mut self, address: u8) -> bool;
+    fn bvs(&mut self, address: u8) -> bool;
+    fn bra(&mut self, address: u8) -> bool;
 }
 
-impl Branches for Cpu {
-    fn bcc(&mut self, data: u8) {
-        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::CARRY));
+impl<B: Bus> Branches for Cpu<B> {
+    fn bcc(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::CARRY))
     }
 
-    fn bcs(&mut self, data: u8) {
-        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::CARRY));
+    fn bcs(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::CARRY))
     }
 
-    fn beq(&mut self, data: u8) {
-        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::ZERO));
+    fn beq(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::ZERO))
     }
 
-    fn bmi(&mut self, data: u8) {
-        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::NEGATIVE));
+    fn bmi(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::NEGATIVE))
     }
 
-    fn bne(&mut self, data: u8) {
-        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::ZERO));
+    fn bne(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::ZERO))
     }
 
-    fn bpl(&mut self, data: u8) {
-        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::NEGATIVE));
+    fn bpl(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::NEGATIVE))
     }
 
-    fn bvc(&mut self, data: u8) {
-        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::OVERFLOW));
+    fn bvc(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, !self.registers.status.contains(CpuFlags::OVERFLOW))
     }
 
-    fn bvs(&mut self, data: u8) {
-        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::OVERFLOW));
+    fn bvs(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, self.registers.status.contains(CpuFlags::OVERFLOW))
+    }
+
+    /// Implementation of BRA (Branch Always) instruction (65C02)
+    fn bra(&mut self, data: u8) -> bool {
+        branch_if_condition(self, data, true)
     }
 }
 
-fn branch_if_condition(cpu: &mut Cpu, data: u8, condition: bool) {
+/// Returns true if the branch was taken
+fn branch_if_condition<B: Bus>(cpu: &mut Cpu<B>, data: u8, condition: bool) -> bool {
     let displacement = data as i8;
     if condition {
         cpu.registers.program_counter = cpu.registers.program_counter.wrapping_add(displacement as u16);
     }
+    condition
 }
 
 #[cfg(test)]
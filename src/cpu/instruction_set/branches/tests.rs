@@ -240,15 +240,16 @@ fn test_bvs_do_not_branch() {
         Cpu::bvs);
 }
 
-fn test_branch(initial_pc: u16, displacement: u8, status: CpuFlags, expected_pc: u16, operation: fn(&mut Cpu, u8)) {
+fn test_branch(initial_pc: u16, displacement: u8, status: CpuFlags, expected_pc: u16, operation: fn(&mut Cpu, u8) -> bool) {
     // Given
     let mut cpu = Cpu::new();
     cpu.registers.status = status;
     cpu.registers.program_counter = initial_pc;
 
     // When
-    operation(&mut cpu, displacement);
+    let taken = operation(&mut cpu, displacement);
 
     // Then
     assert_eq!(cpu.registers.program_counter, expected_pc);
+    assert_eq!(taken, expected_pc != initial_pc);
 }
\ No newline at end of file
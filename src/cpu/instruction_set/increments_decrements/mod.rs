@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait IncrementsDecrements {
@@ -8,9 +9,11 @@ pub trait IncrementsDecrements {
     fn dec(&mut self, address: u16);
     fn dex(&mut self);
     fn dey(&mut self);
+    fn inc_accumulator(&mut self);
+    fn dec_accumulator(&mut self);
 }
 
-impl IncrementsDecrements for Cpu {
+impl<B: Bus> IncrementsDecrements for Cpu<B> {
     /// Implementation of INC (Increment Memory) instruction
     fn inc(&mut self, address: u16) {
         let val = self.memory.read(address).wrapping_add(0x01);
@@ -48,9 +51,21 @@ impl IncrementsDecrements for Cpu {
         self.registers.y_register = self.registers.y_register.wrapping_add(0xff);
         update_flag(self, self.registers.y_register);
     }
+
+    /// Implementation of INC A (Increment Accumulator) instruction (65C02)
+    fn inc_accumulator(&mut self) {
+        self.registers.accumulator = self.registers.accumulator.wrapping_add(0x01);
+        update_flag(self, self.registers.accumulator);
+    }
+
+    /// Implementation of DEC A (Decrement Accumulator) instruction (65C02)
+    fn dec_accumulator(&mut self) {
+        self.registers.accumulator = self.registers.accumulator.wrapping_add(0xff);
+        update_flag(self, self.registers.accumulator);
+    }
 }
 
-fn update_flag(cpu: &mut Cpu, val: u8) {
+fn update_flag<B: Bus>(cpu: &mut Cpu<B>, val: u8) {
     cpu.registers.status.set(CpuFlags::ZERO, val == 0);
     cpu.registers.status.set(CpuFlags::NEGATIVE, val & 0x80 == 0x80);
 }
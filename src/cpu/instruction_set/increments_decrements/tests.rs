@@ -6,8 +6,8 @@ fn test_inc() {
         0x42,
         0x10,
         0x43,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.inc(address),
         CpuFlags::empty());
 }
@@ -42,8 +42,8 @@ fn test_dec() {
         0x42,
         0x10,
         0x41,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.dec(address),
         CpuFlags::empty());
 }
@@ -78,8 +78,8 @@ fn test_inc_zero_flag() {
         0xFF,
         0x10,
         0x00,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.inc(address),
         CpuFlags::ZERO);
 }
@@ -90,8 +90,8 @@ fn test_inc_negative_flag() {
         0x7F,
         0x10,
         0x80,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.inc(address),
         CpuFlags::NEGATIVE);
 }
@@ -102,8 +102,8 @@ fn test_dec_zero_flag() {
         0x01,
         0x10,
         0x00,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.dec(address),
         CpuFlags::ZERO);
 }
@@ -114,8 +114,8 @@ fn test_dec_negative_flag() {
         0x00,
         0x10,
         0xFF,
-        |cpu, address, value| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, address, value| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.dec(address),
         CpuFlags::NEGATIVE);
 }
@@ -1,3 +1,4 @@
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 use crate::cpu::register_bank::STACK_POINTER_BASE_ADDRESS;
 
@@ -7,7 +8,7 @@ pub trait JumpsCalls {
     fn rts(&mut self);
 }
 
-impl JumpsCalls for Cpu {
+impl<B: Bus> JumpsCalls for Cpu<B> {
     fn jmp(&mut self, address: u16) {
         self.registers.program_counter = address;
     }
@@ -17,7 +18,7 @@ impl JumpsCalls for Cpu {
         // The idea is to discount the instruction byte (by this time, instruction was already read and PC incremented)
         // and also one more byte to point to next instruction -1 (according to specs)
         // So, in practice, we are going to point to the last byte of the current instruction
-        let address_to_push: u16 = self.registers.program_counter + bytes_to_skip as u16 - 2;
+        let address_to_push: u16 = self.registers.program_counter.wrapping_add(bytes_to_skip as u16).wrapping_sub(2);
         let address_stack_top = STACK_POINTER_BASE_ADDRESS + (self.registers.stack_pointer as u16);
         let address_stack_top_minus_1 = STACK_POINTER_BASE_ADDRESS + (self.registers.stack_pointer.wrapping_sub(1) as u16);
         self.memory.write(((address_to_push & 0xFF00) >> 8) as u8, address_stack_top);
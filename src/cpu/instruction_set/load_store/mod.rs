@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait LoadStore {
@@ -8,9 +9,10 @@ pub trait LoadStore {
     fn sta(&mut self, address: u16);
     fn stx(&mut self, address: u16);
     fn sty(&mut self, address: u16);
+    fn stz(&mut self, address: u16);
 }
 
-impl LoadStore for Cpu {
+impl<B: Bus> LoadStore for Cpu<B> {
 
     fn lda(&mut self, data: u8) {
         self.registers.accumulator = data;
@@ -41,6 +43,11 @@ impl LoadStore for Cpu {
     fn sty(&mut self, address: u16) {
         self.memory.write(self.registers.y_register, address);
     }
+
+    /// Implementation of STZ (Store Zero) instruction (65C02)
+    fn stz(&mut self, address: u16) {
+        self.memory.write(0x00, address);
+    }
 }
 
 #[cfg(test)]
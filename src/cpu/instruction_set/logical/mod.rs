@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait Logical {
@@ -6,9 +7,12 @@ pub trait Logical {
     fn ora(&mut self, data: u8);
     fn eor(&mut self, data: u8);
     fn bit(&mut self, data: u8);
+    fn bit_immediate(&mut self, data: u8);
+    fn trb(&mut self, address: u16);
+    fn tsb(&mut self, address: u16);
 }
 
-impl Logical for Cpu {
+impl<B: Bus> Logical for Cpu<B> {
     /// Implementation of AND (Logical AND) instruction
     /// Bitwise AND of the accumulator with the operand
     fn and(&mut self, data: u8) {
@@ -77,6 +81,34 @@ impl Logical for Cpu {
             .status
             .set(CpuFlags::OVERFLOW, data & 0x40 == 0x40);
     }
+
+    /// Implementation of BIT #imm (65C02)
+    /// Only the Zero flag is updated, N and V are left untouched
+    fn bit_immediate(&mut self, data: u8) {
+        self.registers
+            .status
+            .set(CpuFlags::ZERO, self.registers.accumulator & data == 0);
+    }
+
+    /// Implementation of TRB (Test and Reset Bits) instruction (65C02)
+    /// Sets Zero like BIT, then clears in memory the bits set in the accumulator
+    fn trb(&mut self, address: u16) {
+        let data = self.memory.read(address);
+        self.registers
+            .status
+            .set(CpuFlags::ZERO, self.registers.accumulator & data == 0);
+        self.memory.write(data & !self.registers.accumulator, address);
+    }
+
+    /// Implementation of TSB (Test and Set Bits) instruction (65C02)
+    /// Sets Zero like BIT, then sets in memory the bits set in the accumulator
+    fn tsb(&mut self, address: u16) {
+        let data = self.memory.read(address);
+        self.registers
+            .status
+            .set(CpuFlags::ZERO, self.registers.accumulator & data == 0);
+        self.memory.write(data | self.registers.accumulator, address);
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +110,50 @@ fn test_ora_flag_negative() {
     );
 }
 
+#[test]
+fn test_bit_immediate_only_updates_zero() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.accumulator = 0x0F;
+    cpu.registers.status = CpuFlags::NEGATIVE;
+
+    // When
+    cpu.bit_immediate(0xF0);
+
+    // Then
+    assert_eq!(CpuFlags::NEGATIVE | CpuFlags::ZERO, cpu.registers.status);
+}
+
+#[test]
+fn test_trb_clears_accumulator_bits_in_memory() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.accumulator = 0x0F;
+    cpu.memory.write(0x3C, 0x0010);
+
+    // When
+    cpu.trb(0x0010);
+
+    // Then
+    assert_eq!(0x30, cpu.memory.read(0x0010));
+    assert!(!cpu.registers.status.contains(CpuFlags::ZERO));
+}
+
+#[test]
+fn test_tsb_sets_accumulator_bits_in_memory() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.accumulator = 0x0F;
+    cpu.memory.write(0x30, 0x0010);
+
+    // When
+    cpu.tsb(0x0010);
+
+    // Then
+    assert_eq!(0x3F, cpu.memory.read(0x0010));
+    assert!(cpu.registers.status.contains(CpuFlags::ZERO));
+}
+
 fn run_logical_test<F>(operand_1: u8, operand_2: u8, initial_status: CpuFlags, expected_result: u8, expected_status: CpuFlags, operation: F)
 where
     F: Fn(&mut Cpu, u8),
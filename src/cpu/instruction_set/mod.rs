@@ -8,4 +8,4 @@ pub mod increments_decrements;
 pub mod shifts;
 pub mod jumps_calls;
 pub mod stack_operations;
-pub mod system_functions;
\ No newline at end of file
+pub mod system_functions;pub mod unofficial;
@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait RegisterTransfers {
@@ -10,7 +11,7 @@ pub trait RegisterTransfers {
     fn txs(&mut self);
 }
 
-impl RegisterTransfers for Cpu {
+impl<B: Bus> RegisterTransfers for Cpu<B> {
 
     fn tax(&mut self) {
         self.registers.x_register = self.registers.accumulator;
@@ -43,7 +44,7 @@ impl RegisterTransfers for Cpu {
     }
 }
 
-fn raise_flags(cpu: &mut Cpu, value: u8) {
+fn raise_flags<B: Bus>(cpu: &mut Cpu<B>, value: u8) {
     cpu.registers.status.set(CpuFlags::ZERO, value == 0);
     cpu.registers.status.set(CpuFlags::NEGATIVE, value & 0x80 == 0x80);
 }
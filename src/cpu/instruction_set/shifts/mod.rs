@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait Shifts {
@@ -12,7 +13,7 @@ pub trait Shifts {
     fn ror_accumulator(&mut self);
 }
 
-impl Shifts for Cpu {
+impl<B: Bus> Shifts for Cpu<B> {
     fn asl(&mut self, address: u16) {
         let val = self.memory.read(address);
         let has_carry = val & 0x80 != 0;
@@ -78,7 +79,7 @@ impl Shifts for Cpu {
     }
 }
 
-fn update_flags(val: u8, has_carry: bool, cpu: &mut Cpu) {
+fn update_flags<B: Bus>(val: u8, has_carry: bool, cpu: &mut Cpu<B>) {
     cpu.registers.status.set(CpuFlags::CARRY, has_carry);
     cpu.registers.status.set(CpuFlags::ZERO, val == 0);
     cpu.registers.status.set(CpuFlags::NEGATIVE, val & 0x80 != 0);
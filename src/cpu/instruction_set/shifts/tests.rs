@@ -6,8 +6,8 @@ fn test_asl() {
         0x10,
         0x4,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.asl(address),
         CpuFlags::empty());
 }
@@ -19,8 +19,8 @@ fn test_asl_flag_carry() {
         0x10,
         0x02,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.asl(address),
         CpuFlags::CARRY);
 }
@@ -32,8 +32,8 @@ fn test_asl_flag_zero() {
         0x10,
         0x00,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.asl(address),
         CpuFlags::ZERO);
 }
@@ -45,8 +45,8 @@ fn test_asl_flag_negative() {
         0x10,
         0x80,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.asl(address),
         CpuFlags::NEGATIVE);
 }
@@ -110,8 +110,8 @@ fn test_lsr() {
         0x10,
         0x01,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.lsr(address),
         CpuFlags::empty());
 }
@@ -123,8 +123,8 @@ fn test_lsr_flag_carry() {
         0x10,
         0x01,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.lsr(address),
         CpuFlags::CARRY);
 }
@@ -136,8 +136,8 @@ fn test_lsr_flag_zero() {
         0x10,
         0x00,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.lsr(address),
         CpuFlags::ZERO);
 }
@@ -188,8 +188,8 @@ fn test_rol() {
         0x10,
         0x02,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.rol(address),
         CpuFlags::empty());
 }
@@ -201,8 +201,8 @@ fn test_rol_with_carry() {
         0x10,
         0x03,
         CpuFlags::CARRY,
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.rol(address),
         CpuFlags::empty());
 }
@@ -214,8 +214,8 @@ fn test_rol_flag_carry() {
         0x10,
         0x02,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.rol(address),
         CpuFlags::CARRY);
 }
@@ -227,8 +227,8 @@ fn test_rol_flag_zero() {
         0x10,
         0x00,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.rol(address),
         CpuFlags::ZERO);
 }
@@ -240,8 +240,8 @@ fn test_rol_flag_negative() {
         0x10,
         0x80,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.rol(address),
         CpuFlags::NEGATIVE);
 }
@@ -305,8 +305,8 @@ fn test_ror() {
         0x10,
         0x01,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.ror(address),
         CpuFlags::empty());
 }
@@ -318,8 +318,8 @@ fn test_ror_with_carry() {
         0x10,
         0x80,
         CpuFlags::CARRY,
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.ror(address),
         CpuFlags::NEGATIVE);
 }
@@ -331,8 +331,8 @@ fn test_ror_flag_carry() {
         0x10,
         0x02,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.ror(address),
         CpuFlags::CARRY);
 }
@@ -344,8 +344,8 @@ fn test_ror_flag_zero() {
         0x10,
         0x00,
         CpuFlags::empty(),
-        |cpu, value, address| cpu.memory.write(value, address as u16),
-        |cpu, address| cpu.memory.read(address as u16),
+        |cpu, value, address| cpu.memory.write(value, address),
+        |cpu, address| cpu.memory.read(address),
         |cpu, address| cpu.ror(address),
         CpuFlags::ZERO);
 }
@@ -389,6 +389,7 @@ fn test_ror_accumulator_flag_zero() {
         CpuFlags::ZERO);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn test_shift<F, G, H>(initial_value: u8, address: u16, expected_value: u8, initial_status: CpuFlags, set_value: F, get_value: G, operation: H, expected_status: CpuFlags) 
 where 
     F: Fn(&mut Cpu, u8, u16),
@@ -1,5 +1,6 @@
 use crate::cpu::register_bank::STACK_POINTER_BASE_ADDRESS;
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait StackOperations {
@@ -7,9 +8,13 @@ pub trait StackOperations {
     fn php(&mut self);
     fn pla(&mut self);
     fn plp(&mut self);
+    fn phx(&mut self);
+    fn phy(&mut self);
+    fn plx(&mut self);
+    fn ply(&mut self);
 }
 
-impl StackOperations for Cpu {
+impl<B: Bus> StackOperations for Cpu<B> {
 
     fn pha(&mut self) {
         self.memory.write(self.registers.accumulator,
@@ -17,8 +22,11 @@ impl StackOperations for Cpu {
         self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
     }
 
+    /// Implementation of PHP (Push Processor Status) instruction
+    /// The pushed copy has the BREAK and UNUSED flags set
     fn php(&mut self) {
-        self.memory.write(self.registers.status.bits(),
+        let status = self.registers.status | CpuFlags::BREAK | CpuFlags::UNUSED;
+        self.memory.write(status.bits(),
             STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16);
         self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
     }
@@ -29,13 +37,45 @@ impl StackOperations for Cpu {
         raise_flags(self, self.registers.accumulator);
     }
 
+    /// Implementation of PLP (Pull Processor Status) instruction
+    /// The BREAK flag only exists in the pushed copy, so it is ignored. UNUSED always reads as 1
     fn plp(&mut self) {
         self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(1);
         self.registers.status = CpuFlags::from_bits_truncate(self.memory.read(STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16));
+        self.registers.status.remove(CpuFlags::BREAK);
+        self.registers.status.insert(CpuFlags::UNUSED);
+    }
+
+    /// Implementation of PHX (Push X Register) instruction (65C02)
+    fn phx(&mut self) {
+        self.memory.write(self.registers.x_register,
+            STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16);
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+    }
+
+    /// Implementation of PHY (Push Y Register) instruction (65C02)
+    fn phy(&mut self) {
+        self.memory.write(self.registers.y_register,
+            STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16);
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_sub(1);
+    }
+
+    /// Implementation of PLX (Pull X Register) instruction (65C02)
+    fn plx(&mut self) {
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(1);
+        self.registers.x_register = self.memory.read(STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16);
+        raise_flags(self, self.registers.x_register);
+    }
+
+    /// Implementation of PLY (Pull Y Register) instruction (65C02)
+    fn ply(&mut self) {
+        self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(1);
+        self.registers.y_register = self.memory.read(STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16);
+        raise_flags(self, self.registers.y_register);
     }
 }
 
-fn raise_flags(cpu: &mut Cpu, value: u8) {
+fn raise_flags<B: Bus>(cpu: &mut Cpu<B>, value: u8) {
     cpu.registers.status.set(CpuFlags::ZERO, value == 0);
     cpu.registers.status.set(CpuFlags::NEGATIVE, value & 0x80 == 0x80);
 }
@@ -18,13 +18,14 @@ fn test_pha() {
 fn test_php() {
     // Given
     let mut cpu = Cpu::new();
-    cpu.registers.status = CpuFlags::BREAK | CpuFlags::CARRY;
+    cpu.registers.status = CpuFlags::CARRY;
 
     // When
     cpu.php();
 
     // Then
-    assert_eq!(cpu.memory.read(0x01FF), cpu.registers.status.bits());
+    assert_eq!(cpu.memory.read(0x01FF), (CpuFlags::BREAK | CpuFlags::UNUSED | CpuFlags::CARRY).bits());
+    assert_eq!(cpu.registers.status, CpuFlags::CARRY);
     assert_eq!(cpu.registers.stack_pointer, 0xFE);
 }
 
@@ -50,14 +51,44 @@ fn test_pla() {
 fn test_plp() {
     // Given
     let mut cpu = Cpu::new();
-    let expected_status = CpuFlags::BREAK | CpuFlags::CARRY;
-    cpu.memory.write(expected_status.bits(), 0x01FF);
+    cpu.memory.write((CpuFlags::BREAK | CpuFlags::CARRY).bits(), 0x01FF);
     cpu.registers.stack_pointer = 0xFE;
 
     // When
     cpu.plp();
 
     // Then
-    assert_eq!(cpu.memory.read(0x01FF), cpu.registers.status.bits());
+    assert_eq!(CpuFlags::UNUSED | CpuFlags::CARRY, cpu.registers.status);
     assert_eq!(cpu.registers.stack_pointer, 0xFF);
-}
\ No newline at end of file
+}
+#[test]
+fn test_phx_and_ply() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.x_register = 0x80;
+
+    // When
+    cpu.phx();
+    cpu.ply();
+
+    // Then
+    assert_eq!(cpu.registers.y_register, 0x80);
+    assert_eq!(cpu.registers.stack_pointer, 0xFF);
+    assert!(cpu.registers.status.contains(CpuFlags::NEGATIVE));
+}
+
+#[test]
+fn test_phy_and_plx() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.x_register = 0x42;
+
+    // When
+    cpu.phy();
+    cpu.plx();
+
+    // Then
+    assert_eq!(cpu.registers.x_register, 0x00);
+    assert_eq!(cpu.registers.stack_pointer, 0xFF);
+    assert!(cpu.registers.status.contains(CpuFlags::ZERO));
+}
@@ -1,4 +1,5 @@
 use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
 
 pub trait StatusFlagChange {
@@ -11,7 +12,7 @@ pub trait StatusFlagChange {
     fn sei(&mut self);
 }
 
-impl StatusFlagChange for Cpu {
+impl<B: Bus> StatusFlagChange for Cpu<B> {
     fn clc(&mut self) {
         self.registers.status.remove(CpuFlags::CARRY);
     }
@@ -1,6 +1,7 @@
 use crate::cpu::register_bank::STACK_POINTER_BASE_ADDRESS;
+use crate::bus::Bus;
 use crate::cpu::Cpu;
-use crate::cpu::types::CpuFlags;
+use crate::cpu::types::{CpuFlags, CpuVariant, IRQ_VECTOR};
 
 pub trait SystemFunctions {
     fn brk(&mut self);
@@ -8,15 +9,22 @@ pub trait SystemFunctions {
     // As NOP doesn't do anything, I'll not add it
 }
 
-impl SystemFunctions for Cpu {
+impl<B: Bus> SystemFunctions for Cpu<B> {
+    /// Implementation of BRK (Force Interrupt) instruction
+    /// It assumes that program_counter (PC) was already incremented after opcode parsing.
+    /// BRK has a padding byte after the opcode, so the return address pushed is PC + 1 (i.e. opcode address + 2)
     fn brk(&mut self) {
-        self.registers.status.insert(CpuFlags::BREAK);
+        let return_address = self.registers.program_counter.wrapping_add(1);
+        interrupt(self, return_address, IRQ_VECTOR, true);
     }
 
     fn rti(&mut self) {
         
         self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(1);
         self.registers.status = CpuFlags::from_bits_truncate(self.memory.read(STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16));
+        // The BREAK flag only exists in the copy of the status pushed onto the stack
+        self.registers.status.remove(CpuFlags::BREAK);
+        self.registers.status.insert(CpuFlags::UNUSED);
         
         self.registers.stack_pointer = self.registers.stack_pointer.wrapping_add(1);
         let low_byte = self.memory.read(STACK_POINTER_BASE_ADDRESS + self.registers.stack_pointer as u16) as u16;
@@ -28,5 +36,30 @@ impl SystemFunctions for Cpu {
     }
 }
 
+/// Interrupt sequence shared by BRK, IRQ and NMI:
+/// pushes the return address and the status register onto the stack,
+/// disables interrupts and jumps to the address stored in the given vector.
+/// The pushed status has the BREAK flag set only when the interrupt comes from a BRK instruction.
+/// The 65C02 also clears the DECIMAL flag, so handlers always start in binary mode.
+pub(crate) fn interrupt<B: Bus>(cpu: &mut Cpu<B>, return_address: u16, vector: u16, from_brk: bool) {
+    push(cpu, (return_address >> 8) as u8);
+    push(cpu, (return_address & 0x00FF) as u8);
+
+    let mut status = cpu.registers.status | CpuFlags::UNUSED;
+    status.set(CpuFlags::BREAK, from_brk);
+    push(cpu, status.bits());
+
+    cpu.registers.status.insert(CpuFlags::INTERRUPT_DISABLE);
+    if cpu.variant == CpuVariant::Cmos65C02 {
+        cpu.registers.status.remove(CpuFlags::DECIMAL_MODE);
+    }
+    cpu.registers.program_counter = cpu.memory.read_u16(vector);
+}
+
+fn push<B: Bus>(cpu: &mut Cpu<B>, data: u8) {
+    cpu.memory.write(data, STACK_POINTER_BASE_ADDRESS + cpu.registers.stack_pointer as u16);
+    cpu.registers.stack_pointer = cpu.registers.stack_pointer.wrapping_sub(1);
+}
+
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
@@ -4,19 +4,49 @@ use super::*;
 fn test_brk() {
     // Given
     let mut cpu = Cpu::new();
+    let initial_status = CpuFlags::CARRY | CpuFlags::NEGATIVE;
+    cpu.registers.status = initial_status;
+    // PC already incremented after reading BRK opcode at 0x8000
+    cpu.registers.program_counter = 0x8001;
+    cpu.memory.write(0x34, 0xFFFE);
+    cpu.memory.write(0x12, 0xFFFF);
 
     // When
     cpu.brk();
 
     // Then
-    assert!(cpu.registers.status.contains(CpuFlags::BREAK));
+    assert_eq!(cpu.registers.program_counter, 0x1234);
+    assert_eq!(cpu.registers.stack_pointer, 0xFC);
+    assert_eq!(cpu.memory.read(0x01FF), 0x80);
+    assert_eq!(cpu.memory.read(0x01FE), 0x02);
+    assert_eq!(cpu.memory.read(0x01FD), (initial_status | CpuFlags::BREAK | CpuFlags::UNUSED).bits());
+    assert!(cpu.registers.status.contains(CpuFlags::INTERRUPT_DISABLE));
+    assert!(!cpu.registers.status.contains(CpuFlags::BREAK));
+}
+
+#[test]
+fn test_interrupt_does_not_push_break_flag() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.status = CpuFlags::ZERO;
+    cpu.memory.write(0x00, 0xFFFA);
+    cpu.memory.write(0x90, 0xFFFB);
+
+    // When
+    interrupt(&mut cpu, 0xC123, 0xFFFA, false);
+
+    // Then
+    assert_eq!(cpu.registers.program_counter, 0x9000);
+    assert_eq!(cpu.memory.read(0x01FF), 0xC1);
+    assert_eq!(cpu.memory.read(0x01FE), 0x23);
+    assert_eq!(cpu.memory.read(0x01FD), (CpuFlags::ZERO | CpuFlags::UNUSED).bits());
 }
 
 #[test]
 fn test_rti() {
     // Given
     let mut cpu = Cpu::new();
-    let expected_status = CpuFlags::CARRY | CpuFlags::ZERO | CpuFlags::OVERFLOW;
+    let expected_status = CpuFlags::CARRY | CpuFlags::ZERO | CpuFlags::UNUSED | CpuFlags::OVERFLOW;
     let expected_pc: u16 = 0x810F;
     cpu.memory.write(0x81, 0x01FF);
     cpu.memory.write(0x0F, 0x01FE);
@@ -29,4 +59,18 @@ fn test_rti() {
     // Then
     assert_eq!(expected_pc, cpu.registers.program_counter);
     assert_eq!(expected_status, cpu.registers.status);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_rti_ignores_break_flag() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.memory.write((CpuFlags::BREAK | CpuFlags::CARRY).bits(), 0x01FD);
+    cpu.registers.stack_pointer = 0xFC;
+
+    // When
+    cpu.rti();
+
+    // Then
+    assert_eq!(CpuFlags::CARRY | CpuFlags::UNUSED, cpu.registers.status);
+}
@@ -0,0 +1,196 @@
+use crate::cpu::types::CpuFlags;
+use crate::bus::Bus;
+use crate::cpu::{is_different_page, Cpu};
+
+use super::arithmetic::Arithmetic;
+use super::logical::Logical;
+use super::shifts::Shifts;
+
+/// Constant ORed with the accumulator by ANE and LXA. It depends on the chip and its temperature,
+/// $EE is the value most commonly observed
+const ANE_MAGIC: u8 = 0xEE;
+
+/// Unofficial (illegal) instructions of the NMOS 6502 and the 2A03.
+/// Most of them combine 2 official instructions sharing the same addressing mode
+///
+/// see: [Unofficial opcodes](https://www.nesdev.org/wiki/Programming_with_unofficial_opcodes)
+pub trait Unofficial {
+    fn slo(&mut self, address: u16);
+    fn rla(&mut self, address: u16);
+    fn sre(&mut self, address: u16);
+    fn rra(&mut self, address: u16);
+    fn sax(&mut self, address: u16);
+    fn lax(&mut self, data: u8);
+    fn dcp(&mut self, address: u16);
+    fn isc(&mut self, address: u16);
+    fn anc(&mut self, data: u8);
+    fn alr(&mut self, data: u8);
+    fn arr(&mut self, data: u8);
+    fn sbx(&mut self, data: u8);
+    fn las(&mut self, data: u8);
+    fn ane(&mut self, data: u8);
+    fn lxa(&mut self, data: u8);
+    fn sha(&mut self, address: u16);
+    fn shx(&mut self, address: u16);
+    fn shy(&mut self, address: u16);
+    fn tas(&mut self, address: u16);
+}
+
+impl<B: Bus> Unofficial for Cpu<B> {
+    /// Implementation of SLO (ASL + ORA) instruction
+    fn slo(&mut self, address: u16) {
+        let value = self.memory.read(address);
+        let result = value << 1;
+        self.memory.write(result, address);
+        self.registers.status.set(CpuFlags::CARRY, value & 0x80 != 0);
+        self.ora(result);
+    }
+
+    /// Implementation of RLA (ROL + AND) instruction
+    fn rla(&mut self, address: u16) {
+        let value = self.memory.read(address);
+        let result = value << 1 | self.registers.status.contains(CpuFlags::CARRY) as u8;
+        self.memory.write(result, address);
+        self.registers.status.set(CpuFlags::CARRY, value & 0x80 != 0);
+        self.and(result);
+    }
+
+    /// Implementation of SRE (LSR + EOR) instruction
+    fn sre(&mut self, address: u16) {
+        let value = self.memory.read(address);
+        let result = value >> 1;
+        self.memory.write(result, address);
+        self.registers.status.set(CpuFlags::CARRY, value & 0x01 != 0);
+        self.eor(result);
+    }
+
+    /// Implementation of RRA (ROR + ADC) instruction. ADC uses the carry shifted out by ROR
+    fn rra(&mut self, address: u16) {
+        let value = self.memory.read(address);
+        let result = value >> 1 | (self.registers.status.contains(CpuFlags::CARRY) as u8) << 7;
+        self.memory.write(result, address);
+        self.registers.status.set(CpuFlags::CARRY, value & 0x01 != 0);
+        self.adc(result);
+    }
+
+    /// Implementation of SAX (Store A AND X) instruction. Flags are not affected
+    fn sax(&mut self, address: u16) {
+        self.memory.write(self.registers.accumulator & self.registers.x_register, address);
+    }
+
+    /// Implementation of LAX (LDA + LDX) instruction
+    fn lax(&mut self, data: u8) {
+        self.registers.accumulator = data;
+        self.registers.x_register = data;
+        update_flags(self, data);
+    }
+
+    /// Implementation of DCP (DEC + CMP) instruction
+    fn dcp(&mut self, address: u16) {
+        let result = self.memory.read(address).wrapping_sub(1);
+        self.memory.write(result, address);
+        self.cmp(result);
+    }
+
+    /// Implementation of ISC (INC + SBC) instruction
+    fn isc(&mut self, address: u16) {
+        let result = self.memory.read(address).wrapping_add(1);
+        self.memory.write(result, address);
+        self.sbc(result);
+    }
+
+    /// Implementation of ANC (AND, then copy N to C) instruction
+    fn anc(&mut self, data: u8) {
+        self.and(data);
+        let negative = self.registers.status.contains(CpuFlags::NEGATIVE);
+        self.registers.status.set(CpuFlags::CARRY, negative);
+    }
+
+    /// Implementation of ALR (AND + LSR A) instruction
+    fn alr(&mut self, data: u8) {
+        self.and(data);
+        self.lsr_accumulator();
+    }
+
+    /// Implementation of ARR (AND + ROR A) instruction.
+    /// C is bit 6 of the result and V is bit 6 XOR bit 5
+    fn arr(&mut self, data: u8) {
+        let value = self.registers.accumulator & data;
+        let result = value >> 1 | (self.registers.status.contains(CpuFlags::CARRY) as u8) << 7;
+        self.registers.accumulator = result;
+        update_flags(self, result);
+        self.registers.status.set(CpuFlags::CARRY, result & 0x40 != 0);
+        self.registers.status.set(CpuFlags::OVERFLOW, (result >> 6 ^ result >> 5) & 0x01 != 0);
+    }
+
+    /// Implementation of SBX (X = A AND X - data) instruction.
+    /// Flags are set as in CMP, the carry is ignored
+    fn sbx(&mut self, data: u8) {
+        let value = self.registers.accumulator & self.registers.x_register;
+        let result = value.wrapping_sub(data);
+        self.registers.x_register = result;
+        self.registers.status.set(CpuFlags::CARRY, value >= data);
+        update_flags(self, result);
+    }
+
+    /// Implementation of LAS (A, X and SP = data AND SP) instruction
+    fn las(&mut self, data: u8) {
+        let result = data & self.registers.stack_pointer;
+        self.registers.accumulator = result;
+        self.registers.x_register = result;
+        self.registers.stack_pointer = result;
+        update_flags(self, result);
+    }
+
+    /// Implementation of ANE (XAA) instruction. Unstable: A = (A OR magic) AND X AND data
+    fn ane(&mut self, data: u8) {
+        let result = (self.registers.accumulator | ANE_MAGIC) & self.registers.x_register & data;
+        self.registers.accumulator = result;
+        update_flags(self, result);
+    }
+
+    /// Implementation of LXA (LAX immediate) instruction. Unstable: A = X = (A OR magic) AND data
+    fn lxa(&mut self, data: u8) {
+        let result = (self.registers.accumulator | ANE_MAGIC) & data;
+        self.lax(result);
+    }
+
+    /// Implementation of SHA (AHX) instruction. Unstable: stores A AND X AND (high byte + 1)
+    fn sha(&mut self, address: u16) {
+        let value = self.registers.accumulator & self.registers.x_register;
+        store_and_high_byte(self, value, address, self.registers.y_register);
+    }
+
+    /// Implementation of SHX instruction. Unstable: stores X AND (high byte + 1)
+    fn shx(&mut self, address: u16) {
+        store_and_high_byte(self, self.registers.x_register, address, self.registers.y_register);
+    }
+
+    /// Implementation of SHY instruction. Unstable: stores Y AND (high byte + 1)
+    fn shy(&mut self, address: u16) {
+        store_and_high_byte(self, self.registers.y_register, address, self.registers.x_register);
+    }
+
+    /// Implementation of TAS (SHS) instruction. Unstable: SP = A AND X, then stores SP AND (high byte + 1)
+    fn tas(&mut self, address: u16) {
+        self.registers.stack_pointer = self.registers.accumulator & self.registers.x_register;
+        store_and_high_byte(self, self.registers.stack_pointer, address, self.registers.y_register);
+    }
+}
+
+fn update_flags<B: Bus>(cpu: &mut Cpu<B>, value: u8) {
+    cpu.registers.status.set(CpuFlags::ZERO, value == 0);
+    cpu.registers.status.set(CpuFlags::NEGATIVE, value & 0x80 == 0x80);
+}
+
+/// Stores the value ANDed with the high byte of the base address + 1.
+/// When indexing crosses a page, the stored value also replaces the high byte of the target address
+fn store_and_high_byte<B: Bus>(cpu: &mut Cpu<B>, value: u8, address: u16, index: u8) {
+    let base = address.wrapping_sub(index as u16);
+    let data = value & ((base >> 8) as u8).wrapping_add(1);
+    let target = if is_different_page(base, address) { (data as u16) << 8 | address & 0x00FF } else { address };
+    cpu.memory.write(data, target);
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,279 @@
+use super::*;
+
+const ADDRESS: u16 = 0x0010;
+
+fn cpu_with(accumulator: u8, x_register: u8, status: CpuFlags) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.registers.accumulator = accumulator;
+    cpu.registers.x_register = x_register;
+    cpu.registers.status = status;
+    cpu
+}
+
+#[test]
+fn test_slo_shifts_memory_and_ors_accumulator() {
+    // Given
+    let mut cpu = cpu_with(0x01, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x81, ADDRESS);
+
+    // When
+    cpu.slo(ADDRESS);
+
+    // Then
+    assert_eq!(0x02, cpu.memory.read(ADDRESS));
+    assert_eq!(0x03, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_rla_rotates_memory_and_ands_accumulator() {
+    // Given
+    let mut cpu = cpu_with(0xFF, 0x00, CpuFlags::CARRY);
+    cpu.memory.write(0x40, ADDRESS);
+
+    // When
+    cpu.rla(ADDRESS);
+
+    // Then
+    assert_eq!(0x81, cpu.memory.read(ADDRESS));
+    assert_eq!(0x81, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::NEGATIVE, cpu.registers.status);
+}
+
+#[test]
+fn test_sre_shifts_memory_and_xors_accumulator() {
+    // Given
+    let mut cpu = cpu_with(0x01, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x03, ADDRESS);
+
+    // When
+    cpu.sre(ADDRESS);
+
+    // Then
+    assert_eq!(0x01, cpu.memory.read(ADDRESS));
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::CARRY | CpuFlags::ZERO, cpu.registers.status);
+}
+
+#[test]
+fn test_rra_adds_with_carry_shifted_out() {
+    // Given
+    let mut cpu = cpu_with(0x10, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x03, ADDRESS);
+
+    // When
+    cpu.rra(ADDRESS);
+
+    // Then
+    assert_eq!(0x01, cpu.memory.read(ADDRESS));
+    assert_eq!(0x12, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::empty(), cpu.registers.status);
+}
+
+#[test]
+fn test_sax_stores_accumulator_and_x_without_flags() {
+    // Given
+    let mut cpu = cpu_with(0xF0, 0x3C, CpuFlags::empty());
+
+    // When
+    cpu.sax(ADDRESS);
+
+    // Then
+    assert_eq!(0x30, cpu.memory.read(ADDRESS));
+    assert_eq!(CpuFlags::empty(), cpu.registers.status);
+}
+
+#[test]
+fn test_lax_loads_accumulator_and_x() {
+    // Given
+    let mut cpu = cpu_with(0x00, 0x00, CpuFlags::empty());
+
+    // When
+    cpu.lax(0x80);
+
+    // Then
+    assert_eq!(0x80, cpu.registers.accumulator);
+    assert_eq!(0x80, cpu.registers.x_register);
+    assert_eq!(CpuFlags::NEGATIVE, cpu.registers.status);
+}
+
+#[test]
+fn test_dcp_decrements_memory_and_compares() {
+    // Given
+    let mut cpu = cpu_with(0x41, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x42, ADDRESS);
+
+    // When
+    cpu.dcp(ADDRESS);
+
+    // Then
+    assert_eq!(0x41, cpu.memory.read(ADDRESS));
+    assert_eq!(CpuFlags::ZERO | CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_dcp_sets_negative_from_difference() {
+    // Given
+    // A = $40 and memory becomes $41: $40 - $41 = $FF
+    let mut cpu = cpu_with(0x40, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x42, ADDRESS);
+
+    // When
+    cpu.dcp(ADDRESS);
+
+    // Then
+    assert_eq!(0x41, cpu.memory.read(ADDRESS));
+    assert_eq!(CpuFlags::NEGATIVE, cpu.registers.status);
+}
+
+#[test]
+fn test_dcp_clears_negative_from_difference() {
+    // Given
+    // A = $80 and memory becomes $01: $80 - $01 = $7F
+    let mut cpu = cpu_with(0x80, 0x00, CpuFlags::empty());
+    cpu.memory.write(0x02, ADDRESS);
+
+    // When
+    cpu.dcp(ADDRESS);
+
+    // Then
+    assert_eq!(0x01, cpu.memory.read(ADDRESS));
+    assert_eq!(CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_isc_increments_memory_and_subtracts() {
+    // Given
+    let mut cpu = cpu_with(0x10, 0x00, CpuFlags::CARRY);
+    cpu.memory.write(0x04, ADDRESS);
+
+    // When
+    cpu.isc(ADDRESS);
+
+    // Then
+    assert_eq!(0x05, cpu.memory.read(ADDRESS));
+    assert_eq!(0x0B, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_anc_copies_negative_to_carry() {
+    // Given
+    let mut cpu = cpu_with(0xF0, 0x00, CpuFlags::empty());
+
+    // When
+    cpu.anc(0x80);
+
+    // Then
+    assert_eq!(0x80, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::NEGATIVE | CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_alr_ands_then_shifts_right() {
+    // Given
+    let mut cpu = cpu_with(0x03, 0x00, CpuFlags::empty());
+
+    // When
+    cpu.alr(0x01);
+
+    // Then
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::ZERO | CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_arr_sets_carry_and_overflow_from_bits_6_and_5() {
+    // Given
+    let mut cpu = cpu_with(0xFF, 0x00, CpuFlags::CARRY);
+
+    // When
+    cpu.arr(0x80);
+
+    // Then
+    // (0xFF & 0x80) ROR with carry = 0xC0: bit 6 set, bit 5 clear
+    assert_eq!(0xC0, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::NEGATIVE | CpuFlags::CARRY | CpuFlags::OVERFLOW, cpu.registers.status);
+}
+
+#[test]
+fn test_sbx_subtracts_from_accumulator_and_x_ignoring_carry() {
+    // Given
+    let mut cpu = cpu_with(0x0F, 0x3C, CpuFlags::empty());
+
+    // When
+    cpu.sbx(0x02);
+
+    // Then
+    assert_eq!(0x0A, cpu.registers.x_register);
+    assert_eq!(0x0F, cpu.registers.accumulator);
+    assert_eq!(CpuFlags::CARRY, cpu.registers.status);
+}
+
+#[test]
+fn test_las_ands_with_stack_pointer() {
+    // Given
+    let mut cpu = cpu_with(0x00, 0x00, CpuFlags::empty());
+    cpu.registers.stack_pointer = 0xF3;
+
+    // When
+    cpu.las(0x3F);
+
+    // Then
+    assert_eq!(0x33, cpu.registers.accumulator);
+    assert_eq!(0x33, cpu.registers.x_register);
+    assert_eq!(0x33, cpu.registers.stack_pointer);
+}
+
+#[test]
+fn test_ane_uses_magic_constant() {
+    // Given
+    let mut cpu = cpu_with(0x00, 0xFF, CpuFlags::empty());
+
+    // When
+    cpu.ane(0xFF);
+
+    // Then
+    assert_eq!(ANE_MAGIC, cpu.registers.accumulator);
+}
+
+#[test]
+fn test_shx_stores_x_and_high_byte_plus_1() {
+    // Given
+    let mut cpu = cpu_with(0x00, 0xFF, CpuFlags::empty());
+    cpu.registers.y_register = 0x01;
+
+    // When
+    cpu.shx(0x1235);
+
+    // Then
+    assert_eq!(0x13, cpu.memory.read(0x1235));
+}
+
+#[test]
+fn test_shy_replaces_high_byte_when_page_is_crossed() {
+    // Given
+    let mut cpu = cpu_with(0x00, 0x10, CpuFlags::empty());
+    cpu.registers.y_register = 0x05;
+
+    // When
+    // Base $02F8 + X = $0308
+    cpu.shy(0x0308);
+
+    // Then
+    assert_eq!(0x05 & 0x03, cpu.memory.read(0x0108));
+}
+
+#[test]
+fn test_tas_sets_stack_pointer() {
+    // Given
+    let mut cpu = cpu_with(0xF0, 0x3F, CpuFlags::empty());
+    cpu.registers.y_register = 0x00;
+
+    // When
+    cpu.tas(0x7000);
+
+    // Then
+    assert_eq!(0x30, cpu.registers.stack_pointer);
+    assert_eq!(0x30 & 0x71, cpu.memory.read(0x7000));
+}
@@ -3,7 +3,10 @@ pub mod types;
 
 mod instruction_set;
 mod register_bank;
+mod trace;
 
+use crate::bus::Bus;
+use crate::bus::nes_bus::NesBus;
 use crate::memory::Memory;
 use crate::memory::types::AddressingMode;
 
@@ -17,357 +20,786 @@ use instruction_set::register_transfers::RegisterTransfers;
 use instruction_set::shifts::Shifts;
 use instruction_set::stack_operations::StackOperations;
 use instruction_set::status_flag_change::StatusFlagChange;
-use instruction_set::system_functions::SystemFunctions;
+use instruction_set::system_functions::{self, SystemFunctions};
+use instruction_set::unofficial::Unofficial;
 
 use register_bank::RegisterBank;
 use opcode::Opcode;
-use types::{CpuFlags, InstructionError};
+use types::{CpuFlags, CpuState, CpuVariant, InstructionError, StepRecord, UnstableOpcodePolicy, IRQ_VECTOR, NMI_VECTOR, RESET_VECTOR};
 
-pub struct Cpu {
+/// Amount of cycles the CPU takes to handle an interrupt (NMI, IRQ or RESET)
+const INTERRUPT_CYCLES: u8 = 7;
+/// A jammed CPU keeps the bus busy. Each step lets the devices run for this amount of cycles
+const JAMMED_CYCLES: u16 = 2;
+
+/// 6502 CPU connected to a system bus.
+/// By default, the bus is a flat 64 KiB `Memory`
+pub struct Cpu<B: Bus = Memory> {
     registers: RegisterBank,
-    memory: Memory
+    memory: B,
+    variant: CpuVariant,
+    unstable_opcode_policy: UnstableOpcodePolicy,
+    /// Set by JAM, cleared by RESET
+    jammed: bool,
+    cycles: u64,
+    /// Last level seen on the NMI line, used to detect the edge that requests the interrupt
+    nmi_line: bool,
+    /// Last level seen on the NMI line driven by the bus
+    bus_nmi_line: bool,
+    nmi_pending: bool,
+    irq_line: bool
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Cpu {
     pub fn new() -> Self {
-        Self {
-            registers: RegisterBank::new(),
-            memory: Memory::new()
-        }
+        Self::new_with_bus(Memory::new())
+    }
+}
+
+impl<B: Bus> Cpu<B> {
+    pub fn new_with_bus(bus: B) -> Self {
+        Self::new_with_parameters(bus, RegisterBank::new())
     }
 
-    pub fn new_with_parameters(memory: Memory, registers: RegisterBank) -> Self {
+    pub fn new_with_parameters(memory: B, registers: RegisterBank) -> Self {
         Self {
             registers,
-            memory
+            memory,
+            variant: CpuVariant::default(),
+            unstable_opcode_policy: UnstableOpcodePolicy::default(),
+            jammed: false,
+            cycles: 0,
+            nmi_line: false,
+            bus_nmi_line: false,
+            nmi_pending: false,
+            irq_line: false
+        }
+    }
+
+    pub fn bus(&self) -> &B {
+        &self.memory
+    }
+
+    pub fn bus_mut(&mut self) -> &mut B {
+        &mut self.memory
+    }
+
+    /// Returns a copy of the registers
+    pub fn state(&self) -> CpuState {
+        CpuState {
+            program_counter: self.registers.program_counter,
+            stack_pointer: self.registers.stack_pointer,
+            accumulator: self.registers.accumulator,
+            x_register: self.registers.x_register,
+            y_register: self.registers.y_register,
+            status: self.registers.status
+        }
+    }
+
+    /// Overwrites the registers. Cycles, interrupt lines and the jammed state are kept
+    pub fn load_state(&mut self, state: CpuState) {
+        self.registers.program_counter = state.program_counter;
+        self.registers.stack_pointer = state.stack_pointer;
+        self.registers.accumulator = state.accumulator;
+        self.registers.x_register = state.x_register;
+        self.registers.y_register = state.y_register;
+        self.registers.status = state.status;
+    }
+
+    pub fn program_counter(&self) -> u16 {
+        self.registers.program_counter
+    }
+
+    /// Moves execution to the given address, e.g. to start a test ROM at a fixed entry point
+    pub fn set_program_counter(&mut self, address: u16) {
+        self.registers.program_counter = address;
+    }
+
+    pub fn variant(&self) -> CpuVariant {
+        self.variant
+    }
+
+    /// Selects the CPU model to emulate. By default, the CPU behaves as the NES 2A03
+    pub fn set_variant(&mut self, variant: CpuVariant) {
+        self.variant = variant;
+    }
+
+    pub fn unstable_opcode_policy(&self) -> UnstableOpcodePolicy {
+        self.unstable_opcode_policy
+    }
+
+    /// Selects how unstable unofficial opcodes and JAM are handled. By default, they are errors
+    pub fn set_unstable_opcode_policy(&mut self, policy: UnstableOpcodePolicy) {
+        self.unstable_opcode_policy = policy;
+    }
+
+    /// Returns true after a JAM opcode halted the CPU
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
+    }
+
+    /// Total amount of cycles executed since the CPU was created
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Simulates the RESET signal: loads PC from the reset vector ($FFFC),
+    /// sets SP to $FD and disables interrupts
+    pub fn reset(&mut self) {
+        self.registers.program_counter = self.memory.read_u16(RESET_VECTOR);
+        self.registers.stack_pointer = 0xFD;
+        self.registers.status.insert(CpuFlags::INTERRUPT_DISABLE | CpuFlags::UNUSED);
+        self.nmi_pending = false;
+        self.jammed = false;
+        self.cycles += INTERRUPT_CYCLES as u64;
+        self.memory.tick(INTERRUPT_CYCLES as u16);
+    }
+
+    /// Sets the level of the NMI line. NMI is edge triggered, so the interrupt is only
+    /// requested when the line goes from inactive to active
+    pub fn set_nmi_line(&mut self, active: bool) {
+        if active && !self.nmi_line {
+            self.nmi_pending = true;
         }
+        self.nmi_line = active;
+    }
+
+    /// Sets the level of the IRQ line. IRQ is level triggered, so the interrupt is requested
+    /// before every instruction while the line is active and INTERRUPT_DISABLE is clear
+    pub fn set_irq_line(&mut self, active: bool) {
+        self.irq_line = active;
     }
 
+    /// Runs the program until a BRK instruction is executed.
+    /// Fails if the CPU gets jammed, as it would never reach a BRK
     pub fn execute_program(&mut self) -> Result<(), InstructionError> {
         loop {
-            if self.registers.status.contains(CpuFlags::BREAK) {
+            let step = self.run_instruction()?;
+            if self.jammed {
+                return Err(InstructionError::CpuJammed(self.registers.program_counter));
+            }
+            if matches!(step.opcode, Opcode::Brk(_, _)) {
                 return Ok(())
             }
-            let opcode = self.memory.read(self.registers.program_counter);
-            match self.execute_instruction(opcode) {
-                Err(instruction_error) => return Err(instruction_error),
-                Ok(_) => ()
+        }
+    }
+
+    /// Runs the program until it traps: an instruction leaves PC unchanged (e.g. `JMP *` or `BNE *`).
+    /// Test suites use these self-loops to report where they stopped.
+    /// Returns the address of the trap. Fails if the CPU gets jammed
+    pub fn run_until_trap(&mut self) -> Result<u16, InstructionError> {
+        loop {
+            let program_counter = self.registers.program_counter;
+            self.run_instruction()?;
+            if self.jammed {
+                return Err(InstructionError::CpuJammed(self.registers.program_counter));
+            }
+            if self.registers.program_counter == program_counter {
+                return Ok(program_counter);
             }
         }
     }
 
-    fn execute_instruction(&mut self, op: u8) -> Result<(), InstructionError> {
-        
+    /// Handles any pending interrupt, then executes the instruction pointed by the program counter (PC)
+    /// Returns what was executed and the amount of cycles consumed
+    pub fn step(&mut self) -> Result<StepRecord, InstructionError> {
+        self.run_instruction()
+    }
+
+    /// Runs instructions until at least the given amount of cycles has elapsed.
+    /// The last instruction may overshoot. Returns the amount of cycles run
+    pub fn run_for_cycles(&mut self, cycles: u64) -> Result<u64, InstructionError> {
+        let target = self.cycles + cycles;
+        self.run_until(|cpu| cpu.cycles >= target)
+    }
+
+    /// Runs instructions until the predicate holds. It's checked before every instruction,
+    /// so nothing is run when it already holds. Returns the amount of cycles run
+    pub fn run_until<F>(&mut self, mut predicate: F) -> Result<u64, InstructionError>
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        let cycles_before = self.cycles;
+        while !predicate(self) {
+            self.run_instruction()?;
+        }
+        Ok(self.cycles - cycles_before)
+    }
+
+    /// Runs the instruction pointed by PC and returns what was executed, with the amount of cycles consumed,
+    /// including any stall requested by the bus. The devices on the bus run for the same amount of cycles afterwards
+    fn run_instruction(&mut self) -> Result<StepRecord, InstructionError> {
+        let cycles_before = self.cycles;
+        // A jammed CPU ignores interrupts, but the devices on the bus keep running
+        if self.jammed {
+            self.cycles += JAMMED_CYCLES as u64;
+            self.memory.tick(JAMMED_CYCLES);
+            let op = self.memory.peek(self.registers.program_counter);
+            let opcode = Opcode::decode(op, self.variant).unwrap_or(Opcode::Jam(op, AddressingMode::Implicit));
+            return Ok(self.step_record(opcode, JAMMED_CYCLES));
+        }
+
+        self.handle_interrupts();
+        let op = self.memory.read(self.registers.program_counter);
+        let opcode = Opcode::decode(op, self.variant).ok_or(InstructionError::InvalidOpcode(op))?;
+        // Registers change during execution, so the operand is captured before
+        let opcode = self.apply_unstable_opcode_policy(opcode)?;
+        let mut step = self.step_record(opcode, 0);
+        self.execute_instruction(opcode)?;
+        self.memory.tick((self.cycles - cycles_before) as u16);
+
+        let stall_cycles = self.memory.take_stall_cycles();
+        if stall_cycles > 0 {
+            self.cycles += stall_cycles as u64;
+            self.memory.tick(stall_cycles);
+        }
+        step.cycles = (self.cycles - cycles_before) as u16;
+        Ok(step)
+    }
+
+    /// Describes the instruction pointed by PC, before executing it
+    fn step_record(&self, opcode: Opcode, cycles: u16) -> StepRecord {
+        let address = self.registers.program_counter;
+        let addressing_mode = opcode.addressing_mode();
+        let operand = match addressing_mode.byte_size() {
+            2 => Some(self.memory.peek(address.wrapping_add(1)) as u16),
+            3 => Some(self.memory.peek_u16(address.wrapping_add(1))),
+            _ => None
+        };
+        StepRecord {
+            address,
+            opcode,
+            addressing_mode,
+            operand,
+            effective_address: self.peek_effective_address(addressing_mode),
+            cycles
+        }
+    }
+
+    /// NMI has priority over IRQ. IRQ is ignored when INTERRUPT_DISABLE flag is set.
+    /// Interrupts can be requested with `set_nmi_line`/`set_irq_line` or by any device on the bus
+    fn handle_interrupts(&mut self) {
+        let bus_nmi_line = self.memory.nmi();
+        if bus_nmi_line && !self.bus_nmi_line {
+            self.nmi_pending = true;
+        }
+        self.bus_nmi_line = bus_nmi_line;
+
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            system_functions::interrupt(self, self.registers.program_counter, NMI_VECTOR, false);
+            self.cycles += INTERRUPT_CYCLES as u64;
+        } else if (self.irq_line || self.memory.irq()) && !self.registers.status.contains(CpuFlags::INTERRUPT_DISABLE) {
+            system_functions::interrupt(self, self.registers.program_counter, IRQ_VECTOR, false);
+            self.cycles += INTERRUPT_CYCLES as u64;
+        }
+    }
+
+    /// Executes an opcode already decoded for the CPU variant and filtered by the unstable opcode policy
+    fn execute_instruction(&mut self, opcode: Opcode) -> Result<(), InstructionError> {
         let current_addressing_mode: AddressingMode;
-        if let Some(opcode) = Opcode::from_u8(op) {
-            
-            self.registers.program_counter += 1;
-
-            match opcode {
-                // Arithmetic
-                Opcode::Adc(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.adc(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sbc(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.sbc(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Cmp(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.cmp(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Cpx(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.cpx(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Cpy(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.cpy(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                // Branches
-                Opcode::Bcc(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bcc(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bcs(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bcs(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Beq(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.beq(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bmi(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bmi(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bne(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bne(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bpl(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bpl(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bvc(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bvc(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bvs(_, addressing_mode) => {
-                    let address: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bvs(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                // IncrementsDecrements
-                Opcode::Inc(_, addressing_mode) => {
+        let mut branch_taken = false;
+
+        self.registers.program_counter = self.registers.program_counter.wrapping_add(1);
+
+        // At this point, PC points to the instruction operand
+        let operand_address = self.registers.program_counter;
+        let page_crossed = self.is_page_crossed(opcode.addressing_mode());
+        let mut cycles = opcode.cycles();
+        if opcode.has_page_crossing_penalty() && page_crossed {
+            cycles += 1;
+        }
+        if self.variant == CpuVariant::Cmos65C02 {
+            cycles = cmos_cycles(opcode, cycles, self.registers.status, page_crossed);
+        }
+
+        match opcode {
+            // Arithmetic
+            Opcode::Adc(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.adc(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sbc(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.sbc(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Cmp(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.cmp(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Cpx(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.cpx(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Cpy(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.cpy(data);
+                current_addressing_mode = addressing_mode;
+            },
+            // Branches
+            Opcode::Bcc(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bcc(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bcs(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bcs(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Beq(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.beq(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bmi(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bmi(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bne(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bne(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bpl(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bpl(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bvc(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bvc(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bvs(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bvs(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bra(_, addressing_mode) => {
+                let address: u8 = self.read_operand(addressing_mode);
+                branch_taken = self.bra(address);
+                current_addressing_mode = addressing_mode;
+            },
+            // IncrementsDecrements
+            Opcode::Inc(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.inc_accumulator();
+                } else {
                     let address: u16 = self.calculate_address(addressing_mode);
                     self.inc(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Inx(_, addressing_mode) => {
-                    self.inx();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Iny(_, addressing_mode) => {
-                    self.iny();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Dec(_, addressing_mode) => {
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Inx(_, addressing_mode) => {
+                self.inx();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Iny(_, addressing_mode) => {
+                self.iny();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Dec(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.dec_accumulator();
+                } else {
                     let address: u16 = self.calculate_address(addressing_mode);
                     self.dec(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Dex(_, addressing_mode) => {
-                    self.dex();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Dey(_, addressing_mode) => {
-                    self.dey();
-                    current_addressing_mode = addressing_mode;
-                },
-                // JumpsCalls
-                Opcode::Jmp(_, addressing_mode) => {
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Dex(_, addressing_mode) => {
+                self.dex();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Dey(_, addressing_mode) => {
+                self.dey();
+                current_addressing_mode = addressing_mode;
+            },
+            // JumpsCalls
+            Opcode::Jmp(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.jmp(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Jsr(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.jsr(address, addressing_mode.byte_size());
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Rts(_, addressing_mode) => {
+                self.rts();
+                current_addressing_mode = addressing_mode;
+                // According to specs, we are supposed to save next instruction - 1,
+                // to PC. In order adjust PC this program logic, we have to increment it
+                // so it will point to next instruction.
+                self.registers.program_counter = self.registers.program_counter.wrapping_add(1);
+            },
+            // LoadStore
+            Opcode::Lda(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.lda(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ldx(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.ldx(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ldy(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.ldy(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sta(_, addressing_mode) => {
+                let data: u16 = self.calculate_address(addressing_mode);
+                self.sta(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Stx(_, addressing_mode) => {
+                let data: u16 = self.calculate_address(addressing_mode);
+                self.stx(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sty(_, addressing_mode) => {
+                let data: u16 = self.calculate_address(addressing_mode);
+                self.sty(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Stz(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.stz(address);
+                current_addressing_mode = addressing_mode;
+            },
+            // Logical
+            Opcode::And(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.and(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ora(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.ora(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Eor(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.eor(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Bit(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                if addressing_mode == AddressingMode::Immediate {
+                    self.bit_immediate(data);
+                } else {
+                    self.bit(data);
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Trb(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.trb(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Tsb(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.tsb(address);
+                current_addressing_mode = addressing_mode;
+            },
+            // RegisterTransfers
+            Opcode::Tax(_, addressing_mode) => {
+                self.tax();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Tay(_, addressing_mode) => {
+                self.tay();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Txa(_, addressing_mode) => {
+                self.txa();
+                current_addressing_mode = addressing_mode;
+            }
+            Opcode::Tya(_, addressing_mode) => {
+                self.tya();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Tsx(_, addressing_mode) => {
+                self.tsx();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Txs(_, addressing_mode) => {
+                self.txs();
+                current_addressing_mode = addressing_mode;
+            },
+            // Shifts
+            Opcode::Asl(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.asl_accumulator();    
+                } else {
                     let address: u16 = self.calculate_address(addressing_mode);
-                    self.jmp(address);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Jsr(_, addressing_mode) => {
+                    self.asl(address);
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Lsr(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.lsr_accumulator();    
+                } else {
                     let address: u16 = self.calculate_address(addressing_mode);
-                    self.jsr(address, addressing_mode.byte_size());
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Rts(_, addressing_mode) => {
-                    self.rts();
-                    current_addressing_mode = addressing_mode;
-                    // According to specs, we are supposed to save next instruction - 1,
-                    // to PC. In order adjust PC this program logic, we have to increment it
-                    // so it will point to next instruction.
-                    self.registers.program_counter += 1;
-                },
-                // LoadStore
-                Opcode::Lda(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.lda(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Ldx(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.ldx(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Ldy(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.ldy(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sta(_, addressing_mode) => {
-                    let data: u16 = self.calculate_address(addressing_mode);
-                    self.sta(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Stx(_, addressing_mode) => {
-                    let data: u16 = self.calculate_address(addressing_mode);
-                    self.stx(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sty(_, addressing_mode) => {
-                    let data: u16 = self.calculate_address(addressing_mode);
-                    self.sty(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                // Logical
-                Opcode::And(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.and(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Ora(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.ora(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Eor(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.eor(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Bit(_, addressing_mode) => {
-                    let data: u8 = self.memory.read(self.calculate_address(addressing_mode));
-                    self.bit(data);
-                    current_addressing_mode = addressing_mode;
-                },
-                // RegisterTransfers
-                Opcode::Tax(_, addressing_mode) => {
-                    self.tax();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Tay(_, addressing_mode) => {
-                    self.tay();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Txa(_, addressing_mode) => {
-                    self.txa();
-                    current_addressing_mode = addressing_mode;
+                    self.lsr(address);
                 }
-                Opcode::Tya(_, addressing_mode) => {
-                    self.tya();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Tsx(_, addressing_mode) => {
-                    self.tsx();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Txs(_, addressing_mode) => {
-                    self.txs();
-                    current_addressing_mode = addressing_mode;
-                },
-                // Shifts
-                Opcode::Asl(_, addressing_mode) => {
-                    if addressing_mode == AddressingMode::Accumulator {
-                        self.asl_accumulator();    
-                    } else {
-                        let address: u16 = self.calculate_address(addressing_mode);
-                        self.asl(address);
-                    }
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Lsr(_, addressing_mode) => {
-                    if addressing_mode == AddressingMode::Accumulator {
-                        self.lsr_accumulator();    
-                    } else {
-                        let address: u16 = self.calculate_address(addressing_mode);
-                        self.lsr(address);
-                    }
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Rol(_, addressing_mode) => {
-                    if addressing_mode == AddressingMode::Accumulator {
-                        self.rol_accumulator();    
-                    } else {
-                        let address: u16 = self.calculate_address(addressing_mode);
-                        self.rol(address);
-                    }
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Ror(_, addressing_mode) => {
-                    if addressing_mode == AddressingMode::Accumulator {
-                        self.ror_accumulator();    
-                    } else {
-                        let address: u16 = self.calculate_address(addressing_mode);
-                        self.ror(address);
-                    }
-                    current_addressing_mode = addressing_mode;
-                },
-                // Stack Operations
-                Opcode::Pha(_, addressing_mode) => {
-                    self.pha();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Php(_, addressing_mode) => {
-                    self.php();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Pla(_, addressing_mode) => {
-                    self.pla();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Plp(_, addressing_mode) => {
-                    self.plp();
-                    current_addressing_mode = addressing_mode;
-                },
-                // StatusFlagChange
-                Opcode::Clc(_, addressing_mode) => {
-                    self.clc();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Cld(_, addressing_mode) => {
-                    self.cld();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Cli(_, addressing_mode) => {
-                    self.cli();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Clv(_, addressing_mode) => {
-                    self.clv();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sec(_, addressing_mode) => {
-                    self.sec();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sed(_, addressing_mode) => {
-                    self.sed();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Sei(_, addressing_mode) => {
-                    self.sei();
-                    current_addressing_mode = addressing_mode;
-                },
-                // System functions
-                Opcode::Brk(_, addressing_mode) => {
-                    self.brk();
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Nop(_, addressing_mode) => {
-                    // NOP does nothing but increment program counter...
-                    current_addressing_mode = addressing_mode;
-                },
-                Opcode::Rti(_, addressing_mode) => {
-                    self.rti();
-                    current_addressing_mode = addressing_mode;
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Rol(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.rol_accumulator();    
+                } else {
+                    let address: u16 = self.calculate_address(addressing_mode);
+                    self.rol(address);
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ror(_, addressing_mode) => {
+                if addressing_mode == AddressingMode::Accumulator {
+                    self.ror_accumulator();    
+                } else {
+                    let address: u16 = self.calculate_address(addressing_mode);
+                    self.ror(address);
+                }
+                current_addressing_mode = addressing_mode;
+            },
+            // Stack Operations
+            Opcode::Pha(_, addressing_mode) => {
+                self.pha();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Php(_, addressing_mode) => {
+                self.php();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Pla(_, addressing_mode) => {
+                self.pla();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Plp(_, addressing_mode) => {
+                self.plp();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Phx(_, addressing_mode) => {
+                self.phx();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Phy(_, addressing_mode) => {
+                self.phy();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Plx(_, addressing_mode) => {
+                self.plx();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ply(_, addressing_mode) => {
+                self.ply();
+                current_addressing_mode = addressing_mode;
+            },
+            // StatusFlagChange
+            Opcode::Clc(_, addressing_mode) => {
+                self.clc();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Cld(_, addressing_mode) => {
+                self.cld();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Cli(_, addressing_mode) => {
+                self.cli();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Clv(_, addressing_mode) => {
+                self.clv();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sec(_, addressing_mode) => {
+                self.sec();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sed(_, addressing_mode) => {
+                self.sed();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sei(_, addressing_mode) => {
+                self.sei();
+                current_addressing_mode = addressing_mode;
+            },
+            // System functions
+            Opcode::Brk(_, addressing_mode) => {
+                self.brk();
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Nop(_, addressing_mode) => {
+                // NOP does nothing but increment program counter...
+                // Unofficial NOPs with an operand still read it
+                if addressing_mode != AddressingMode::Implicit {
+                    self.read_operand(addressing_mode);
                 }
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Rti(_, addressing_mode) => {
+                self.rti();
+                current_addressing_mode = addressing_mode;
+            },
+            // Unofficial
+            Opcode::Slo(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.slo(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Rla(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.rla(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sre(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.sre(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Rra(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.rra(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sax(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.sax(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Lax(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.lax(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Dcp(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.dcp(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Isc(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.isc(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Anc(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.anc(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Alr(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.alr(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Arr(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.arr(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sbx(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.sbx(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Las(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.las(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Ane(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.ane(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Lxa(_, addressing_mode) => {
+                let data: u8 = self.read_operand(addressing_mode);
+                self.lxa(data);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Sha(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.sha(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Shx(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.shx(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Shy(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.shy(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Tas(_, addressing_mode) => {
+                let address: u16 = self.calculate_address(addressing_mode);
+                self.tas(address);
+                current_addressing_mode = addressing_mode;
+            },
+            Opcode::Jam(_, addressing_mode) => {
+                // PC stays on the JAM opcode
+                self.registers.program_counter = self.registers.program_counter.wrapping_sub(1);
+                self.jammed = true;
+                current_addressing_mode = addressing_mode;
             }
+        }
 
-            // Jump instructions will set PC direct to next instruction address
-            // So, no need to increment PC in these cases
-            if !opcode.is_jump_instruction() {
-                self.registers.program_counter += current_addressing_mode.byte_size() as u16 - 1;
-            }
-        } else {
-            return Err(InstructionError::InvalidOpcode(op));
+        // Jump instructions will set PC direct to next instruction address
+        // So, no need to increment PC in these cases
+        if !opcode.is_jump_instruction() {
+            self.registers.program_counter = self.registers.program_counter.wrapping_add(current_addressing_mode.byte_size() as u16 - 1);
+        }
+
+        // Taken branches cost one extra cycle, and another one if the destination is in a different page
+        if branch_taken {
+            let next_instruction = operand_address.wrapping_add(1);
+            cycles += if is_different_page(next_instruction, self.registers.program_counter) { 2 } else { 1 };
         }
+        self.cycles += cycles as u64;
 
         Ok(())
     }
 
+    /// Unstable opcodes are either rejected, replaced by a NOP of the same size or kept, according to the policy
+    fn apply_unstable_opcode_policy(&self, opcode: Opcode) -> Result<Opcode, InstructionError> {
+        if !opcode.is_unstable() {
+            return Ok(opcode);
+        }
+        match self.unstable_opcode_policy {
+            UnstableOpcodePolicy::Error => Err(InstructionError::UnstableOpcode(opcode.value())),
+            UnstableOpcodePolicy::Nop => Ok(Opcode::Nop(opcode.value(), opcode.addressing_mode())),
+            UnstableOpcodePolicy::Execute => Ok(opcode)
+        }
+    }
+
+    /// Reads the data the current instruction operates on, according to the AddressingMode
+    fn read_operand(&mut self, mode: AddressingMode) -> u8 {
+        let address = self.calculate_address(mode);
+        self.memory.read(address)
+    }
+
     /// Returns the address required by the AddressingMode of the current instruction
     /// It assumes that program_counter (PC) was already incremented after opcode parsing
     /// Some addressing modes may not have a memory address to return. E.g. AddressingMode::Accumulator
-    fn calculate_address(&self, mode: AddressingMode) -> u16 {
+    fn calculate_address(&mut self, mode: AddressingMode) -> u16 {
         match mode {
             AddressingMode::Absolute => self.memory.read_u16(self.registers.program_counter),
 
@@ -384,19 +816,35 @@ impl Cpu {
             AddressingMode::Immediate => self.registers.program_counter,
 
             AddressingMode::Indirect => {
-                self.memory.read_u16(
-                    self.memory.read_u16(self.registers.program_counter))
+                let pointer = self.memory.read_u16(self.registers.program_counter);
+                if self.variant == CpuVariant::Cmos65C02 {
+                    return self.memory.read_u16(pointer);
+                }
+                // NMOS bug: the pointer high byte is read without carrying into the page,
+                // so JMP ($10FF) reads $10FF and $1000
+                let low_byte = self.memory.read(pointer) as u16;
+                let high_byte = self.memory.read((pointer & 0xFF00) | (pointer.wrapping_add(1) & 0x00FF)) as u16;
+                high_byte << 8 | low_byte
+            },
+
+            AddressingMode::AbsoluteIndirectX => {
+                let pointer = self.memory.read_u16(self.registers.program_counter);
+                self.memory.read_u16(pointer.wrapping_add(self.registers.x_register as u16))
+            },
+
+            AddressingMode::ZeroPageIndirect => {
+                let pointer = self.memory.read(self.registers.program_counter);
+                self.read_zero_page_u16(pointer)
             },
 
             AddressingMode::IndirectX => {
                 let base = self.memory.read(self.registers.program_counter);
-                let address = base.wrapping_add(self.registers.x_register) as u16;
-                self.memory.read_u16(address)
+                self.read_zero_page_u16(base.wrapping_add(self.registers.x_register))
             }
 
             AddressingMode::IndirectY => {
-                let base = self.memory.read(self.registers.program_counter) as u16;
-                let address = self.memory.read_u16(base);
+                let base = self.memory.read(self.registers.program_counter);
+                let address = self.read_zero_page_u16(base);
                 address.wrapping_add(self.registers.y_register as u16)
             }
 
@@ -419,6 +867,108 @@ impl Cpu {
             _ => panic!("Addressing mode {:?} not available", mode),
         }
     }
+
+    /// Reads a pointer stored in zero page. A pointer at $FF takes its high byte from $00
+    fn read_zero_page_u16(&mut self, pointer: u8) -> u16 {
+        let low_byte = self.memory.read(pointer as u16) as u16;
+        let high_byte = self.memory.read(pointer.wrapping_add(1) as u16) as u16;
+        high_byte << 8 | low_byte
+    }
+
+    /// Returns the address the instruction pointed by PC would access, without any side effect.
+    /// Branches return their destination, as if they were taken
+    fn peek_effective_address(&self, mode: AddressingMode) -> Option<u16> {
+        let operand_address = self.registers.program_counter.wrapping_add(1);
+        let byte = self.memory.peek(operand_address);
+        let word = self.memory.peek_u16(operand_address);
+        let x = self.registers.x_register;
+        let y = self.registers.y_register;
+
+        match mode {
+            AddressingMode::Implicit | AddressingMode::Accumulator | AddressingMode::Immediate => None,
+            AddressingMode::ZeroPage => Some(byte as u16),
+            AddressingMode::ZeroPageX => Some(byte.wrapping_add(x) as u16),
+            AddressingMode::ZeroPageY => Some(byte.wrapping_add(y) as u16),
+            AddressingMode::Absolute => Some(word),
+            AddressingMode::AbsoluteX => Some(word.wrapping_add(x as u16)),
+            AddressingMode::AbsoluteY => Some(word.wrapping_add(y as u16)),
+            AddressingMode::Indirect => {
+                let high_byte_address = if self.variant == CpuVariant::Cmos65C02 {
+                    word.wrapping_add(1)
+                } else {
+                    (word & 0xFF00) | (word.wrapping_add(1) & 0x00FF)
+                };
+                Some((self.memory.peek(high_byte_address) as u16) << 8 | self.memory.peek(word) as u16)
+            },
+            AddressingMode::IndirectX => Some(self.peek_zero_page_u16(byte.wrapping_add(x))),
+            AddressingMode::IndirectY => Some(self.peek_zero_page_u16(byte).wrapping_add(y as u16)),
+            AddressingMode::ZeroPageIndirect => Some(self.peek_zero_page_u16(byte)),
+            AddressingMode::AbsoluteIndirectX => Some(self.memory.peek_u16(word.wrapping_add(x as u16))),
+            AddressingMode::Relative => Some(operand_address.wrapping_add(1).wrapping_add(byte as i8 as u16)),
+        }
+    }
+
+    /// Same as `read_zero_page_u16`, but without any side effect
+    fn peek_zero_page_u16(&self, pointer: u8) -> u16 {
+        let low_byte = self.memory.peek(pointer as u16) as u16;
+        let high_byte = self.memory.peek(pointer.wrapping_add(1) as u16) as u16;
+        high_byte << 8 | low_byte
+    }
+
+    /// Checks if indexing the base address of the current instruction results in an address in a different page
+    /// It assumes that program_counter (PC) was already incremented after opcode parsing
+    fn is_page_crossed(&self, mode: AddressingMode) -> bool {
+        match mode {
+            AddressingMode::AbsoluteX => {
+                let base = self.memory.peek_u16(self.registers.program_counter);
+                is_different_page(base, base.wrapping_add(self.registers.x_register as u16))
+            }
+
+            AddressingMode::AbsoluteY => {
+                let base = self.memory.peek_u16(self.registers.program_counter);
+                is_different_page(base, base.wrapping_add(self.registers.y_register as u16))
+            }
+
+            AddressingMode::IndirectY => {
+                let pointer = self.memory.peek(self.registers.program_counter);
+                let low_byte = self.memory.peek(pointer as u16) as u16;
+                let high_byte = self.memory.peek(pointer.wrapping_add(1) as u16) as u16;
+                let base = high_byte << 8 | low_byte;
+                is_different_page(base, base.wrapping_add(self.registers.y_register as u16))
+            }
+
+            _ => false,
+        }
+    }
+}
+
+impl Cpu<NesBus> {
+    /// Runs instructions until the PPU starts the next frame. Returns the amount of cycles run
+    pub fn run_frame(&mut self) -> Result<u64, InstructionError> {
+        let frame = self.memory.ppu().frame();
+        self.run_until(|cpu| cpu.memory.ppu().frame() != frame)
+    }
+}
+
+/// Adjusts the NMOS cycles of an instruction to the 65C02:
+///
+/// | Instruction             | 65C02 cycles                                            |
+/// |-------------------------|---------------------------------------------------------|
+/// | JMP (abs)               | 1 extra, as the pointer no longer wraps inside the page |
+/// | ADC/SBC in decimal mode | 1 extra, to compute valid N and Z flags                 |
+/// | ASL/LSR/ROL/ROR abs,X   | 1 less, unless the page is crossed                      |
+fn cmos_cycles(opcode: Opcode, cycles: u8, status: CpuFlags, page_crossed: bool) -> u8 {
+    match opcode {
+        Opcode::Jmp(_, AddressingMode::Indirect) => cycles + 1,
+        Opcode::Adc(_, _) | Opcode::Sbc(_, _) if status.contains(CpuFlags::DECIMAL_MODE) => cycles + 1,
+        Opcode::Asl(_, AddressingMode::AbsoluteX) | Opcode::Lsr(_, AddressingMode::AbsoluteX) |
+        Opcode::Rol(_, AddressingMode::AbsoluteX) | Opcode::Ror(_, AddressingMode::AbsoluteX) if !page_crossed => cycles - 1,
+        _ => cycles
+    }
+}
+
+fn is_different_page(address_1: u16, address_2: u16) -> bool {
+    address_1 & 0xFF00 != address_2 & 0xFF00
 }
 
 #[cfg(test)]
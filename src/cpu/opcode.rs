@@ -4,7 +4,7 @@ use lazy_static::lazy_static;
 
 use crate::memory::types::AddressingMode;
 
-use super::types::InstructionError;
+use super::types::{CpuVariant, InstructionError};
 
 lazy_static! {
     static ref OPCODE_MAP: HashMap<(&'static str, AddressingMode), u8> = {
@@ -52,7 +52,7 @@ lazy_static! {
 
         map.insert(("BVC", AddressingMode::Relative), BVC);
 
-        map.insert(("BNS", AddressingMode::Relative), BVS);
+        map.insert(("BVS", AddressingMode::Relative), BVS);
 
         map.insert(("CLC", AddressingMode::Implicit), CLC);
 
@@ -219,6 +219,137 @@ lazy_static! {
         map
     };
 
+    /// Instructions and addressing modes added by the 65C02
+    static ref CMOS_OPCODE_MAP: HashMap<(&'static str, AddressingMode), u8> = {
+        let mut map = HashMap::new();
+        map.insert(("BRA", AddressingMode::Relative), BRA);
+        map.insert(("PHX", AddressingMode::Implicit), PHX);
+        map.insert(("PHY", AddressingMode::Implicit), PHY);
+        map.insert(("PLX", AddressingMode::Implicit), PLX);
+        map.insert(("PLY", AddressingMode::Implicit), PLY);
+        map.insert(("STZ", AddressingMode::ZeroPage), STZ_ZERO_PAGE);
+        map.insert(("STZ", AddressingMode::ZeroPageX), STZ_ZERO_PAGE_X);
+        map.insert(("STZ", AddressingMode::Absolute), STZ_ABSOLUTE);
+        map.insert(("STZ", AddressingMode::AbsoluteX), STZ_ABSOLUTE_X);
+        map.insert(("TRB", AddressingMode::ZeroPage), TRB_ZERO_PAGE);
+        map.insert(("TRB", AddressingMode::Absolute), TRB_ABSOLUTE);
+        map.insert(("TSB", AddressingMode::ZeroPage), TSB_ZERO_PAGE);
+        map.insert(("TSB", AddressingMode::Absolute), TSB_ABSOLUTE);
+        map.insert(("BIT", AddressingMode::Immediate), BIT_IMMEDIATE);
+        map.insert(("BIT", AddressingMode::ZeroPageX), BIT_ZERO_PAGE_X);
+        map.insert(("BIT", AddressingMode::AbsoluteX), BIT_ABSOLUTE_X);
+        map.insert(("INC", AddressingMode::Accumulator), INC_ACCUMULATOR);
+        map.insert(("DEC", AddressingMode::Accumulator), DEC_ACCUMULATOR);
+        map.insert(("ORA", AddressingMode::ZeroPageIndirect), ORA_ZERO_PAGE_INDIRECT);
+        map.insert(("AND", AddressingMode::ZeroPageIndirect), AND_ZERO_PAGE_INDIRECT);
+        map.insert(("EOR", AddressingMode::ZeroPageIndirect), EOR_ZERO_PAGE_INDIRECT);
+        map.insert(("ADC", AddressingMode::ZeroPageIndirect), ADC_ZERO_PAGE_INDIRECT);
+        map.insert(("STA", AddressingMode::ZeroPageIndirect), STA_ZERO_PAGE_INDIRECT);
+        map.insert(("LDA", AddressingMode::ZeroPageIndirect), LDA_ZERO_PAGE_INDIRECT);
+        map.insert(("CMP", AddressingMode::ZeroPageIndirect), CMP_ZERO_PAGE_INDIRECT);
+        map.insert(("SBC", AddressingMode::ZeroPageIndirect), SBC_ZERO_PAGE_INDIRECT);
+        map.insert(("JMP", AddressingMode::AbsoluteIndirectX), JMP_ABSOLUTE_INDIRECT_X);
+
+        map
+    };
+
+    /// Unofficial instructions, only assembled when enabled in the assembler options
+    static ref UNOFFICIAL_OPCODE_MAP: HashMap<(&'static str, AddressingMode), u8> = {
+        let mut map = HashMap::new();
+        map.insert(("SLO", AddressingMode::ZeroPage), SLO_ZERO_PAGE);
+        map.insert(("SLO", AddressingMode::ZeroPageX), SLO_ZERO_PAGE_X);
+        map.insert(("SLO", AddressingMode::Absolute), SLO_ABSOLUTE);
+        map.insert(("SLO", AddressingMode::AbsoluteX), SLO_ABSOLUTE_X);
+        map.insert(("SLO", AddressingMode::AbsoluteY), SLO_ABSOLUTE_Y);
+        map.insert(("SLO", AddressingMode::IndirectX), SLO_INDIRECT_X);
+        map.insert(("SLO", AddressingMode::IndirectY), SLO_INDIRECT_Y);
+
+        map.insert(("RLA", AddressingMode::ZeroPage), RLA_ZERO_PAGE);
+        map.insert(("RLA", AddressingMode::ZeroPageX), RLA_ZERO_PAGE_X);
+        map.insert(("RLA", AddressingMode::Absolute), RLA_ABSOLUTE);
+        map.insert(("RLA", AddressingMode::AbsoluteX), RLA_ABSOLUTE_X);
+        map.insert(("RLA", AddressingMode::AbsoluteY), RLA_ABSOLUTE_Y);
+        map.insert(("RLA", AddressingMode::IndirectX), RLA_INDIRECT_X);
+        map.insert(("RLA", AddressingMode::IndirectY), RLA_INDIRECT_Y);
+
+        map.insert(("SRE", AddressingMode::ZeroPage), SRE_ZERO_PAGE);
+        map.insert(("SRE", AddressingMode::ZeroPageX), SRE_ZERO_PAGE_X);
+        map.insert(("SRE", AddressingMode::Absolute), SRE_ABSOLUTE);
+        map.insert(("SRE", AddressingMode::AbsoluteX), SRE_ABSOLUTE_X);
+        map.insert(("SRE", AddressingMode::AbsoluteY), SRE_ABSOLUTE_Y);
+        map.insert(("SRE", AddressingMode::IndirectX), SRE_INDIRECT_X);
+        map.insert(("SRE", AddressingMode::IndirectY), SRE_INDIRECT_Y);
+
+        map.insert(("RRA", AddressingMode::ZeroPage), RRA_ZERO_PAGE);
+        map.insert(("RRA", AddressingMode::ZeroPageX), RRA_ZERO_PAGE_X);
+        map.insert(("RRA", AddressingMode::Absolute), RRA_ABSOLUTE);
+        map.insert(("RRA", AddressingMode::AbsoluteX), RRA_ABSOLUTE_X);
+        map.insert(("RRA", AddressingMode::AbsoluteY), RRA_ABSOLUTE_Y);
+        map.insert(("RRA", AddressingMode::IndirectX), RRA_INDIRECT_X);
+        map.insert(("RRA", AddressingMode::IndirectY), RRA_INDIRECT_Y);
+
+        map.insert(("SAX", AddressingMode::ZeroPage), SAX_ZERO_PAGE);
+        map.insert(("SAX", AddressingMode::ZeroPageY), SAX_ZERO_PAGE_Y);
+        map.insert(("SAX", AddressingMode::Absolute), SAX_ABSOLUTE);
+        map.insert(("SAX", AddressingMode::IndirectX), SAX_INDIRECT_X);
+
+        map.insert(("LAX", AddressingMode::ZeroPage), LAX_ZERO_PAGE);
+        map.insert(("LAX", AddressingMode::ZeroPageY), LAX_ZERO_PAGE_Y);
+        map.insert(("LAX", AddressingMode::Absolute), LAX_ABSOLUTE);
+        map.insert(("LAX", AddressingMode::AbsoluteY), LAX_ABSOLUTE_Y);
+        map.insert(("LAX", AddressingMode::IndirectX), LAX_INDIRECT_X);
+        map.insert(("LAX", AddressingMode::IndirectY), LAX_INDIRECT_Y);
+
+        map.insert(("DCP", AddressingMode::ZeroPage), DCP_ZERO_PAGE);
+        map.insert(("DCP", AddressingMode::ZeroPageX), DCP_ZERO_PAGE_X);
+        map.insert(("DCP", AddressingMode::Absolute), DCP_ABSOLUTE);
+        map.insert(("DCP", AddressingMode::AbsoluteX), DCP_ABSOLUTE_X);
+        map.insert(("DCP", AddressingMode::AbsoluteY), DCP_ABSOLUTE_Y);
+        map.insert(("DCP", AddressingMode::IndirectX), DCP_INDIRECT_X);
+        map.insert(("DCP", AddressingMode::IndirectY), DCP_INDIRECT_Y);
+
+        map.insert(("ISC", AddressingMode::ZeroPage), ISC_ZERO_PAGE);
+        map.insert(("ISC", AddressingMode::ZeroPageX), ISC_ZERO_PAGE_X);
+        map.insert(("ISC", AddressingMode::Absolute), ISC_ABSOLUTE);
+        map.insert(("ISC", AddressingMode::AbsoluteX), ISC_ABSOLUTE_X);
+        map.insert(("ISC", AddressingMode::AbsoluteY), ISC_ABSOLUTE_Y);
+        map.insert(("ISC", AddressingMode::IndirectX), ISC_INDIRECT_X);
+        map.insert(("ISC", AddressingMode::IndirectY), ISC_INDIRECT_Y);
+
+        map.insert(("ANC", AddressingMode::Immediate), ANC_IMMEDIATE);
+
+        map.insert(("ALR", AddressingMode::Immediate), ALR_IMMEDIATE);
+
+        map.insert(("ARR", AddressingMode::Immediate), ARR_IMMEDIATE);
+
+        map.insert(("SBX", AddressingMode::Immediate), SBX_IMMEDIATE);
+
+        map.insert(("LAS", AddressingMode::AbsoluteY), LAS_ABSOLUTE_Y);
+
+        map.insert(("ANE", AddressingMode::Immediate), ANE_IMMEDIATE);
+
+        map.insert(("LXA", AddressingMode::Immediate), LXA_IMMEDIATE);
+
+        map.insert(("SHA", AddressingMode::AbsoluteY), SHA_ABSOLUTE_Y);
+        map.insert(("SHA", AddressingMode::IndirectY), SHA_INDIRECT_Y);
+
+        map.insert(("SHX", AddressingMode::AbsoluteY), SHX_ABSOLUTE_Y);
+
+        map.insert(("SHY", AddressingMode::AbsoluteX), SHY_ABSOLUTE_X);
+
+        map.insert(("TAS", AddressingMode::AbsoluteY), TAS_ABSOLUTE_Y);
+
+        map.insert(("NOP", AddressingMode::Immediate), NOP_IMMEDIATE[0]);
+        map.insert(("NOP", AddressingMode::ZeroPage), NOP_ZERO_PAGE[0]);
+        map.insert(("NOP", AddressingMode::ZeroPageX), NOP_ZERO_PAGE_X[0]);
+        map.insert(("NOP", AddressingMode::Absolute), NOP_ABSOLUTE);
+        map.insert(("NOP", AddressingMode::AbsoluteX), NOP_ABSOLUTE_X[0]);
+
+        map.insert(("JAM", AddressingMode::Implicit), JAM[0]);
+
+        map
+    };
+
     static ref OPCODE_SET: HashSet<&'static str> = {
         let mut set = HashSet::new();
         set.insert("ADC");
@@ -267,6 +398,7 @@ lazy_static! {
         set.insert("SBC");
         set.insert("SEC");
         set.insert("SED");
+        set.insert("SEI");
         set.insert("STA");
         set.insert("STX");
         set.insert("STY");
@@ -279,6 +411,21 @@ lazy_static! {
 
         set
     };
+
+    /// Mnemonics only available on the 65C02
+    static ref CMOS_OPCODE_SET: HashSet<&'static str> = {
+        let mut set = HashSet::new();
+        set.insert("BRA");
+        set.insert("PHX");
+        set.insert("PHY");
+        set.insert("PLX");
+        set.insert("PLY");
+        set.insert("STZ");
+        set.insert("TRB");
+        set.insert("TSB");
+
+        set
+    };
 }
 
 pub const BRK: u8 = 0x00;
@@ -433,14 +580,119 @@ const PLA: u8 = 0x68;
 const PLP: u8 = 0x28;
 const RTI: u8 = 0x40;
 
+// 65C02 opcodes
+const BRA: u8 = 0x80;
+const PHX: u8 = 0xDA;
+const PHY: u8 = 0x5A;
+const PLX: u8 = 0xFA;
+const PLY: u8 = 0x7A;
+const STZ_ZERO_PAGE: u8 = 0x64;
+const STZ_ZERO_PAGE_X: u8 = 0x74;
+const STZ_ABSOLUTE: u8 = 0x9C;
+const STZ_ABSOLUTE_X: u8 = 0x9E;
+const TRB_ZERO_PAGE: u8 = 0x14;
+const TRB_ABSOLUTE: u8 = 0x1C;
+const TSB_ZERO_PAGE: u8 = 0x04;
+const TSB_ABSOLUTE: u8 = 0x0C;
+const BIT_IMMEDIATE: u8 = 0x89;
+const BIT_ZERO_PAGE_X: u8 = 0x34;
+const BIT_ABSOLUTE_X: u8 = 0x3C;
+const INC_ACCUMULATOR: u8 = 0x1A;
+const DEC_ACCUMULATOR: u8 = 0x3A;
+const ORA_ZERO_PAGE_INDIRECT: u8 = 0x12;
+const AND_ZERO_PAGE_INDIRECT: u8 = 0x32;
+const EOR_ZERO_PAGE_INDIRECT: u8 = 0x52;
+const ADC_ZERO_PAGE_INDIRECT: u8 = 0x72;
+const STA_ZERO_PAGE_INDIRECT: u8 = 0x92;
+const LDA_ZERO_PAGE_INDIRECT: u8 = 0xB2;
+const CMP_ZERO_PAGE_INDIRECT: u8 = 0xD2;
+const SBC_ZERO_PAGE_INDIRECT: u8 = 0xF2;
+const JMP_ABSOLUTE_INDIRECT_X: u8 = 0x7C;
+
+// Unofficial opcodes
+const SLO_ZERO_PAGE: u8 = 0x07;
+const SLO_ZERO_PAGE_X: u8 = 0x17;
+const SLO_ABSOLUTE: u8 = 0x0F;
+const SLO_ABSOLUTE_X: u8 = 0x1F;
+const SLO_ABSOLUTE_Y: u8 = 0x1B;
+const SLO_INDIRECT_X: u8 = 0x03;
+const SLO_INDIRECT_Y: u8 = 0x13;
+const RLA_ZERO_PAGE: u8 = 0x27;
+const RLA_ZERO_PAGE_X: u8 = 0x37;
+const RLA_ABSOLUTE: u8 = 0x2F;
+const RLA_ABSOLUTE_X: u8 = 0x3F;
+const RLA_ABSOLUTE_Y: u8 = 0x3B;
+const RLA_INDIRECT_X: u8 = 0x23;
+const RLA_INDIRECT_Y: u8 = 0x33;
+const SRE_ZERO_PAGE: u8 = 0x47;
+const SRE_ZERO_PAGE_X: u8 = 0x57;
+const SRE_ABSOLUTE: u8 = 0x4F;
+const SRE_ABSOLUTE_X: u8 = 0x5F;
+const SRE_ABSOLUTE_Y: u8 = 0x5B;
+const SRE_INDIRECT_X: u8 = 0x43;
+const SRE_INDIRECT_Y: u8 = 0x53;
+const RRA_ZERO_PAGE: u8 = 0x67;
+const RRA_ZERO_PAGE_X: u8 = 0x77;
+const RRA_ABSOLUTE: u8 = 0x6F;
+const RRA_ABSOLUTE_X: u8 = 0x7F;
+const RRA_ABSOLUTE_Y: u8 = 0x7B;
+const RRA_INDIRECT_X: u8 = 0x63;
+const RRA_INDIRECT_Y: u8 = 0x73;
+const SAX_ZERO_PAGE: u8 = 0x87;
+const SAX_ZERO_PAGE_Y: u8 = 0x97;
+const SAX_ABSOLUTE: u8 = 0x8F;
+const SAX_INDIRECT_X: u8 = 0x83;
+const LAX_ZERO_PAGE: u8 = 0xA7;
+const LAX_ZERO_PAGE_Y: u8 = 0xB7;
+const LAX_ABSOLUTE: u8 = 0xAF;
+const LAX_ABSOLUTE_Y: u8 = 0xBF;
+const LAX_INDIRECT_X: u8 = 0xA3;
+const LAX_INDIRECT_Y: u8 = 0xB3;
+const DCP_ZERO_PAGE: u8 = 0xC7;
+const DCP_ZERO_PAGE_X: u8 = 0xD7;
+const DCP_ABSOLUTE: u8 = 0xCF;
+const DCP_ABSOLUTE_X: u8 = 0xDF;
+const DCP_ABSOLUTE_Y: u8 = 0xDB;
+const DCP_INDIRECT_X: u8 = 0xC3;
+const DCP_INDIRECT_Y: u8 = 0xD3;
+const ISC_ZERO_PAGE: u8 = 0xE7;
+const ISC_ZERO_PAGE_X: u8 = 0xF7;
+const ISC_ABSOLUTE: u8 = 0xEF;
+const ISC_ABSOLUTE_X: u8 = 0xFF;
+const ISC_ABSOLUTE_Y: u8 = 0xFB;
+const ISC_INDIRECT_X: u8 = 0xE3;
+const ISC_INDIRECT_Y: u8 = 0xF3;
+const ANC_IMMEDIATE: u8 = 0x0B;
+const ALR_IMMEDIATE: u8 = 0x4B;
+const ARR_IMMEDIATE: u8 = 0x6B;
+const SBX_IMMEDIATE: u8 = 0xCB;
+const LAS_ABSOLUTE_Y: u8 = 0xBB;
+const ANE_IMMEDIATE: u8 = 0x8B;
+const LXA_IMMEDIATE: u8 = 0xAB;
+const SHA_ABSOLUTE_Y: u8 = 0x9F;
+const SHA_INDIRECT_Y: u8 = 0x93;
+const SHX_ABSOLUTE_Y: u8 = 0x9E;
+const SHY_ABSOLUTE_X: u8 = 0x9C;
+const TAS_ABSOLUTE_Y: u8 = 0x9B;
+const ANC_IMMEDIATE_2: u8 = 0x2B;
+/// Same as SBC #
+const USBC_IMMEDIATE: u8 = 0xEB;
+const JAM: [u8; 12] = [0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2];
+const NOP_IMPLICIT: [u8; 6] = [0x1A, 0x3A, 0x5A, 0x7A, 0xDA, 0xFA];
+const NOP_IMMEDIATE: [u8; 5] = [0x80, 0x82, 0x89, 0xC2, 0xE2];
+const NOP_ZERO_PAGE: [u8; 3] = [0x04, 0x44, 0x64];
+const NOP_ZERO_PAGE_X: [u8; 6] = [0x14, 0x34, 0x54, 0x74, 0xD4, 0xF4];
+const NOP_ABSOLUTE: u8 = 0x0C;
+const NOP_ABSOLUTE_X: [u8; 6] = [0x1C, 0x3C, 0x5C, 0x7C, 0xDC, 0xFC];
+
 
 /// Opcodes of instruction set for 6502 processor
 ///
 /// Each variant of this enum represents a specific instruction in the 6502 instruction set.
 /// - The `u8` parameter represents the opcode value;
 /// - The `AddressingMode` parameter
-/// represents the addressing mode used by the instruction
-/// (how the instruction will extract it's data. E.g. from memory/registers, and how);
+///   represents the addressing mode used by the instruction
+///   (how the instruction will extract it's data. E.g. from memory/registers, and how);
 ///
 /// see: [6502 docs](http://www.6502.org/tutorials/6502opcodes.html)
 /// 
@@ -557,7 +809,63 @@ pub enum Opcode {
     /// Pull processor status from Stack
     Plp(u8, AddressingMode),
     /// Return from Interrupt
-    Rti(u8, AddressingMode)
+    Rti(u8, AddressingMode),
+    /// ASL + ORA (unofficial)
+    Slo(u8, AddressingMode),
+    /// ROL + AND (unofficial)
+    Rla(u8, AddressingMode),
+    /// LSR + EOR (unofficial)
+    Sre(u8, AddressingMode),
+    /// ROR + ADC (unofficial)
+    Rra(u8, AddressingMode),
+    /// Store A AND X (unofficial)
+    Sax(u8, AddressingMode),
+    /// LDA + LDX (unofficial)
+    Lax(u8, AddressingMode),
+    /// DEC + CMP (unofficial)
+    Dcp(u8, AddressingMode),
+    /// INC + SBC (unofficial)
+    Isc(u8, AddressingMode),
+    /// AND, then copy N to C (unofficial)
+    Anc(u8, AddressingMode),
+    /// AND + LSR A (unofficial)
+    Alr(u8, AddressingMode),
+    /// AND + ROR A (unofficial)
+    Arr(u8, AddressingMode),
+    /// X = A AND X - operand (unofficial)
+    Sbx(u8, AddressingMode),
+    /// A, X and SP = operand AND SP (unofficial)
+    Las(u8, AddressingMode),
+    /// A = (A OR magic) AND X AND operand (unofficial, unstable)
+    Ane(u8, AddressingMode),
+    /// A = X = (A OR magic) AND operand (unofficial, unstable)
+    Lxa(u8, AddressingMode),
+    /// Store A AND X AND (high byte + 1) (unofficial, unstable)
+    Sha(u8, AddressingMode),
+    /// Store X AND (high byte + 1) (unofficial, unstable)
+    Shx(u8, AddressingMode),
+    /// Store Y AND (high byte + 1) (unofficial, unstable)
+    Shy(u8, AddressingMode),
+    /// SP = A AND X, then store SP AND (high byte + 1) (unofficial, unstable)
+    Tas(u8, AddressingMode),
+    /// Halts the CPU until the next reset (unofficial)
+    Jam(u8, AddressingMode),
+    /// Branch Always (65C02)
+    Bra(u8, AddressingMode),
+    /// Push X Register on Stack (65C02)
+    Phx(u8, AddressingMode),
+    /// Push Y Register on Stack (65C02)
+    Phy(u8, AddressingMode),
+    /// Pull X Register from Stack (65C02)
+    Plx(u8, AddressingMode),
+    /// Pull Y Register from Stack (65C02)
+    Ply(u8, AddressingMode),
+    /// Store Zero (65C02)
+    Stz(u8, AddressingMode),
+    /// Test and Reset Bits (65C02)
+    Trb(u8, AddressingMode),
+    /// Test and Set Bits (65C02)
+    Tsb(u8, AddressingMode)
 }
 
 impl Opcode {
@@ -688,21 +996,21 @@ impl Opcode {
             ASL_ZERO_PAGE_X => Some(Opcode::Asl(value, AddressingMode::ZeroPageX)),
             ASL_ABSOLUTE => Some(Opcode::Asl(value, AddressingMode::Absolute)),
             ASL_ABSOLUTE_X => Some(Opcode::Asl(value, AddressingMode::AbsoluteX)),
-            LSR_ACCUMULATOR => Some(Opcode::Asl(value, AddressingMode::Accumulator)),
-            LSR_ZERO_PAGE => Some(Opcode::Asl(value, AddressingMode::ZeroPage)),
-            LSR_ZERO_PAGE_X => Some(Opcode::Asl(value, AddressingMode::ZeroPageX)),
-            LSR_ABSOLUTE => Some(Opcode::Asl(value, AddressingMode::Absolute)),
-            LSR_ABSOLUTE_X => Some(Opcode::Asl(value, AddressingMode::AbsoluteX)),
-            ROL_ACCUMULATOR => Some(Opcode::Asl(value, AddressingMode::Accumulator)),
-            ROL_ZERO_PAGE => Some(Opcode::Asl(value, AddressingMode::ZeroPage)),
-            ROL_ZERO_PAGE_X => Some(Opcode::Asl(value, AddressingMode::ZeroPageX)),
-            ROL_ABSOLUTE => Some(Opcode::Asl(value, AddressingMode::Absolute)),
-            ROL_ABSOLUTE_X => Some(Opcode::Asl(value, AddressingMode::AbsoluteX)),
-            ROR_ACCUMULATOR => Some(Opcode::Asl(value, AddressingMode::Accumulator)),
-            ROR_ZERO_PAGE => Some(Opcode::Asl(value, AddressingMode::ZeroPage)),
-            ROR_ZERO_PAGE_X => Some(Opcode::Asl(value, AddressingMode::ZeroPageX)),
-            ROR_ABSOLUTE => Some(Opcode::Asl(value, AddressingMode::Absolute)),
-            ROR_ABSOLUTE_X => Some(Opcode::Asl(value, AddressingMode::AbsoluteX)),
+            LSR_ACCUMULATOR => Some(Opcode::Lsr(value, AddressingMode::Accumulator)),
+            LSR_ZERO_PAGE => Some(Opcode::Lsr(value, AddressingMode::ZeroPage)),
+            LSR_ZERO_PAGE_X => Some(Opcode::Lsr(value, AddressingMode::ZeroPageX)),
+            LSR_ABSOLUTE => Some(Opcode::Lsr(value, AddressingMode::Absolute)),
+            LSR_ABSOLUTE_X => Some(Opcode::Lsr(value, AddressingMode::AbsoluteX)),
+            ROL_ACCUMULATOR => Some(Opcode::Rol(value, AddressingMode::Accumulator)),
+            ROL_ZERO_PAGE => Some(Opcode::Rol(value, AddressingMode::ZeroPage)),
+            ROL_ZERO_PAGE_X => Some(Opcode::Rol(value, AddressingMode::ZeroPageX)),
+            ROL_ABSOLUTE => Some(Opcode::Rol(value, AddressingMode::Absolute)),
+            ROL_ABSOLUTE_X => Some(Opcode::Rol(value, AddressingMode::AbsoluteX)),
+            ROR_ACCUMULATOR => Some(Opcode::Ror(value, AddressingMode::Accumulator)),
+            ROR_ZERO_PAGE => Some(Opcode::Ror(value, AddressingMode::ZeroPage)),
+            ROR_ZERO_PAGE_X => Some(Opcode::Ror(value, AddressingMode::ZeroPageX)),
+            ROR_ABSOLUTE => Some(Opcode::Ror(value, AddressingMode::Absolute)),
+            ROR_ABSOLUTE_X => Some(Opcode::Ror(value, AddressingMode::AbsoluteX)),
             JMP_ABSOLUTE => Some(Opcode::Jmp(value, AddressingMode::Absolute)),
             JMP_INDIRECT => Some(Opcode::Jmp(value, AddressingMode::Indirect)),
             JSR_ABSOLUTE => Some(Opcode::Jsr(value, AddressingMode::Absolute)),
@@ -714,20 +1022,410 @@ impl Opcode {
             BRK => Some(Opcode::Brk(value, AddressingMode::Implicit)),
             RTI => Some(Opcode::Rti(value, AddressingMode::Implicit)),
             NOP => Some(Opcode::Nop(value, AddressingMode::Implicit)),
+            SLO_ZERO_PAGE => Some(Opcode::Slo(value, AddressingMode::ZeroPage)),
+            SLO_ZERO_PAGE_X => Some(Opcode::Slo(value, AddressingMode::ZeroPageX)),
+            SLO_ABSOLUTE => Some(Opcode::Slo(value, AddressingMode::Absolute)),
+            SLO_ABSOLUTE_X => Some(Opcode::Slo(value, AddressingMode::AbsoluteX)),
+            SLO_ABSOLUTE_Y => Some(Opcode::Slo(value, AddressingMode::AbsoluteY)),
+            SLO_INDIRECT_X => Some(Opcode::Slo(value, AddressingMode::IndirectX)),
+            SLO_INDIRECT_Y => Some(Opcode::Slo(value, AddressingMode::IndirectY)),
+            RLA_ZERO_PAGE => Some(Opcode::Rla(value, AddressingMode::ZeroPage)),
+            RLA_ZERO_PAGE_X => Some(Opcode::Rla(value, AddressingMode::ZeroPageX)),
+            RLA_ABSOLUTE => Some(Opcode::Rla(value, AddressingMode::Absolute)),
+            RLA_ABSOLUTE_X => Some(Opcode::Rla(value, AddressingMode::AbsoluteX)),
+            RLA_ABSOLUTE_Y => Some(Opcode::Rla(value, AddressingMode::AbsoluteY)),
+            RLA_INDIRECT_X => Some(Opcode::Rla(value, AddressingMode::IndirectX)),
+            RLA_INDIRECT_Y => Some(Opcode::Rla(value, AddressingMode::IndirectY)),
+            SRE_ZERO_PAGE => Some(Opcode::Sre(value, AddressingMode::ZeroPage)),
+            SRE_ZERO_PAGE_X => Some(Opcode::Sre(value, AddressingMode::ZeroPageX)),
+            SRE_ABSOLUTE => Some(Opcode::Sre(value, AddressingMode::Absolute)),
+            SRE_ABSOLUTE_X => Some(Opcode::Sre(value, AddressingMode::AbsoluteX)),
+            SRE_ABSOLUTE_Y => Some(Opcode::Sre(value, AddressingMode::AbsoluteY)),
+            SRE_INDIRECT_X => Some(Opcode::Sre(value, AddressingMode::IndirectX)),
+            SRE_INDIRECT_Y => Some(Opcode::Sre(value, AddressingMode::IndirectY)),
+            RRA_ZERO_PAGE => Some(Opcode::Rra(value, AddressingMode::ZeroPage)),
+            RRA_ZERO_PAGE_X => Some(Opcode::Rra(value, AddressingMode::ZeroPageX)),
+            RRA_ABSOLUTE => Some(Opcode::Rra(value, AddressingMode::Absolute)),
+            RRA_ABSOLUTE_X => Some(Opcode::Rra(value, AddressingMode::AbsoluteX)),
+            RRA_ABSOLUTE_Y => Some(Opcode::Rra(value, AddressingMode::AbsoluteY)),
+            RRA_INDIRECT_X => Some(Opcode::Rra(value, AddressingMode::IndirectX)),
+            RRA_INDIRECT_Y => Some(Opcode::Rra(value, AddressingMode::IndirectY)),
+            SAX_ZERO_PAGE => Some(Opcode::Sax(value, AddressingMode::ZeroPage)),
+            SAX_ZERO_PAGE_Y => Some(Opcode::Sax(value, AddressingMode::ZeroPageY)),
+            SAX_ABSOLUTE => Some(Opcode::Sax(value, AddressingMode::Absolute)),
+            SAX_INDIRECT_X => Some(Opcode::Sax(value, AddressingMode::IndirectX)),
+            LAX_ZERO_PAGE => Some(Opcode::Lax(value, AddressingMode::ZeroPage)),
+            LAX_ZERO_PAGE_Y => Some(Opcode::Lax(value, AddressingMode::ZeroPageY)),
+            LAX_ABSOLUTE => Some(Opcode::Lax(value, AddressingMode::Absolute)),
+            LAX_ABSOLUTE_Y => Some(Opcode::Lax(value, AddressingMode::AbsoluteY)),
+            LAX_INDIRECT_X => Some(Opcode::Lax(value, AddressingMode::IndirectX)),
+            LAX_INDIRECT_Y => Some(Opcode::Lax(value, AddressingMode::IndirectY)),
+            DCP_ZERO_PAGE => Some(Opcode::Dcp(value, AddressingMode::ZeroPage)),
+            DCP_ZERO_PAGE_X => Some(Opcode::Dcp(value, AddressingMode::ZeroPageX)),
+            DCP_ABSOLUTE => Some(Opcode::Dcp(value, AddressingMode::Absolute)),
+            DCP_ABSOLUTE_X => Some(Opcode::Dcp(value, AddressingMode::AbsoluteX)),
+            DCP_ABSOLUTE_Y => Some(Opcode::Dcp(value, AddressingMode::AbsoluteY)),
+            DCP_INDIRECT_X => Some(Opcode::Dcp(value, AddressingMode::IndirectX)),
+            DCP_INDIRECT_Y => Some(Opcode::Dcp(value, AddressingMode::IndirectY)),
+            ISC_ZERO_PAGE => Some(Opcode::Isc(value, AddressingMode::ZeroPage)),
+            ISC_ZERO_PAGE_X => Some(Opcode::Isc(value, AddressingMode::ZeroPageX)),
+            ISC_ABSOLUTE => Some(Opcode::Isc(value, AddressingMode::Absolute)),
+            ISC_ABSOLUTE_X => Some(Opcode::Isc(value, AddressingMode::AbsoluteX)),
+            ISC_ABSOLUTE_Y => Some(Opcode::Isc(value, AddressingMode::AbsoluteY)),
+            ISC_INDIRECT_X => Some(Opcode::Isc(value, AddressingMode::IndirectX)),
+            ISC_INDIRECT_Y => Some(Opcode::Isc(value, AddressingMode::IndirectY)),
+            ANC_IMMEDIATE => Some(Opcode::Anc(value, AddressingMode::Immediate)),
+            ALR_IMMEDIATE => Some(Opcode::Alr(value, AddressingMode::Immediate)),
+            ARR_IMMEDIATE => Some(Opcode::Arr(value, AddressingMode::Immediate)),
+            SBX_IMMEDIATE => Some(Opcode::Sbx(value, AddressingMode::Immediate)),
+            LAS_ABSOLUTE_Y => Some(Opcode::Las(value, AddressingMode::AbsoluteY)),
+            ANE_IMMEDIATE => Some(Opcode::Ane(value, AddressingMode::Immediate)),
+            LXA_IMMEDIATE => Some(Opcode::Lxa(value, AddressingMode::Immediate)),
+            SHA_ABSOLUTE_Y => Some(Opcode::Sha(value, AddressingMode::AbsoluteY)),
+            SHA_INDIRECT_Y => Some(Opcode::Sha(value, AddressingMode::IndirectY)),
+            SHX_ABSOLUTE_Y => Some(Opcode::Shx(value, AddressingMode::AbsoluteY)),
+            SHY_ABSOLUTE_X => Some(Opcode::Shy(value, AddressingMode::AbsoluteX)),
+            TAS_ABSOLUTE_Y => Some(Opcode::Tas(value, AddressingMode::AbsoluteY)),
+            ANC_IMMEDIATE_2 => Some(Opcode::Anc(value, AddressingMode::Immediate)),
+            USBC_IMMEDIATE => Some(Opcode::Sbc(value, AddressingMode::Immediate)),
+            NOP_ABSOLUTE => Some(Opcode::Nop(value, AddressingMode::Absolute)),
+            _ if JAM.contains(&value) => Some(Opcode::Jam(value, AddressingMode::Implicit)),
+            _ if NOP_IMPLICIT.contains(&value) => Some(Opcode::Nop(value, AddressingMode::Implicit)),
+            _ if NOP_IMMEDIATE.contains(&value) => Some(Opcode::Nop(value, AddressingMode::Immediate)),
+            _ if NOP_ZERO_PAGE.contains(&value) => Some(Opcode::Nop(value, AddressingMode::ZeroPage)),
+            _ if NOP_ZERO_PAGE_X.contains(&value) => Some(Opcode::Nop(value, AddressingMode::ZeroPageX)),
+            _ if NOP_ABSOLUTE_X.contains(&value) => Some(Opcode::Nop(value, AddressingMode::AbsoluteX)),
             _ => None,
         }
     }
 
-    pub fn is_jump_instruction(&self) -> bool {
+    /// Returns true if the opcode is not part of the documented 6502 instruction set
+    pub fn is_unofficial(&self) -> bool {
+        match *self {
+            Opcode::Nop(value, _) => value != NOP,
+            Opcode::Sbc(value, _) => value == USBC_IMMEDIATE,
+            Opcode::Slo(_, _) | Opcode::Rla(_, _) | Opcode::Sre(_, _) | Opcode::Rra(_, _) |
+            Opcode::Sax(_, _) | Opcode::Lax(_, _) | Opcode::Dcp(_, _) | Opcode::Isc(_, _) |
+            Opcode::Anc(_, _) | Opcode::Alr(_, _) | Opcode::Arr(_, _) | Opcode::Sbx(_, _) |
+            Opcode::Las(_, _) => true,
+            _ => self.is_unstable(),
+        }
+    }
+
+    /// Returns true for the unofficial opcodes whose result depends on the chip (e.g. temperature,
+    /// bus capacitance) and for the opcodes that halt the CPU.
+    /// The CPU handles them according to its `UnstableOpcodePolicy`
+    pub fn is_unstable(&self) -> bool {
+        matches!(self,
+            Opcode::Ane(_, _) | Opcode::Lxa(_, _) | Opcode::Sha(_, _) | Opcode::Shx(_, _) |
+            Opcode::Shy(_, _) | Opcode::Tas(_, _) | Opcode::Jam(_, _))
+    }
+
+    /// Decodes the opcode as seen by the given CPU model
+    pub fn decode(value: u8, variant: CpuVariant) -> Option<Self> {
+        match variant {
+            CpuVariant::Cmos65C02 => Some(Self::from_u8_65c02(value)),
+            CpuVariant::Ricoh2A03 | CpuVariant::Nmos6502 => Self::from_u8(value),
+        }
+    }
+
+    /// The 65C02 reuses some unofficial NMOS opcodes for its new instructions.
+    /// The remaining ones are NOPs of different sizes
+    ///
+    /// see: [65C02 opcodes](http://www.6502.org/tutorials/65c02opcodes.html)
+    fn from_u8_65c02(value: u8) -> Self {
+        match value {
+            BRA => Opcode::Bra(value, AddressingMode::Relative),
+            PHX => Opcode::Phx(value, AddressingMode::Implicit),
+            PHY => Opcode::Phy(value, AddressingMode::Implicit),
+            PLX => Opcode::Plx(value, AddressingMode::Implicit),
+            PLY => Opcode::Ply(value, AddressingMode::Implicit),
+            STZ_ZERO_PAGE => Opcode::Stz(value, AddressingMode::ZeroPage),
+            STZ_ZERO_PAGE_X => Opcode::Stz(value, AddressingMode::ZeroPageX),
+            STZ_ABSOLUTE => Opcode::Stz(value, AddressingMode::Absolute),
+            STZ_ABSOLUTE_X => Opcode::Stz(value, AddressingMode::AbsoluteX),
+            TRB_ZERO_PAGE => Opcode::Trb(value, AddressingMode::ZeroPage),
+            TRB_ABSOLUTE => Opcode::Trb(value, AddressingMode::Absolute),
+            TSB_ZERO_PAGE => Opcode::Tsb(value, AddressingMode::ZeroPage),
+            TSB_ABSOLUTE => Opcode::Tsb(value, AddressingMode::Absolute),
+            BIT_IMMEDIATE => Opcode::Bit(value, AddressingMode::Immediate),
+            BIT_ZERO_PAGE_X => Opcode::Bit(value, AddressingMode::ZeroPageX),
+            BIT_ABSOLUTE_X => Opcode::Bit(value, AddressingMode::AbsoluteX),
+            INC_ACCUMULATOR => Opcode::Inc(value, AddressingMode::Accumulator),
+            DEC_ACCUMULATOR => Opcode::Dec(value, AddressingMode::Accumulator),
+            ORA_ZERO_PAGE_INDIRECT => Opcode::Ora(value, AddressingMode::ZeroPageIndirect),
+            AND_ZERO_PAGE_INDIRECT => Opcode::And(value, AddressingMode::ZeroPageIndirect),
+            EOR_ZERO_PAGE_INDIRECT => Opcode::Eor(value, AddressingMode::ZeroPageIndirect),
+            ADC_ZERO_PAGE_INDIRECT => Opcode::Adc(value, AddressingMode::ZeroPageIndirect),
+            STA_ZERO_PAGE_INDIRECT => Opcode::Sta(value, AddressingMode::ZeroPageIndirect),
+            LDA_ZERO_PAGE_INDIRECT => Opcode::Lda(value, AddressingMode::ZeroPageIndirect),
+            CMP_ZERO_PAGE_INDIRECT => Opcode::Cmp(value, AddressingMode::ZeroPageIndirect),
+            SBC_ZERO_PAGE_INDIRECT => Opcode::Sbc(value, AddressingMode::ZeroPageIndirect),
+            JMP_ABSOLUTE_INDIRECT_X => Opcode::Jmp(value, AddressingMode::AbsoluteIndirectX),
+            _ => match Self::from_u8(value) {
+                Some(opcode) if !opcode.is_unofficial() => opcode,
+                _ => Opcode::Nop(value, match value {
+                    0x44 => AddressingMode::ZeroPage,
+                    0x54 | 0xD4 | 0xF4 => AddressingMode::ZeroPageX,
+                    0x5C | 0xDC | 0xFC => AddressingMode::Absolute,
+                    _ if value & 0x0F == 0x02 => AddressingMode::Immediate,
+                    _ => AddressingMode::Implicit,
+                }),
+            },
+        }
+    }
+
+    /// Returns the mnemonic of the instruction, as written in assembly (e.g. "LDA")
+    pub fn mnemonic(&self) -> &'static str {
         match self {
-            Opcode::Jmp(_, _) | Opcode::Jsr(_, _) => true,
-            _ => false
+            Opcode::Adc(_, _) => "ADC",
+            Opcode::Sbc(_, _) => "SBC",
+            Opcode::Cmp(_, _) => "CMP",
+            Opcode::Cpx(_, _) => "CPX",
+            Opcode::Cpy(_, _) => "CPY",
+            Opcode::And(_, _) => "AND",
+            Opcode::Ora(_, _) => "ORA",
+            Opcode::Eor(_, _) => "EOR",
+            Opcode::Bit(_, _) => "BIT",
+            Opcode::Lda(_, _) => "LDA",
+            Opcode::Ldx(_, _) => "LDX",
+            Opcode::Ldy(_, _) => "LDY",
+            Opcode::Sta(_, _) => "STA",
+            Opcode::Stx(_, _) => "STX",
+            Opcode::Sty(_, _) => "STY",
+            Opcode::Clc(_, _) => "CLC",
+            Opcode::Cld(_, _) => "CLD",
+            Opcode::Cli(_, _) => "CLI",
+            Opcode::Clv(_, _) => "CLV",
+            Opcode::Sec(_, _) => "SEC",
+            Opcode::Sed(_, _) => "SED",
+            Opcode::Sei(_, _) => "SEI",
+            Opcode::Bpl(_, _) => "BPL",
+            Opcode::Bmi(_, _) => "BMI",
+            Opcode::Bvc(_, _) => "BVC",
+            Opcode::Bvs(_, _) => "BVS",
+            Opcode::Bcc(_, _) => "BCC",
+            Opcode::Bcs(_, _) => "BCS",
+            Opcode::Bne(_, _) => "BNE",
+            Opcode::Beq(_, _) => "BEQ",
+            Opcode::Tax(_, _) => "TAX",
+            Opcode::Tay(_, _) => "TAY",
+            Opcode::Txa(_, _) => "TXA",
+            Opcode::Tya(_, _) => "TYA",
+            Opcode::Tsx(_, _) => "TSX",
+            Opcode::Txs(_, _) => "TXS",
+            Opcode::Inc(_, _) => "INC",
+            Opcode::Inx(_, _) => "INX",
+            Opcode::Iny(_, _) => "INY",
+            Opcode::Dec(_, _) => "DEC",
+            Opcode::Dex(_, _) => "DEX",
+            Opcode::Dey(_, _) => "DEY",
+            Opcode::Asl(_, _) => "ASL",
+            Opcode::Lsr(_, _) => "LSR",
+            Opcode::Rol(_, _) => "ROL",
+            Opcode::Ror(_, _) => "ROR",
+            Opcode::Jmp(_, _) => "JMP",
+            Opcode::Jsr(_, _) => "JSR",
+            Opcode::Rts(_, _) => "RTS",
+            Opcode::Brk(_, _) => "BRK",
+            Opcode::Nop(_, _) => "NOP",
+            Opcode::Pha(_, _) => "PHA",
+            Opcode::Php(_, _) => "PHP",
+            Opcode::Pla(_, _) => "PLA",
+            Opcode::Plp(_, _) => "PLP",
+            Opcode::Rti(_, _) => "RTI",
+            Opcode::Slo(_, _) => "SLO",
+            Opcode::Rla(_, _) => "RLA",
+            Opcode::Sre(_, _) => "SRE",
+            Opcode::Rra(_, _) => "RRA",
+            Opcode::Sax(_, _) => "SAX",
+            Opcode::Lax(_, _) => "LAX",
+            Opcode::Dcp(_, _) => "DCP",
+            Opcode::Isc(_, _) => "ISC",
+            Opcode::Anc(_, _) => "ANC",
+            Opcode::Alr(_, _) => "ALR",
+            Opcode::Arr(_, _) => "ARR",
+            Opcode::Sbx(_, _) => "SBX",
+            Opcode::Las(_, _) => "LAS",
+            Opcode::Ane(_, _) => "ANE",
+            Opcode::Lxa(_, _) => "LXA",
+            Opcode::Sha(_, _) => "SHA",
+            Opcode::Shx(_, _) => "SHX",
+            Opcode::Shy(_, _) => "SHY",
+            Opcode::Tas(_, _) => "TAS",
+            Opcode::Jam(_, _) => "JAM",
+            Opcode::Bra(_, _) => "BRA",
+            Opcode::Phx(_, _) => "PHX",
+            Opcode::Phy(_, _) => "PHY",
+            Opcode::Plx(_, _) => "PLX",
+            Opcode::Ply(_, _) => "PLY",
+            Opcode::Stz(_, _) => "STZ",
+            Opcode::Trb(_, _) => "TRB",
+            Opcode::Tsb(_, _) => "TSB",
         }
     }
+
+    /// Returns the byte the instruction was decoded from (e.g. $A9 for LDA #imm)
+    pub fn value(&self) -> u8 {
+        match *self {
+            Opcode::Adc(value, _) | Opcode::Sbc(value, _) | Opcode::Cmp(value, _) | Opcode::Cpx(value, _) |
+            Opcode::Cpy(value, _) | Opcode::And(value, _) | Opcode::Ora(value, _) | Opcode::Eor(value, _) |
+            Opcode::Bit(value, _) | Opcode::Lda(value, _) | Opcode::Ldx(value, _) | Opcode::Ldy(value, _) |
+            Opcode::Sta(value, _) | Opcode::Stx(value, _) | Opcode::Sty(value, _) | Opcode::Clc(value, _) |
+            Opcode::Cld(value, _) | Opcode::Cli(value, _) | Opcode::Clv(value, _) | Opcode::Sec(value, _) |
+            Opcode::Sed(value, _) | Opcode::Sei(value, _) | Opcode::Bpl(value, _) | Opcode::Bmi(value, _) |
+            Opcode::Bvc(value, _) | Opcode::Bvs(value, _) | Opcode::Bcc(value, _) | Opcode::Bcs(value, _) |
+            Opcode::Bne(value, _) | Opcode::Beq(value, _) | Opcode::Tax(value, _) | Opcode::Tay(value, _) |
+            Opcode::Txa(value, _) | Opcode::Tya(value, _) | Opcode::Tsx(value, _) | Opcode::Txs(value, _) |
+            Opcode::Inc(value, _) | Opcode::Inx(value, _) | Opcode::Iny(value, _) | Opcode::Dec(value, _) |
+            Opcode::Dex(value, _) | Opcode::Dey(value, _) | Opcode::Asl(value, _) | Opcode::Lsr(value, _) |
+            Opcode::Rol(value, _) | Opcode::Ror(value, _) | Opcode::Jmp(value, _) | Opcode::Jsr(value, _) |
+            Opcode::Rts(value, _) | Opcode::Brk(value, _) | Opcode::Nop(value, _) | Opcode::Pha(value, _) |
+            Opcode::Php(value, _) | Opcode::Pla(value, _) | Opcode::Plp(value, _) | Opcode::Rti(value, _) |
+            Opcode::Slo(value, _) | Opcode::Rla(value, _) | Opcode::Sre(value, _) | Opcode::Rra(value, _) |
+            Opcode::Sax(value, _) | Opcode::Lax(value, _) | Opcode::Dcp(value, _) | Opcode::Isc(value, _) |
+            Opcode::Anc(value, _) | Opcode::Alr(value, _) | Opcode::Arr(value, _) | Opcode::Sbx(value, _) |
+            Opcode::Las(value, _) | Opcode::Ane(value, _) | Opcode::Lxa(value, _) | Opcode::Sha(value, _) |
+            Opcode::Shx(value, _) | Opcode::Shy(value, _) | Opcode::Tas(value, _) | Opcode::Jam(value, _) |
+            Opcode::Bra(value, _) | Opcode::Phx(value, _) | Opcode::Phy(value, _) | Opcode::Plx(value, _) |
+            Opcode::Ply(value, _) | Opcode::Stz(value, _) | Opcode::Trb(value, _) | Opcode::Tsb(value, _) => value,
+        }
+    }
+
+    pub fn is_jump_instruction(&self) -> bool {
+        matches!(self, Opcode::Jmp(_, _) | Opcode::Jsr(_, _))
+    }
+
+    pub fn is_branch_instruction(&self) -> bool {
+        matches!(self,
+            Opcode::Bcc(_, _) | Opcode::Bcs(_, _) | Opcode::Beq(_, _) | Opcode::Bmi(_, _) |
+            Opcode::Bne(_, _) | Opcode::Bpl(_, _) | Opcode::Bvc(_, _) | Opcode::Bvs(_, _) |
+            Opcode::Bra(_, _))
+    }
+
+    /// Returns the base amount of cycles the instruction takes to execute.
+    /// It doesn't include the extra cycles due to page crossing or taken branches,
+    /// as they depend on the CPU state at the time of execution.
+    ///
+    /// see: [6502 instruction set](https://www.masswerk.at/6502/6502_instruction_set.html)
+    pub fn cycles(&self) -> u8 {
+        match *self {
+            Opcode::Brk(_, _) => 7,
+            Opcode::Jsr(_, _) | Opcode::Rts(_, _) | Opcode::Rti(_, _) => 6,
+            Opcode::Jmp(_, AddressingMode::Indirect) => 5,
+            Opcode::Jmp(_, AddressingMode::AbsoluteIndirectX) => 6,
+            Opcode::Jmp(_, _) => 3,
+            Opcode::Pha(_, _) | Opcode::Php(_, _) | Opcode::Phx(_, _) | Opcode::Phy(_, _) => 3,
+            Opcode::Pla(_, _) | Opcode::Plp(_, _) | Opcode::Plx(_, _) | Opcode::Ply(_, _) => 4,
+
+            // Read-Modify-Write instructions need extra cycles to write the result back
+            Opcode::Asl(_, mode) | Opcode::Lsr(_, mode) | Opcode::Rol(_, mode) |
+            Opcode::Ror(_, mode) | Opcode::Inc(_, mode) | Opcode::Dec(_, mode) |
+            Opcode::Trb(_, mode) | Opcode::Tsb(_, mode) => match mode {
+                AddressingMode::Accumulator => 2,
+                AddressingMode::ZeroPage => 5,
+                AddressingMode::AbsoluteX => 7,
+                _ => 6,
+            },
+
+            // Unofficial Read-Modify-Write instructions always take the worst case
+            Opcode::Slo(_, mode) | Opcode::Rla(_, mode) | Opcode::Sre(_, mode) |
+            Opcode::Rra(_, mode) | Opcode::Dcp(_, mode) | Opcode::Isc(_, mode) => match mode {
+                AddressingMode::ZeroPage => 5,
+                AddressingMode::ZeroPageX | AddressingMode::Absolute => 6,
+                AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => 7,
+                _ => 8,
+            },
+
+            // Stores always take the worst case (no page crossing optimization)
+            Opcode::Sta(_, mode) | Opcode::Stx(_, mode) | Opcode::Sty(_, mode) | Opcode::Sax(_, mode) |
+            Opcode::Sha(_, mode) | Opcode::Shx(_, mode) | Opcode::Shy(_, mode) | Opcode::Tas(_, mode) |
+            Opcode::Stz(_, mode) => match mode {
+                AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => 5,
+                AddressingMode::IndirectY => 6,
+                _ => base_cycles(mode),
+            },
+
+            _ => base_cycles(self.addressing_mode()),
+        }
+    }
+
+    /// Returns true if the instruction takes one extra cycle when
+    /// the effective address crosses a page boundary.
+    /// Only read instructions using AbsoluteX, AbsoluteY or IndirectY addressing are affected.
+    pub fn has_page_crossing_penalty(&self) -> bool {
+        match *self {
+            Opcode::Adc(_, mode) | Opcode::Sbc(_, mode) | Opcode::Cmp(_, mode) |
+            Opcode::And(_, mode) | Opcode::Ora(_, mode) | Opcode::Eor(_, mode) |
+            Opcode::Lda(_, mode) | Opcode::Ldx(_, mode) | Opcode::Ldy(_, mode) | Opcode::Lax(_, mode) |
+            Opcode::Las(_, mode) | Opcode::Nop(_, mode) | Opcode::Bit(_, mode) => matches!(mode,
+                AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::IndirectY),
+            _ => false,
+        }
+    }
+
+    pub fn addressing_mode(&self) -> AddressingMode {
+        match *self {
+            Opcode::Adc(_, mode) | Opcode::Sbc(_, mode) | Opcode::Cmp(_, mode) | Opcode::Cpx(_, mode) |
+            Opcode::Cpy(_, mode) | Opcode::And(_, mode) | Opcode::Ora(_, mode) | Opcode::Eor(_, mode) |
+            Opcode::Bit(_, mode) | Opcode::Lda(_, mode) | Opcode::Ldx(_, mode) | Opcode::Ldy(_, mode) |
+            Opcode::Sta(_, mode) | Opcode::Stx(_, mode) | Opcode::Sty(_, mode) | Opcode::Clc(_, mode) |
+            Opcode::Cld(_, mode) | Opcode::Cli(_, mode) | Opcode::Clv(_, mode) | Opcode::Sec(_, mode) |
+            Opcode::Sed(_, mode) | Opcode::Sei(_, mode) | Opcode::Bpl(_, mode) | Opcode::Bmi(_, mode) |
+            Opcode::Bvc(_, mode) | Opcode::Bvs(_, mode) | Opcode::Bcc(_, mode) | Opcode::Bcs(_, mode) |
+            Opcode::Bne(_, mode) | Opcode::Beq(_, mode) | Opcode::Tax(_, mode) | Opcode::Tay(_, mode) |
+            Opcode::Txa(_, mode) | Opcode::Tya(_, mode) | Opcode::Tsx(_, mode) | Opcode::Txs(_, mode) |
+            Opcode::Inc(_, mode) | Opcode::Inx(_, mode) | Opcode::Iny(_, mode) | Opcode::Dec(_, mode) |
+            Opcode::Dex(_, mode) | Opcode::Dey(_, mode) | Opcode::Asl(_, mode) | Opcode::Lsr(_, mode) |
+            Opcode::Rol(_, mode) | Opcode::Ror(_, mode) | Opcode::Jmp(_, mode) | Opcode::Jsr(_, mode) |
+            Opcode::Rts(_, mode) | Opcode::Brk(_, mode) | Opcode::Nop(_, mode) | Opcode::Pha(_, mode) |
+            Opcode::Php(_, mode) | Opcode::Pla(_, mode) | Opcode::Plp(_, mode) | Opcode::Rti(_, mode) |
+            Opcode::Slo(_, mode) | Opcode::Rla(_, mode) | Opcode::Sre(_, mode) | Opcode::Rra(_, mode) |
+            Opcode::Sax(_, mode) | Opcode::Lax(_, mode) | Opcode::Dcp(_, mode) | Opcode::Isc(_, mode) |
+            Opcode::Anc(_, mode) | Opcode::Alr(_, mode) | Opcode::Arr(_, mode) | Opcode::Sbx(_, mode) |
+            Opcode::Las(_, mode) | Opcode::Ane(_, mode) | Opcode::Lxa(_, mode) | Opcode::Sha(_, mode) |
+            Opcode::Shx(_, mode) | Opcode::Shy(_, mode) | Opcode::Tas(_, mode) | Opcode::Jam(_, mode) |
+            Opcode::Bra(_, mode) | Opcode::Phx(_, mode) | Opcode::Phy(_, mode) | Opcode::Plx(_, mode) |
+            Opcode::Ply(_, mode) | Opcode::Stz(_, mode) | Opcode::Trb(_, mode) | Opcode::Tsb(_, mode) => mode,
+        }
+    }
+}
+
+/// Cycles taken by an instruction that reads its operand using the given addressing mode
+fn base_cycles(mode: AddressingMode) -> u8 {
+    match mode {
+        AddressingMode::Implicit => 2,
+        AddressingMode::Accumulator => 2,
+        AddressingMode::Immediate => 2,
+        AddressingMode::Relative => 2,
+        AddressingMode::ZeroPage => 3,
+        AddressingMode::ZeroPageX => 4,
+        AddressingMode::ZeroPageY => 4,
+        AddressingMode::Absolute => 4,
+        AddressingMode::AbsoluteX => 4,
+        AddressingMode::AbsoluteY => 4,
+        AddressingMode::Indirect => 5,
+        AddressingMode::IndirectX => 6,
+        AddressingMode::IndirectY => 5,
+        AddressingMode::ZeroPageIndirect => 5,
+        AddressingMode::AbsoluteIndirectX => 6,
+    }
 }
 
-pub fn translate_instruction_to_opcode(mnemonic: &str, addressing_mode: AddressingMode) -> Result<u8, InstructionError> {
+/// The 65C02 extensions are only translated when that model is selected
+pub fn translate_instruction_to_opcode(mnemonic: &str, addressing_mode: AddressingMode, variant: CpuVariant) -> Result<u8, InstructionError> {
     OPCODE_MAP.get(&(mnemonic, addressing_mode))
+        .or_else(|| match variant {
+            CpuVariant::Cmos65C02 => CMOS_OPCODE_MAP.get(&(mnemonic, addressing_mode)),
+            CpuVariant::Ricoh2A03 | CpuVariant::Nmos6502 => None,
+        })
+        .copied()
+        .ok_or_else(|| InstructionError::InvalidMnemonicAndAddressingModePair(mnemonic.to_string(), addressing_mode.to_string()))
+}
+
+/// Same as `translate_instruction_to_opcode`, but for unofficial instructions
+pub fn translate_unofficial_instruction_to_opcode(mnemonic: &str, addressing_mode: AddressingMode) -> Result<u8, InstructionError> {
+    UNOFFICIAL_OPCODE_MAP.get(&(mnemonic, addressing_mode))
         .copied()
         .ok_or_else(|| InstructionError::InvalidMnemonicAndAddressingModePair(mnemonic.to_string(), addressing_mode.to_string()))
 }
@@ -736,7 +1434,89 @@ pub fn is_valid_mnemonic(mnemonic: &str) -> bool {
     OPCODE_SET.contains(mnemonic)
 }
 
-pub fn addressing_modes_from_mnemonic(mnemonic: &str) -> Result<Vec<AddressingMode>, InstructionError> {
+pub fn is_cmos_mnemonic(mnemonic: &str) -> bool {
+    CMOS_OPCODE_SET.contains(mnemonic)
+}
+
+pub fn is_unofficial_mnemonic(mnemonic: &str) -> bool {
+    UNOFFICIAL_OPCODE_MAP.keys().any(|(unofficial_mnemonic, _)| *unofficial_mnemonic == mnemonic && mnemonic != "NOP")
+}
+
+/// Addressing modes of the unofficial instructions. NOP also accepts operands, as unofficial NOPs read memory
+pub fn unofficial_addressing_modes_from_mnemonic(mnemonic: &str) -> Result<Vec<AddressingMode>, InstructionError> {
+    match mnemonic {
+        "SLO" | "RLA" | "SRE" | "RRA" | "DCP" | "ISC" => Ok(vec![
+            AddressingMode::ZeroPage,
+            AddressingMode::ZeroPageX,
+            AddressingMode::Absolute,
+            AddressingMode::AbsoluteX,
+            AddressingMode::AbsoluteY,
+            AddressingMode::IndirectX,
+            AddressingMode::IndirectY,
+        ]),
+        "SAX" => Ok(vec![
+            AddressingMode::ZeroPage,
+            AddressingMode::ZeroPageY,
+            AddressingMode::Absolute,
+            AddressingMode::IndirectX,
+        ]),
+        "LAX" => Ok(vec![
+            AddressingMode::ZeroPage,
+            AddressingMode::ZeroPageY,
+            AddressingMode::Absolute,
+            AddressingMode::AbsoluteY,
+            AddressingMode::IndirectX,
+            AddressingMode::IndirectY,
+        ]),
+        "ANC" | "ALR" | "ARR" | "SBX" | "ANE" | "LXA" => Ok(vec![AddressingMode::Immediate]),
+        "LAS" | "SHX" | "TAS" => Ok(vec![AddressingMode::AbsoluteY]),
+        "SHY" => Ok(vec![AddressingMode::AbsoluteX]),
+        "SHA" => Ok(vec![AddressingMode::AbsoluteY, AddressingMode::IndirectY]),
+        "JAM" => Ok(vec![AddressingMode::Implicit]),
+        "NOP" => Ok(vec![
+            AddressingMode::Immediate,
+            AddressingMode::ZeroPage,
+            AddressingMode::ZeroPageX,
+            AddressingMode::Absolute,
+            AddressingMode::AbsoluteX,
+        ]),
+        _ => Err(InstructionError::InvalidInstruction(mnemonic.to_string())),
+    }
+}
+
+/// The 65C02 extensions are only included when that model is selected
+pub fn addressing_modes_from_mnemonic(mnemonic: &str, variant: CpuVariant) -> Result<Vec<AddressingMode>, InstructionError> {
+    let addressing_modes = nmos_addressing_modes_from_mnemonic(mnemonic);
+    if variant != CpuVariant::Cmos65C02 {
+        return addressing_modes;
+    }
+
+    let mut extended_addressing_modes = addressing_modes.unwrap_or_default();
+    match mnemonic {
+        "ADC" | "SBC" | "CMP" | "AND" | "EOR" | "ORA" | "LDA" | "STA" => extended_addressing_modes.push(AddressingMode::ZeroPageIndirect),
+        "BIT" => extended_addressing_modes.extend([AddressingMode::Immediate, AddressingMode::ZeroPageX, AddressingMode::AbsoluteX]),
+        "INC" | "DEC" => extended_addressing_modes.insert(0, AddressingMode::Accumulator),
+        "JMP" => extended_addressing_modes.push(AddressingMode::AbsoluteIndirectX),
+        "BRA" => extended_addressing_modes.push(AddressingMode::Relative),
+        "PHX" | "PHY" | "PLX" | "PLY" => extended_addressing_modes.push(AddressingMode::Implicit),
+        "STZ" => extended_addressing_modes.extend([
+            AddressingMode::ZeroPage,
+            AddressingMode::ZeroPageX,
+            AddressingMode::Absolute,
+            AddressingMode::AbsoluteX,
+        ]),
+        "TRB" | "TSB" => extended_addressing_modes.extend([AddressingMode::ZeroPage, AddressingMode::Absolute]),
+        _ => ()
+    }
+
+    if extended_addressing_modes.is_empty() {
+        Err(InstructionError::InvalidInstruction(mnemonic.to_string()))
+    } else {
+        Ok(extended_addressing_modes)
+    }
+}
+
+fn nmos_addressing_modes_from_mnemonic(mnemonic: &str) -> Result<Vec<AddressingMode>, InstructionError> {
     match mnemonic {
         "ADC" | "SBC" | "CMP" | "AND" | "EOR" | "ORA" | "LDA" => Ok(vec![
             AddressingMode::Immediate,
@@ -755,7 +1535,7 @@ pub fn addressing_modes_from_mnemonic(mnemonic: &str) -> Result<Vec<AddressingMo
             AddressingMode::Absolute,
             AddressingMode::AbsoluteX,
         ]),
-        "BCC" | "BSC" | "BEQ" | "BMI" | "BNE" | "BPL" | "BVC" | "BVS" => Ok(vec![AddressingMode::Relative]),
+        "BCC" | "BCS" | "BEQ" | "BMI" | "BNE" | "BPL" | "BVC" | "BVS" => Ok(vec![AddressingMode::Relative]),
         "BIT" => Ok(vec![AddressingMode::ZeroPage, AddressingMode::Absolute]),
         "BRK" | "CLC" | "CLD" | "CLI" | "CLV" | "DEX" | "DEY" | "INX" | "INY" | "NOP" | "PHA" | "PHP" | "PLA" | "PLP" | "RTI" | "RTS" | "SEC" | "SED" | "SEI" | "TAX" | "TAY" | "TSX" | "TXA" | "TXS" | "TYA" => Ok(vec![AddressingMode::Implicit]),
         "CPX" | "CPY" => Ok(vec![
@@ -16,7 +16,7 @@ fn execute_adc_immediate() {
     cpu.memory.write(operand_2, initial_address+1);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_IMMEDIATE));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_IMMEDIATE).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -38,7 +38,7 @@ fn execute_adc_zero_page() {
     cpu.memory.write(operand_2, zero_page_address as u16);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ZERO_PAGE));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ZERO_PAGE).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -63,7 +63,7 @@ fn execute_adc_zero_page_x() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ZERO_PAGE_X));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ZERO_PAGE_X).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -88,7 +88,7 @@ fn execute_adc_zero_page_x_with_high_x_value() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ZERO_PAGE_X));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ZERO_PAGE_X).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -113,7 +113,7 @@ fn execute_adc_absolute() {
     cpu.memory.write(operand_2, absolute_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ABSOLUTE));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ABSOLUTE).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -140,7 +140,7 @@ fn execute_adc_absolute_x() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ABSOLUTE_X));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ABSOLUTE_X).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -168,7 +168,7 @@ fn execute_adc_absolute_y() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_ABSOLUTE_Y));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_ABSOLUTE_Y).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -198,7 +198,7 @@ fn execute_adc_indirect_x() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_INDIRECT_X));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_INDIRECT_X).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -229,7 +229,7 @@ fn execute_adc_indirect_x_with_high_x_value() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_INDIRECT_X));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_INDIRECT_X).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -259,7 +259,7 @@ fn execute_adc_indirect_y() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_INDIRECT_Y));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_INDIRECT_Y).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -289,7 +289,7 @@ fn execute_adc_indirect_y_with_high_y_value() {
     cpu.memory.write(operand_2, operand_address);
 
     // Then
-    assert_eq!(Ok(()), cpu.execute_instruction(opcode::ADC_INDIRECT_Y));
+    assert_eq!(Ok(()), cpu.execute_instruction(Opcode::from_u8(opcode::ADC_INDIRECT_Y).unwrap()));
     assert_eq!(cpu.registers.accumulator, expected_result);
 }
 
@@ -413,6 +413,168 @@ fn test_calculate_address_indirect_y() {
     assert_eq!(expected_value, result);
 }
 
+#[test]
+fn test_calculate_address_indirect_wraps_in_page() {
+    // Given
+    // JMP ($10FF) takes the high byte from $1000 on NMOS
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+
+    cpu.memory.write_array(&[0xFF, 0x10], 0x8000);
+    cpu.memory.write(0x34, 0x10FF);
+    cpu.memory.write(0x12, 0x1000);
+    cpu.memory.write(0x56, 0x1100);
+
+    // When
+    let result = cpu.calculate_address(AddressingMode::Indirect);
+
+    // Then
+    assert_eq!(0x1234, result);
+}
+
+#[test]
+fn test_calculate_address_indirect_x_wraps_in_zero_page() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.x_register = 0x0F;
+
+    cpu.memory.write(0xF0, 0x8000);
+    cpu.memory.write(0x34, 0x00FF);
+    cpu.memory.write(0x12, 0x0000);
+    cpu.memory.write(0x56, 0x0100);
+
+    // When
+    let result = cpu.calculate_address(AddressingMode::IndirectX);
+
+    // Then
+    assert_eq!(0x1234, result);
+}
+
+#[test]
+fn test_calculate_address_indirect_y_wraps_in_zero_page() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.y_register = 0x01;
+
+    cpu.memory.write(0xFF, 0x8000);
+    cpu.memory.write(0x34, 0x00FF);
+    cpu.memory.write(0x12, 0x0000);
+    cpu.memory.write(0x56, 0x0100);
+
+    // When
+    let result = cpu.calculate_address(AddressingMode::IndirectY);
+
+    // Then
+    assert_eq!(0x1235, result);
+}
+
+#[test]
+fn test_jmp_indirect_page_wrap_bug() {
+    // Given
+    // JMP ($02FF)
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x6C, 0xFF, 0x02], 0x0600);
+    cpu.memory.write(0x00, 0x02FF);
+    cpu.memory.write(0x07, 0x0200);
+    cpu.memory.write(0x08, 0x0300);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(5), result);
+    assert_eq!(0x0700, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_nop_at_end_of_memory_wraps_program_counter() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0xEA, 0xFFFF);
+    cpu.registers.program_counter = 0xFFFF;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0x0000, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_lda_absolute_at_end_of_memory_reads_operand_from_start() {
+    // Given
+    // LDA $1234 with the opcode at $FFFF and the operand at $0000
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0xAD, 0xFFFF);
+    cpu.memory.write_array(&[0x34, 0x12], 0x0000);
+    cpu.memory.write(0x69, 0x1234);
+    cpu.registers.program_counter = 0xFFFF;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(4), result);
+    assert_eq!(0x69, cpu.registers.accumulator);
+    assert_eq!(0x0002, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_lda_immediate_at_end_of_memory_wraps_program_counter() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xA9, 0x69], 0xFFFE);
+    cpu.registers.program_counter = 0xFFFE;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0x69, cpu.registers.accumulator);
+    assert_eq!(0x0000, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_jsr_at_end_of_memory_pushes_wrapped_return_address() {
+    // Given
+    // JSR $0600 with the opcode at $FFFE, so the last byte of the instruction is at $0000
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x20, 0x00], 0xFFFE);
+    cpu.memory.write(0x06, 0x0000);
+    cpu.registers.program_counter = 0xFFFE;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(6), result);
+    assert_eq!(0x0600, cpu.registers.program_counter);
+    assert_eq!(0x0000, cpu.memory.read_u16(register_bank::STACK_POINTER_BASE_ADDRESS + cpu.registers.stack_pointer.wrapping_add(1) as u16));
+}
+
+#[test]
+fn test_rts_pulling_end_of_memory_wraps_program_counter() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0x60, 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.stack_pointer = 0xFB;
+    cpu.memory.write_array(&[0xFF, 0xFF], 0x01FC);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(6), result);
+    assert_eq!(0x0000, cpu.registers.program_counter);
+    assert_eq!(0xFD, cpu.registers.stack_pointer);
+}
+
 #[test]
 fn test_calculate_address_zero_page() {
     // Given
@@ -430,6 +592,349 @@ fn test_calculate_address_zero_page() {
     assert_eq!(expected_value, result);
 }
 
+#[test]
+fn test_step_returns_base_cycles() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    // LDA $1020
+    cpu.memory.write_array(&[0xAD, 0x20, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(4), result);
+    assert_eq!(4, cpu.cycles());
+    assert_eq!(0x8003, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_step_absolute_x_read_with_page_crossing() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.x_register = 0x01;
+    // LDA $10FF,X
+    cpu.memory.write_array(&[0xBD, 0xFF, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(5), result);
+}
+
+#[test]
+fn test_step_absolute_y_read_without_page_crossing() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.y_register = 0x01;
+    // LDA $10FE,Y
+    cpu.memory.write_array(&[0xB9, 0xFE, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(4), result);
+}
+
+#[test]
+fn test_step_indirect_y_read_with_page_crossing() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.y_register = 0x10;
+    cpu.memory.write(0xF8, 0x0020);
+    cpu.memory.write(0x10, 0x0021);
+    // LDA ($20),Y
+    cpu.memory.write_array(&[0xB1, 0x20], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(6), result);
+}
+
+#[test]
+fn test_step_store_has_no_page_crossing_penalty() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.x_register = 0x01;
+    // STA $10FF,X
+    cpu.memory.write_array(&[0x9D, 0xFF, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(5), result);
+}
+
+#[test]
+fn test_step_read_modify_write() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    // INC $1020,X
+    cpu.memory.write_array(&[0xFE, 0x20, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(7), result);
+}
+
+#[test]
+fn test_step_branch_not_taken() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.status = CpuFlags::ZERO;
+    // BNE $10
+    cpu.memory.write_array(&[0xD0, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0x8002, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_step_branch_taken_same_page() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    // BNE $10
+    cpu.memory.write_array(&[0xD0, 0x10], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(3), result);
+    assert_eq!(0x8012, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_step_branch_taken_to_different_page() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    // BNE $FC (-4)
+    cpu.memory.write_array(&[0xD0, 0xFC], 0x8000);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(4), result);
+    assert_eq!(0x7FFE, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_cycles_are_accumulated() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $0600    a2 00     LDX #$00      2
+    // $0602    e8        INX           2 * 3
+    // $0603    e0 03     CPX #$03      2 * 3
+    // $0605    d0 fb     BNE $0602     3 * 2 + 2
+    // $0607    20 0b 06  JSR $060b     6
+    // $060a    00        BRK           7
+    // $060b    60        RTS           6
+    let program: [u8; 12] = [0xA2, 0x00, 0xE8, 0xE0, 0x03, 0xD0, 0xFB, 0x20, 0x0B, 0x06, 0x00, 0x60];
+    let cpu = execute_program(&program);
+
+    assert_eq!(cpu.cycles(), 2 + 2 * 3 + 2 * 3 + 3 * 2 + 2 + 6 + 6 + 7);
+}
+
+#[test]
+fn test_reset() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0x00, 0xFFFC);
+    cpu.memory.write(0xC0, 0xFFFD);
+
+    // When
+    cpu.reset();
+
+    // Then
+    assert_eq!(0xC000, cpu.registers.program_counter);
+    assert_eq!(0xFD, cpu.registers.stack_pointer);
+    assert!(cpu.registers.status.contains(CpuFlags::INTERRUPT_DISABLE));
+    assert_eq!(7, cpu.cycles());
+}
+
+#[test]
+fn test_nmi_is_serviced_before_next_instruction() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.status = CpuFlags::INTERRUPT_DISABLE;
+    cpu.memory.write(0x00, 0xFFFA);
+    cpu.memory.write(0x90, 0xFFFB);
+    // NMI handler: INX
+    cpu.memory.write(0xE8, 0x9000);
+
+    // When
+    cpu.set_nmi_line(true);
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(7 + 2), result);
+    assert_eq!(0x9001, cpu.registers.program_counter);
+    assert_eq!(0x01, cpu.registers.x_register);
+    assert_eq!(0x8000, cpu.memory.read_u16(0x01FE));
+    assert_eq!((CpuFlags::INTERRUPT_DISABLE | CpuFlags::UNUSED).bits(), cpu.memory.read(0x01FD));
+}
+
+#[test]
+fn test_nmi_is_edge_triggered() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.memory.write(0x00, 0xFFFA);
+    cpu.memory.write(0x90, 0xFFFB);
+    // NOP everywhere we are going to execute
+    cpu.memory.write(0xEA, 0x9000);
+    cpu.memory.write(0xEA, 0x9001);
+
+    // When
+    cpu.set_nmi_line(true);
+    cpu.step().unwrap();
+    cpu.set_nmi_line(true);
+    cpu.step().unwrap();
+
+    // Then
+    assert_eq!(0x9002, cpu.registers.program_counter);
+    assert_eq!(0xFC, cpu.registers.stack_pointer);
+}
+
+#[test]
+fn test_irq_is_serviced_when_interrupts_enabled() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.memory.write(0x00, 0xFFFE);
+    cpu.memory.write(0xA0, 0xFFFF);
+    // IRQ handler: NOP
+    cpu.memory.write(0xEA, 0xA000);
+
+    // When
+    cpu.set_irq_line(true);
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(7 + 2), result);
+    assert_eq!(0xA001, cpu.registers.program_counter);
+    assert!(cpu.registers.status.contains(CpuFlags::INTERRUPT_DISABLE));
+    assert_eq!(CpuFlags::UNUSED.bits(), cpu.memory.read(0x01FD));
+}
+
+#[test]
+fn test_irq_is_masked_by_interrupt_disable() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.registers.status = CpuFlags::INTERRUPT_DISABLE;
+    cpu.memory.write(0x00, 0xFFFE);
+    cpu.memory.write(0xA0, 0xFFFF);
+    // NOP
+    cpu.memory.write(0xEA, 0x8000);
+
+    // When
+    cpu.set_irq_line(true);
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0x8001, cpu.registers.program_counter);
+    assert_eq!(0xFF, cpu.registers.stack_pointer);
+}
+
+#[test]
+fn test_brk_and_rti_return_after_padding_byte() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $8000    00 ff     BRK
+    // $8002    e8        INX
+    // $9000    40        RTI
+    let mut cpu = Cpu::new();
+    cpu.registers.program_counter = 0x8000;
+    cpu.memory.write_array(&[0x00, 0xFF, 0xE8], 0x8000);
+    cpu.memory.write(0x40, 0x9000);
+    cpu.memory.write(0x00, 0xFFFE);
+    cpu.memory.write(0x90, 0xFFFF);
+
+    assert_eq!(Ok(7), cpu.step().map(|step| step.cycles));
+    assert_eq!(0x9000, cpu.registers.program_counter);
+    assert_eq!(Ok(6), cpu.step().map(|step| step.cycles));
+    assert_eq!(0x8002, cpu.registers.program_counter);
+    assert_eq!(Ok(2), cpu.step().map(|step| step.cycles));
+    assert_eq!(0x01, cpu.registers.x_register);
+    assert_eq!(0xFF, cpu.registers.stack_pointer);
+}
+
+/// Bus with a single register at $2000 that is cleared when read, like many hardware registers
+struct ClearOnReadBus {
+    memory: Memory,
+    register: u8,
+}
+
+impl Bus for ClearOnReadBus {
+    fn read(&mut self, address: u16) -> u8 {
+        if address == 0x2000 {
+            let value = self.register;
+            self.register = 0;
+            value
+        } else {
+            self.memory.read(address)
+        }
+    }
+
+    fn write(&mut self, data: u8, address: u16) {
+        if address == 0x2000 {
+            self.register = data;
+        } else {
+            self.memory.write(data, address);
+        }
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        if address == 0x2000 { self.register } else { self.memory.read(address) }
+    }
+}
+
+#[test]
+fn test_cpu_reads_and_writes_through_generic_bus() {
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $0600    a9 42     LDA #$42
+    // $0602    8d 00 20  STA $2000
+    // $0605    ae 00 20  LDX $2000
+    // $0608    ac 00 20  LDY $2000
+    let mut memory = Memory::new();
+    memory.write_array(&[0xA9, 0x42, 0x8D, 0x00, 0x20, 0xAE, 0x00, 0x20, 0xAC, 0x00, 0x20], 0x0600);
+    let mut cpu = Cpu::new_with_bus(ClearOnReadBus { memory, register: 0 });
+    cpu.registers.program_counter = 0x0600;
+
+    for _ in 0..4 {
+        cpu.step().unwrap();
+    }
+
+    assert_eq!(cpu.registers.x_register, 0x42);
+    assert_eq!(cpu.registers.y_register, 0x00);
+    assert_eq!(cpu.bus().peek(0x2000), 0x00);
+}
+
 #[test]
 fn test_easy_6502_our_first_program() {
 
@@ -445,7 +950,7 @@ fn test_easy_6502_our_first_program() {
     let program: [u8; 16] = [0xA9, 0x01, 0x8D, 0x00, 0x02, 0xA9, 0x05, 0x8D, 0x01, 0x02, 0xA9, 0x08, 0x8D, 0x02, 0x02, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0610);
+    assert_eq!(brk_return_address(&cpu), 0x0611);
     assert_eq!(cpu.registers.accumulator, 0x08);
     assert_eq!(cpu.memory.read(0x0200), 0x01);
     assert_eq!(cpu.memory.read(0x0201), 0x05);
@@ -466,7 +971,7 @@ fn test_easy_6502_instructions() {
     let program: [u8; 7] = [0xA9, 0xC0, 0xAA, 0xE8, 0x69, 0xC4, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0607);
+    assert_eq!(brk_return_address(&cpu), 0x0608);
     assert_eq!(cpu.registers.accumulator, 0x84);
     assert_eq!(cpu.registers.x_register, 0xC1);
 }
@@ -482,7 +987,7 @@ fn test_easy_6502_instructions_2() {
     let program: [u8; 7] = [0xA9, 0x80, 0x85, 0x01, 0x65, 0x01, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0607);
+    assert_eq!(brk_return_address(&cpu), 0x0608);
     // $80 + $80 = 100 => wrapped = 00
     assert_eq!(cpu.registers.accumulator, 0x00);
 }
@@ -503,7 +1008,7 @@ fn test_easy_6502_branching() {
     let program: [u8; 14] = [0xA2, 0x08, 0xCA, 0x8E, 0x00, 0x02, 0xE0, 0x03, 0xD0, 0xF8, 0x8E, 0x01, 0x02, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x060E);
+    assert_eq!(brk_return_address(&cpu), 0x060F);
     assert_eq!(cpu.registers.accumulator, 0x00);
     assert_eq!(cpu.registers.x_register, 0x03);
     assert_eq!(cpu.memory.read(0x0000), 0x00);
@@ -524,7 +1029,7 @@ fn test_easy_6502_addressing_modes_relative() {
     let program: [u8; 9] = [0xA9, 0x01, 0xC9, 0x02, 0xD0, 0x02, 0x85, 0x22, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0609);
+    assert_eq!(brk_return_address(&cpu), 0x060A);
     assert_eq!(cpu.registers.accumulator, 0x01);
     assert_eq!(cpu.memory.read(0x0022), 0x00);
 }
@@ -543,7 +1048,7 @@ fn test_easy_6502_addressing_modes_indirect() {
     let program: [u8; 11] = [0xA9, 0x01, 0x85, 0xF0, 0xA9, 0xCC, 0x85, 0xF1, 0x6C, 0xF0, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0xCC02);
+    assert_eq!(brk_return_address(&cpu), 0xCC03);
     assert_eq!(cpu.registers.accumulator, 0xCC);
     assert_eq!(cpu.memory.read_u16(0x00F0), 0xCC01);
 }
@@ -563,7 +1068,7 @@ fn test_easy_6502_addressing_modes_indexed_indirect() {
     let program: [u8; 18] = [0xA2, 0x01, 0xA9, 0x05, 0x85, 0x01, 0xA9, 0x07, 0x85, 0x02, 0xA0, 0x0A, 0x8C, 0x05, 0x07, 0xA1, 0x00, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0612);
+    assert_eq!(brk_return_address(&cpu), 0x0613);
     assert_eq!(cpu.registers.accumulator, 0x0A);
     assert_eq!(cpu.registers.x_register, 0x01);
     assert_eq!(cpu.registers.y_register, 0x0A);
@@ -588,7 +1093,7 @@ fn test_easy_6502_addressing_modes_indirect_indexed() {
     let program: [u8; 18] = [0xA0, 0x01, 0xA9, 0x03, 0x85, 0x01, 0xA9, 0x07, 0x85, 0x02, 0xA2, 0x0A, 0x8E, 0x04, 0x07, 0xB1, 0x01, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0612);
+    assert_eq!(brk_return_address(&cpu), 0x0613);
     assert_eq!(cpu.registers.accumulator, 0x0A);
     assert_eq!(cpu.registers.x_register, 0x0A);
     assert_eq!(cpu.registers.y_register, 0x01);
@@ -620,14 +1125,15 @@ fn test_easy_6502_the_stack() {
     let cpu = execute_program(&program);
     let mut expected_array: [u8; 16] = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0xA, 0xB, 0xC, 0xD, 0x0E, 0x0F];
 
-    assert_eq!(cpu.registers.program_counter, 0x0619);
+    assert_eq!(brk_return_address(&cpu), 0x061A);
     assert_eq!(cpu.registers.accumulator, 0x00);
     assert_eq!(cpu.registers.x_register, 0x10);
     assert_eq!(cpu.registers.y_register, 0x20);
-    assert_eq!(cpu.registers.stack_pointer, 0xFF);
+    assert_eq!(cpu.registers.stack_pointer, 0xFC);
     assert_eq!(cpu.memory.read_as_array(0x0200), expected_array);
     expected_array.reverse();
-    assert_eq!(cpu.memory.read_as_array(0x01F0), expected_array);
+    // The last 3 bytes of the stack are overwritten when BRK pushes PC and status
+    assert_eq!(cpu.memory.read_as_array::<13>(0x01F0), expected_array[..13]);
     assert_eq!(cpu.memory.read_as_array(0x0210), expected_array);
 }
 
@@ -644,7 +1150,7 @@ fn test_easy_6502_jumping_jmp() {
     let program: [u8; 12] = [0xA9, 0x03, 0x4C, 0x08, 0x06, 0x00, 0x00, 0x00, 0x8D, 0x00, 0x02, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x060C);
+    assert_eq!(brk_return_address(&cpu), 0x060D);
     assert_eq!(cpu.registers.accumulator, 0x03);
     assert_eq!(cpu.memory.read(0x0200), 0x03);
 }
@@ -666,22 +1172,586 @@ fn test_easy_6502_jumping_jsr_rts() {
     let program: [u8; 19] = [0x20, 0x09, 0x06, 0x20, 0x0C, 0x06, 0x20, 0x12, 0x06, 0xA2, 0x03, 0x60, 0xE8, 0xE0, 0x05, 0xD0, 0xFB, 0x60, 0x00];
     let cpu = execute_program(&program);
 
-    assert_eq!(cpu.registers.program_counter, 0x0613);
+    assert_eq!(brk_return_address(&cpu), 0x0614);
     assert_eq!(cpu.registers.accumulator, 0x00);
     assert_eq!(cpu.registers.x_register, 0x05);
     assert_eq!(cpu.registers.y_register, 0x00);
-    assert_eq!(cpu.registers.stack_pointer, 0xFD);
+    assert_eq!(cpu.registers.stack_pointer, 0xFA);
     assert_eq!(cpu.memory.read(0x0000), 0x00);
     assert_eq!(cpu.memory.read(0x01FE), 0x08);
     assert_eq!(cpu.memory.read(0x01FF), 0x06);
 }
 
+#[test]
+fn test_every_opcode_is_decoded() {
+    for value in 0..=0xFFu8 {
+        assert!(Opcode::from_u8(value).is_some(), "Opcode 0x{:02X} not decoded", value);
+    }
+}
+
+#[test]
+fn test_shift_opcodes_are_decoded_to_their_instructions() {
+    assert_eq!(Some(Opcode::Lsr(0x4A, AddressingMode::Accumulator)), Opcode::from_u8(0x4A));
+    assert_eq!(Some(Opcode::Rol(0x2E, AddressingMode::Absolute)), Opcode::from_u8(0x2E));
+    assert_eq!(Some(Opcode::Ror(0x76, AddressingMode::ZeroPageX)), Opcode::from_u8(0x76));
+}
+
+#[test]
+fn test_unofficial_opcodes_are_flagged() {
+    assert!(!Opcode::from_u8(0xEA).unwrap().is_unofficial());
+    assert!(!Opcode::from_u8(0xE9).unwrap().is_unofficial());
+    assert!(Opcode::from_u8(0xEB).unwrap().is_unofficial());
+    assert!(Opcode::from_u8(0x1A).unwrap().is_unofficial());
+    assert!(Opcode::from_u8(0xA7).unwrap().is_unofficial());
+    assert!(!Opcode::from_u8(0xA7).unwrap().is_unstable());
+    assert!(Opcode::from_u8(0x8B).unwrap().is_unstable());
+    assert!(Opcode::from_u8(0x02).unwrap().is_unstable());
+}
+
+#[test]
+fn test_lax_indirect_y_takes_page_crossing_penalty() {
+    // Given
+    // LAX ($10),Y with $10 -> $20FF, Y = 1
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xB3, 0x10], 0x0600);
+    cpu.memory.write_array(&[0xFF, 0x20], 0x0010);
+    cpu.memory.write(0x42, 0x2100);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.y_register = 0x01;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(6), result);
+    assert_eq!(0x42, cpu.registers.accumulator);
+    assert_eq!(0x42, cpu.registers.x_register);
+    assert_eq!(0x0602, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_dcp_absolute_y_takes_read_modify_write_cycles() {
+    // Given
+    // DCP $1000,Y
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xDB, 0x00, 0x10], 0x0600);
+    cpu.memory.write(0x05, 0x1002);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.y_register = 0x02;
+    cpu.registers.accumulator = 0x04;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(7), result);
+    assert_eq!(0x04, cpu.memory.read(0x1002));
+    assert!(cpu.registers.status.contains(CpuFlags::ZERO | CpuFlags::CARRY));
+    assert_eq!(0x0603, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_unofficial_nop_skips_its_operand() {
+    // Given
+    // NOP $10FF,X ; NOP #$01 ; NOP
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x1C, 0xFF, 0x10, 0x80, 0x01, 0x1A], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0x01;
+
+    // When
+    let cycles = [cpu.step().map(|step| step.cycles), cpu.step().map(|step| step.cycles), cpu.step().map(|step| step.cycles)];
+
+    // Then
+    assert_eq!([Ok(5), Ok(2), Ok(2)], cycles);
+    assert_eq!(0x0606, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_unstable_opcode_is_an_error_by_default() {
+    // Given
+    // ANE #$FF
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x8B, 0xFF], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Err(InstructionError::UnstableOpcode(0x8B)), result);
+    assert_eq!(0x0600, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_unstable_opcode_can_be_treated_as_nop() {
+    // Given
+    // ANE #$FF
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x8B, 0xFF], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0xFF;
+    cpu.set_unstable_opcode_policy(UnstableOpcodePolicy::Nop);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(0x0602, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_unstable_opcode_can_be_executed() {
+    // Given
+    // ANE #$FF
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x8B, 0xFF], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0xFF;
+    cpu.set_unstable_opcode_policy(UnstableOpcodePolicy::Execute);
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(2), result);
+    assert_eq!(0xEE, cpu.registers.accumulator);
+    assert_eq!(0x0602, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_jam_halts_cpu_until_reset() {
+    // Given
+    // JAM
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0x02, 0x0600);
+    cpu.memory.write_array(&[0x00, 0x07], 0xFFFC);
+    cpu.registers.program_counter = 0x0600;
+    cpu.set_unstable_opcode_policy(UnstableOpcodePolicy::Execute);
+
+    // When
+    cpu.step().unwrap();
+    cpu.set_irq_line(true);
+    let jammed_step = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert!(cpu.is_jammed());
+    assert_eq!(Ok(2), jammed_step);
+    assert_eq!(0x0600, cpu.registers.program_counter);
+
+    cpu.reset();
+    assert!(!cpu.is_jammed());
+    assert_eq!(0x0700, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_execute_program_fails_when_cpu_jams() {
+    // Given
+    // INX ; JAM
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xE8, 0x12], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.set_unstable_opcode_policy(UnstableOpcodePolicy::Execute);
+
+    // When
+    let result = cpu.execute_program();
+
+    // Then
+    assert_eq!(Err(InstructionError::CpuJammed(0x0601)), result);
+}
+
+#[test]
+fn test_65c02_extended_instructions() {
+    // Given
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $0600    a2 42     LDX #$42
+    // $0602    da        PHX
+    // $0603    7a        PLY
+    // $0604    9c 00 02  STZ $0200
+    // $0607    1a        INC A
+    // $0608    80 01     BRA $060b
+    // $060a    3a        DEC A
+    // $060b    b2 10     LDA ($10)
+    // $060d    04 20     TSB $20
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.memory.write_array(&[0xA2, 0x42, 0xDA, 0x7A, 0x9C, 0x00, 0x02, 0x1A, 0x80, 0x01, 0x3A, 0xB2, 0x10, 0x04, 0x20], 0x0600);
+    cpu.memory.write_array(&[0xFF, 0x02], 0x0010);
+    cpu.memory.write(0x81, 0x02FF);
+    cpu.memory.write(0x69, 0x0200);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let cycles: Vec<_> = (0..8).map(|_| cpu.step().map(|step| step.cycles)).collect();
+
+    // Then
+    assert_eq!(vec![Ok(2), Ok(3), Ok(4), Ok(4), Ok(2), Ok(3), Ok(5), Ok(5)], cycles);
+    assert_eq!(0x42, cpu.registers.y_register);
+    assert_eq!(0x00, cpu.memory.read(0x0200));
+    assert_eq!(0x81, cpu.registers.accumulator);
+    assert_eq!(0x81, cpu.memory.read(0x0020));
+    assert_eq!(0x060F, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_65c02_jmp_indirect_reads_pointer_across_page() {
+    // Given
+    // JMP ($10FF)
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.memory.write_array(&[0x6C, 0xFF, 0x10], 0x0600);
+    cpu.memory.write(0x00, 0x10FF);
+    cpu.memory.write(0x07, 0x1100);
+    cpu.memory.write(0x08, 0x1000);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.step().map(|step| step.cycles);
+
+    // Then
+    assert_eq!(Ok(6), result);
+    assert_eq!(0x0700, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_65c02_shift_absolute_x_takes_extra_cycle_only_across_page() {
+    // Given
+    // ASL $0200,X, LSR $02FF,X, ROL $0200,X, ROR $02FF,X with X = 1
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.memory.write_array(&[0x1E, 0x00, 0x02, 0x5E, 0xFF, 0x02, 0x3E, 0x00, 0x02, 0x7E, 0xFF, 0x02], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0x01;
+
+    // When
+    let cycles: Vec<u16> = (0..4).map(|_| cpu.step().unwrap().cycles).collect();
+
+    // Then
+    assert_eq!(vec![6, 7, 6, 7], cycles);
+}
+
+#[test]
+fn test_nmos_shift_absolute_x_always_takes_7_cycles() {
+    // Given
+    // ASL $0200,X, LSR $02FF,X with X = 1
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x1E, 0x00, 0x02, 0x5E, 0xFF, 0x02], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0x01;
+
+    // When
+    let cycles: Vec<u16> = (0..2).map(|_| cpu.step().unwrap().cycles).collect();
+
+    // Then
+    assert_eq!(vec![7, 7], cycles);
+}
+
+#[test]
+fn test_65c02_unofficial_opcodes_are_nops() {
+    // Given
+    // LAX on NMOS, a 1 byte NOP on 65C02 ; JAM on NMOS, a 2 bytes NOP on 65C02
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.memory.write_array(&[0xA7, 0x02, 0x10], 0x0600);
+    cpu.memory.write(0x42, 0x0010);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    cpu.step().unwrap();
+    cpu.step().unwrap();
+
+    // Then
+    assert!(!cpu.is_jammed());
+    assert_eq!(0x00, cpu.registers.accumulator);
+    assert_eq!(0x00, cpu.registers.x_register);
+    assert_eq!(0x0603, cpu.registers.program_counter);
+}
+
+#[test]
+fn test_65c02_interrupt_clears_decimal_mode() {
+    // Given
+    let mut cpu = Cpu::new();
+    cpu.set_variant(CpuVariant::Cmos65C02);
+    cpu.memory.write(0xEA, 0x0600);
+    cpu.memory.write_array(&[0x00, 0x07], 0xFFFA);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.status = CpuFlags::DECIMAL_MODE;
+
+    // When
+    cpu.set_nmi_line(true);
+    cpu.step().unwrap();
+
+    // Then
+    assert!(!cpu.registers.status.contains(CpuFlags::DECIMAL_MODE));
+}
+
+#[test]
+fn test_trace_uses_nintendulator_format() {
+    // Given
+    // First instructions of nestest, started at $C000
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x4C, 0xF5, 0xC5], 0xC000);
+    cpu.memory.write_array(&[0xA2, 0x00, 0x86, 0x00, 0x20, 0x2D, 0xC7], 0xC5F5);
+    cpu.memory.write_array(&[0x04, 0xA9], 0xC72D);
+    cpu.registers.program_counter = 0xC000;
+    cpu.registers.stack_pointer = 0xFD;
+    cpu.registers.status = CpuFlags::INTERRUPT_DISABLE | CpuFlags::UNUSED;
+
+    // When
+    let trace: Vec<String> = (0..5).map(|_| {
+        let line = cpu.trace();
+        cpu.step().unwrap();
+        line
+    }).collect();
+
+    // Then
+    assert_eq!(vec![
+        "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD",
+        "C5F5  A2 00     LDX #$00                        A:00 X:00 Y:00 P:24 SP:FD",
+        "C5F7  86 00     STX $00 = 00                    A:00 X:00 Y:00 P:26 SP:FD",
+        "C5F9  20 2D C7  JSR $C72D                       A:00 X:00 Y:00 P:26 SP:FD",
+        "C72D  04 A9    *NOP $A9 = 00                    A:00 X:00 Y:00 P:26 SP:FB",
+    ], trace);
+}
+
+#[test]
+fn test_trace_shows_effective_address_and_data() {
+    // Given
+    // LDA ($80),Y with $80 -> $02FF, Y = 1
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xB1, 0x80], 0x0600);
+    cpu.memory.write_array(&[0xFF, 0x02], 0x0080);
+    cpu.memory.write(0x5A, 0x0300);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.y_register = 0x01;
+
+    // When
+    let line = cpu.trace();
+
+    // Then
+    assert_eq!("0600  B1 80     LDA ($80),Y = 02FF @ 0300 = 5A  A:00 X:00 Y:01 P:00 SP:FF", line);
+}
+
+/// Address and bytes written to memory before tracing
+type MemoryBlock<'a> = (u16, &'a [u8]);
+
+#[test]
+fn test_trace_matches_nestest_log_lines() {
+    // Given
+    // CPU part of nestest.log lines: unofficial opcodes, JMP indirect on a page boundary and (zp),Y crossing the zero page
+    // (program counter, memory, A, X, Y, P, SP, expected line)
+    let lines: [(u16, &[MemoryBlock], [u8; 5], &str); 4] = [
+        (0xC6BD, &[(0xC6BD, &[0x04, 0xA9]), (0x00A9, &[0x00])], [0xAA, 0x97, 0x4E, 0xEF, 0xF5],
+            "C6BD  04 A9    *NOP $A9 = 00                    A:AA X:97 Y:4E P:EF SP:F5"),
+        (0xE0E7, &[(0xE0E7, &[0xE3, 0x45]), (0x0047, &[0x47, 0x06]), (0x0647, &[0xEB])], [0x40, 0x02, 0x00, 0x64, 0xFB],
+            "E0E7  E3 45    *ISB ($45,X) @ 47 = 0647 = EB    A:40 X:02 Y:00 P:64 SP:FB"),
+        (0xDB7E, &[(0xDB7E, &[0x6C, 0xFF, 0x02]), (0x02FF, &[0x00, 0x04]), (0x0200, &[0x03])], [0x60, 0x07, 0x00, 0x65, 0xF9],
+            "DB7E  6C FF 02  JMP ($02FF) = 0300              A:60 X:07 Y:00 P:65 SP:F9"),
+        (0xD959, &[(0xD959, &[0xB1, 0xFF]), (0x00FF, &[0x46]), (0x0000, &[0x01]), (0x0245, &[0x12])], [0x00, 0x34, 0xFF, 0x26, 0xFB],
+            "D959  B1 FF     LDA ($FF),Y = 0146 @ 0245 = 12  A:00 X:34 Y:FF P:26 SP:FB"),
+    ];
+
+    for (program_counter, memory, [accumulator, x, y, status, stack_pointer], expected_line) in lines {
+        let mut cpu = Cpu::new();
+        for (address, data) in memory {
+            cpu.memory.write_array(data, *address);
+        }
+        cpu.registers.program_counter = program_counter;
+        cpu.registers.accumulator = accumulator;
+        cpu.registers.x_register = x;
+        cpu.registers.y_register = y;
+        cpu.registers.status = CpuFlags::from_bits_truncate(status);
+        cpu.registers.stack_pointer = stack_pointer;
+
+        // When
+        let line = cpu.trace();
+
+        // Then
+        assert_eq!(expected_line, line);
+    }
+}
+
+#[test]
+fn test_run_until_trap_stops_at_self_loop() {
+    // Given
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $0600    a2 03     LDX #$03
+    // $0602    ca        DEX
+    // $0603    d0 fd     BNE $0602
+    // $0605    4c 05 06  JMP $0605
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xA2, 0x03, 0xCA, 0xD0, 0xFD, 0x4C, 0x05, 0x06], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.run_until_trap();
+
+    // Then
+    assert_eq!(Ok(0x0605), result);
+    assert_eq!(0x00, cpu.registers.x_register);
+}
+
+#[test]
+fn test_run_until_trap_detects_branch_to_itself() {
+    // Given
+    // $0600    f0 fe     BEQ $0600
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xF0, 0xFE], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.status = CpuFlags::ZERO;
+
+    // When
+    let result = cpu.run_until_trap();
+
+    // Then
+    assert_eq!(Ok(0x0600), result);
+}
+
+#[test]
+fn test_step_returns_executed_instruction() {
+    // Given
+    // Address  Hexdump   Dissassembly
+    // -------------------------------
+    // $0600    bd ff 10  LDA $10FF,X
+    // $0603    e8        INX
+    // $0604    d0 fa     BNE $0600
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xBD, 0xFF, 0x10, 0xE8, 0xD0, 0xFA], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+    cpu.registers.x_register = 0x01;
+
+    // When
+    let steps = [cpu.step(), cpu.step(), cpu.step()];
+
+    // Then
+    assert_eq!(Ok(StepRecord {
+        address: 0x0600,
+        opcode: Opcode::Lda(0xBD, AddressingMode::AbsoluteX),
+        addressing_mode: AddressingMode::AbsoluteX,
+        operand: Some(0x10FF),
+        effective_address: Some(0x1100),
+        cycles: 5
+    }), steps[0]);
+    assert_eq!(Ok(StepRecord {
+        address: 0x0603,
+        opcode: Opcode::Inx(0xE8, AddressingMode::Implicit),
+        addressing_mode: AddressingMode::Implicit,
+        operand: None,
+        effective_address: None,
+        cycles: 2
+    }), steps[1]);
+    assert_eq!(Ok(StepRecord {
+        address: 0x0604,
+        opcode: Opcode::Bne(0xD0, AddressingMode::Relative),
+        addressing_mode: AddressingMode::Relative,
+        operand: Some(0xFA),
+        effective_address: Some(0x0600),
+        cycles: 3
+    }), steps[2]);
+}
+
+#[test]
+fn test_run_for_cycles_stops_after_cycles_elapsed() {
+    // Given
+    // $0600    e8        INX
+    // $0601    4c 00 06  JMP $0600
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xE8, 0x4C, 0x00, 0x06], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.run_for_cycles(10);
+
+    // Then
+    // INX (2) + JMP (3) + INX (2) + JMP (3)
+    assert_eq!(Ok(10), result);
+    assert_eq!(0x02, cpu.registers.x_register);
+    assert_eq!(10, cpu.cycles());
+}
+
+#[test]
+fn test_run_until_checks_predicate_before_every_instruction() {
+    // Given
+    // $0600    e8        INX
+    // $0601    4c 00 06  JMP $0600
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0xE8, 0x4C, 0x00, 0x06], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.run_until(|cpu| cpu.registers.x_register == 0x03);
+
+    // Then
+    assert_eq!(Ok(12), result);
+    assert_eq!(0x0601, cpu.program_counter());
+    assert_eq!(Ok(0), cpu.run_until(|_| true));
+}
+
+#[test]
+fn test_run_until_stops_on_error() {
+    // Given
+    // ANE #$FF
+    let mut cpu = Cpu::new();
+    cpu.memory.write_array(&[0x8B, 0xFF], 0x0600);
+    cpu.registers.program_counter = 0x0600;
+
+    // When
+    let result = cpu.run_until(|_| false);
+
+    // Then
+    assert_eq!(Err(InstructionError::UnstableOpcode(0x8B)), result);
+}
+
+#[test]
+fn test_state_can_be_loaded_and_read_back() {
+    // Given
+    let state = CpuState {
+        program_counter: 0x0600,
+        stack_pointer: 0xF0,
+        accumulator: 0x01,
+        x_register: 0x02,
+        y_register: 0x03,
+        status: CpuFlags::CARRY
+    };
+    let mut cpu = Cpu::new();
+    cpu.memory.write(0xE8, 0x0600);
+
+    // When
+    cpu.load_state(state);
+    cpu.step().unwrap();
+
+    // Then
+    assert_eq!(CpuState { program_counter: 0x0601, x_register: 0x03, ..state }, cpu.state());
+}
+
+#[test]
+fn test_state_display_shows_flags_as_letters() {
+    // Given
+    let state = CpuState {
+        program_counter: 0xC000,
+        stack_pointer: 0xFD,
+        accumulator: 0x0A,
+        x_register: 0x0B,
+        y_register: 0x0C,
+        status: CpuFlags::NEGATIVE | CpuFlags::UNUSED | CpuFlags::INTERRUPT_DISABLE | CpuFlags::CARRY
+    };
+
+    // Then
+    assert_eq!("PC:C000 A:0A X:0B Y:0C SP:FD P:Nv-bdIzC", state.to_string());
+    assert_eq!("NV-BDIZC", CpuFlags::all().to_string());
+    assert_eq!("nv-bdizc", CpuFlags::empty().to_string());
+}
+
 fn execute_program(program: &[u8]) -> Cpu {
     let program_address: u16 = 0x0600;
     let mut cpu = Cpu::new();
 
     // Load program into memory, starting at 'program_address'
-    cpu.memory.write_array(&program, program_address);
+    cpu.memory.write_array(program, program_address);
     cpu.registers.program_counter = program_address;
     
     if let Err(e) = cpu.execute_program() {
@@ -689,4 +1759,11 @@ fn execute_program(program: &[u8]) -> Cpu {
         panic!();
     }
     cpu
-}
\ No newline at end of file
+}
+
+/// BRK pushes its address + 2 onto the stack before jumping to the IRQ vector,
+/// so it tells us where the program stopped
+fn brk_return_address(cpu: &Cpu) -> u16 {
+    let status_address = register_bank::STACK_POINTER_BASE_ADDRESS + cpu.registers.stack_pointer as u16 + 1;
+    cpu.memory.read_u16(status_address + 1)
+}
@@ -0,0 +1,109 @@
+use super::Cpu;
+use super::opcode::Opcode;
+use crate::bus::Bus;
+use crate::memory::types::AddressingMode;
+
+impl<B: Bus> Cpu<B> {
+    /// Formats the instruction pointed by PC and the registers, before executing it,
+    /// as the CPU part of a Nintendulator trace line:
+    ///
+    /// `C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD`
+    ///
+    /// Unofficial opcodes are marked with `*`. Operands show the effective address and
+    /// the data found there. Memory is peeked, so tracing has no side effect on the bus
+    ///
+    /// see: [nestest](https://www.qmtpro.com/~nes/misc/nestest.txt)
+    pub fn trace(&self) -> String {
+        let program_counter = self.registers.program_counter;
+        let op = self.memory.peek(program_counter);
+        let (disassembly, byte_size, unofficial) = match Opcode::decode(op, self.variant) {
+            Some(opcode) => {
+                let operand = self.trace_operand(opcode);
+                let mnemonic = trace_mnemonic(opcode);
+                let disassembly = if operand.is_empty() { mnemonic.to_string() } else { format!("{} {}", mnemonic, operand) };
+                (disassembly, opcode.addressing_mode().byte_size(), opcode.is_unofficial())
+            },
+            None => ("???".to_string(), 1, true)
+        };
+
+        let bytes = (0..byte_size as u16)
+            .map(|offset| format!("{:02X}", self.memory.peek(program_counter.wrapping_add(offset))))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!("{:04X}  {:<8} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X}",
+            program_counter,
+            bytes,
+            if unofficial { '*' } else { ' ' },
+            disassembly,
+            self.registers.accumulator,
+            self.registers.x_register,
+            self.registers.y_register,
+            self.registers.status.bits(),
+            self.registers.stack_pointer)
+    }
+
+    fn trace_operand(&self, opcode: Opcode) -> String {
+        let operand_address = self.registers.program_counter.wrapping_add(1);
+        let byte = self.memory.peek(operand_address);
+        let word = self.memory.peek_u16(operand_address);
+        let x = self.registers.x_register;
+        let y = self.registers.y_register;
+
+        match opcode.addressing_mode() {
+            AddressingMode::Implicit => String::new(),
+            AddressingMode::Accumulator => "A".to_string(),
+            AddressingMode::Immediate => format!("#${:02X}", byte),
+            AddressingMode::ZeroPage => format!("${:02X} = {:02X}", byte, self.memory.peek(byte as u16)),
+            AddressingMode::ZeroPageX => {
+                let address = byte.wrapping_add(x);
+                format!("${:02X},X @ {:02X} = {:02X}", byte, address, self.memory.peek(address as u16))
+            },
+            AddressingMode::ZeroPageY => {
+                let address = byte.wrapping_add(y);
+                format!("${:02X},Y @ {:02X} = {:02X}", byte, address, self.memory.peek(address as u16))
+            },
+            AddressingMode::Absolute if opcode.is_jump_instruction() => format!("${:04X}", word),
+            AddressingMode::Absolute => format!("${:04X} = {:02X}", word, self.memory.peek(word)),
+            AddressingMode::AbsoluteX => {
+                let address = word.wrapping_add(x as u16);
+                format!("${:04X},X @ {:04X} = {:02X}", word, address, self.memory.peek(address))
+            },
+            AddressingMode::AbsoluteY => {
+                let address = word.wrapping_add(y as u16);
+                format!("${:04X},Y @ {:04X} = {:02X}", word, address, self.memory.peek(address))
+            },
+            AddressingMode::Indirect => {
+                format!("(${:04X}) = {:04X}", word, self.peek_effective_address(AddressingMode::Indirect).unwrap_or_default())
+            },
+            AddressingMode::IndirectX => {
+                let pointer = byte.wrapping_add(x);
+                let address = self.peek_zero_page_u16(pointer);
+                format!("(${:02X},X) @ {:02X} = {:04X} = {:02X}", byte, pointer, address, self.memory.peek(address))
+            },
+            AddressingMode::IndirectY => {
+                let base = self.peek_zero_page_u16(byte);
+                let address = base.wrapping_add(y as u16);
+                format!("(${:02X}),Y = {:04X} @ {:04X} = {:02X}", byte, base, address, self.memory.peek(address))
+            },
+            AddressingMode::ZeroPageIndirect => {
+                let address = self.peek_zero_page_u16(byte);
+                format!("(${:02X}) = {:04X} = {:02X}", byte, address, self.memory.peek(address))
+            },
+            AddressingMode::AbsoluteIndirectX => {
+                format!("(${:04X},X) = {:04X}", word, self.peek_effective_address(AddressingMode::AbsoluteIndirectX).unwrap_or_default())
+            },
+            AddressingMode::Relative => {
+                format!("${:04X}", self.peek_effective_address(AddressingMode::Relative).unwrap_or_default())
+            },
+        }
+    }
+}
+
+/// Nintendulator names ISC as ISB
+fn trace_mnemonic(opcode: Opcode) -> &'static str {
+    match opcode {
+        Opcode::Isc(_, _) => "ISB",
+        _ => opcode.mnemonic()
+    }
+}
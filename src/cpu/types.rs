@@ -1,6 +1,67 @@
 use bitflags::bitflags;
 use std::fmt;
 
+use super::opcode::Opcode;
+use crate::memory::types::AddressingMode;
+
+/// Address of the vector holding the Non-Maskable Interrupt (NMI) handler address
+pub const NMI_VECTOR: u16 = 0xFFFA;
+/// Address of the vector holding the address the CPU jumps to on RESET
+pub const RESET_VECTOR: u16 = 0xFFFC;
+/// Address of the vector holding the IRQ/BRK handler address
+pub const IRQ_VECTOR: u16 = 0xFFFE;
+
+/// CPU models sharing this core
+///
+/// | Variant    | Decimal mode (D flag)                                  | Instruction set                          |
+/// |------------|--------------------------------------------------------|------------------------------------------|
+/// | Ricoh2A03  | Ignored, ADC/SBC always work in binary (NES, Famicom)  | NMOS, with the unofficial opcodes        |
+/// | Nmos6502   | ADC/SBC work in BCD, with the NMOS flag behavior       | NMOS, with the unofficial opcodes        |
+/// | Cmos65C02  | ADC/SBC work in BCD, N and Z are valid (1 extra cycle) | Extended, unused opcodes are NOPs        |
+///
+/// The 65C02 also fixes the JMP ($xxFF) page wrap and clears D on interrupts
+///
+/// see: [Decimal mode](http://www.6502.org/tutorials/decimal_mode.html),
+/// [65C02 opcodes](http://www.6502.org/tutorials/65c02opcodes.html)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CpuVariant {
+    #[default]
+    Ricoh2A03,
+    Nmos6502,
+    Cmos65C02
+}
+
+/// How the CPU handles the unstable unofficial opcodes (ANE, LXA, SHA, SHX, SHY, TAS) and JAM
+///
+/// | Policy  | Behavior                                                                  |
+/// |---------|---------------------------------------------------------------------------|
+/// | Error   | The instruction is not executed and `InstructionError::UnstableOpcode` is returned |
+/// | Nop     | The instruction is skipped, as a NOP of the same size                     |
+/// | Execute | The instruction is emulated. JAM halts the CPU until the next reset       |
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnstableOpcodePolicy {
+    #[default]
+    Error,
+    Nop,
+    Execute
+}
+
+/// What the CPU did in a single step, as returned by `Cpu::step`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRecord {
+    /// Address of the executed instruction. After an interrupt, it's the first instruction of the handler
+    pub address: u16,
+    pub opcode: Opcode,
+    pub addressing_mode: AddressingMode,
+    /// Bytes following the opcode, as a little endian value. None when the instruction has no operand
+    pub operand: Option<u16>,
+    /// Address the instruction read, wrote or jumped to.
+    /// None when it doesn't access memory through its operand (implicit, accumulator and immediate modes)
+    pub effective_address: Option<u16>,
+    /// Cycles consumed, including interrupt handling and stalls requested by the bus (e.g. OAM DMA)
+    pub cycles: u16
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum InstructionError {
     InvalidOpcode(u8),
@@ -9,6 +70,10 @@ pub enum InstructionError {
     NotImplementedInstruction(String),
     InvalidMnemonicAndAddressingModePair(String, String),
     AddressingModeNotRecognized(String),
+    UnstableOpcode(u8),
+    /// A JAM opcode halted the CPU at this address. Only a reset recovers it
+    CpuJammed(u16),
+    UnofficialInstruction(String),
     FatalError(String)
 }
 
@@ -33,6 +98,15 @@ impl fmt::Display for InstructionError {
             InstructionError::AddressingModeNotRecognized(ref data) => {
                 write!(f, "Addressing mode not recognized for data: {}", data)
             },
+            InstructionError::UnstableOpcode(opcode) => {
+                write!(f, "Unstable opcode: 0x{:02X}", opcode)
+            },
+            InstructionError::CpuJammed(address) => {
+                write!(f, "CPU jammed at ${:04X}", address)
+            },
+            InstructionError::UnofficialInstruction(ref mnemonic) => {
+                write!(f, "Unofficial instruction {} requires illegal opcodes to be enabled", mnemonic)
+            },
             InstructionError::FatalError(ref message) => {
                 write!(f, "Fatal error! Message: {}", message)
             }
@@ -52,3 +126,47 @@ bitflags! {
         const NEGATIVE          = 0b10000000;
     }
 }
+
+/// Flags as `NV-BDIZC`: uppercase when set, lowercase when clear. Bit 5 is always shown as `-`
+impl fmt::Display for CpuFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flags = [
+            (CpuFlags::NEGATIVE, 'N'),
+            (CpuFlags::OVERFLOW, 'V'),
+            (CpuFlags::UNUSED, '-'),
+            (CpuFlags::BREAK, 'B'),
+            (CpuFlags::DECIMAL_MODE, 'D'),
+            (CpuFlags::INTERRUPT_DISABLE, 'I'),
+            (CpuFlags::ZERO, 'Z'),
+            (CpuFlags::CARRY, 'C'),
+        ];
+        for (flag, letter) in flags {
+            let letter = if self.contains(flag) { letter } else { letter.to_ascii_lowercase() };
+            write!(f, "{}", letter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot of the CPU registers, read with `Cpu::state` and restored with `Cpu::load_state`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuState {
+    pub program_counter: u16,
+    pub stack_pointer: u8,
+    pub accumulator: u8,
+    pub x_register: u8,
+    pub y_register: u8,
+    pub status: CpuFlags
+}
+
+impl fmt::Display for CpuState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PC:{:04X} A:{:02X} X:{:02X} Y:{:02X} SP:{:02X} P:{}",
+            self.program_counter,
+            self.accumulator,
+            self.x_register,
+            self.y_register,
+            self.stack_pointer,
+            self.status)
+    }
+}
@@ -0,0 +1,148 @@
+pub mod types;
+
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use types::{DisassembledLine, DisassemblerOptions, DATA_DIRECTIVE};
+
+use crate::bus::Bus;
+use crate::cpu::opcode::{self, Opcode};
+use crate::cpu::types::CpuVariant;
+use crate::memory::types::AddressingMode;
+
+/// Disassemble a program loaded at `start_address`.
+///
+/// Each line is written in the syntax of the assembler, so assembling the lines gives back the same bytes.
+/// Opcodes that the assembler would encode differently (e.g. the duplicated unofficial NOPs),
+/// unknown opcodes and truncated instructions at the end of the program are written as `.byte`
+pub fn disassemble(program: &[u8], start_address: u16, options: &DisassemblerOptions) -> Vec<DisassembledLine> {
+    let mut lines: Vec<(DisassembledLine, Option<u16>)> = Vec::with_capacity(program.len());
+    let mut offset = 0;
+
+    while offset < program.len() {
+        let address = start_address.wrapping_add(offset as u16);
+        let line = match decode_instruction(&program[offset..], options.cpu_variant) {
+            Some(opcode) => {
+                let bytes = program[offset..offset + opcode.addressing_mode().byte_size() as usize].to_vec();
+                disassemble_instruction(opcode, address, bytes)
+            },
+            None => (data_line(address, &program[offset..offset + 1]), None)
+        };
+        offset += line.0.bytes.len();
+        lines.push(line);
+    }
+
+    if options.labels {
+        add_labels(&mut lines);
+    }
+    lines.into_iter().map(|(line, _)| line).collect()
+}
+
+/// Disassemble the memory seen by the CPU in the given address range.
+/// Memory is read with `peek`, so registers with side effects are left untouched
+pub fn disassemble_bus<B: Bus>(bus: &B, range: RangeInclusive<u16>, options: &DisassemblerOptions) -> Vec<DisassembledLine> {
+    let start_address = *range.start();
+    let program: Vec<u8> = range.map(|address| bus.peek(address)).collect();
+    disassemble(&program, start_address, options)
+}
+
+/// Returns the opcode at the start of the program, if it's complete and the assembler encodes it back to the same byte
+fn decode_instruction(program: &[u8], variant: CpuVariant) -> Option<Opcode> {
+    let opcode = Opcode::decode(program[0], variant)?;
+    let complete = opcode.addressing_mode().byte_size() as usize <= program.len();
+    (complete && assembles_back(opcode, variant)).then_some(opcode)
+}
+
+fn assembles_back(opcode: Opcode, variant: CpuVariant) -> bool {
+    let mnemonic = opcode.mnemonic();
+    let addressing_mode = opcode.addressing_mode();
+    let translated = if !opcode.is_unofficial() {
+        opcode::translate_instruction_to_opcode(mnemonic, addressing_mode, variant)
+    } else if variant == CpuVariant::Cmos65C02 {
+        return false;
+    } else {
+        opcode::translate_unofficial_instruction_to_opcode(mnemonic, addressing_mode)
+    };
+    translated == Ok(opcode.value())
+}
+
+/// Returns the line and the address the instruction branches or jumps to
+fn disassemble_instruction(opcode: Opcode, address: u16, bytes: Vec<u8>) -> (DisassembledLine, Option<u16>) {
+    let addressing_mode = opcode.addressing_mode();
+    let operand = match bytes.len() {
+        2 => bytes[1] as u16,
+        3 => u16::from_le_bytes([bytes[1], bytes[2]]),
+        _ => 0
+    };
+
+    let target = match addressing_mode {
+        AddressingMode::Relative => {
+            Some(address.wrapping_add(bytes.len() as u16).wrapping_add(operand as u8 as i8 as u16))
+        },
+        AddressingMode::Absolute if opcode.is_jump_instruction() => Some(operand),
+        _ => None
+    };
+
+    let operand = match addressing_mode {
+        AddressingMode::Implicit => String::new(),
+        AddressingMode::Accumulator => String::from("A"),
+        AddressingMode::Immediate => format!("#${:02X}", operand),
+        AddressingMode::ZeroPage => format!("${:02X}", operand),
+        AddressingMode::ZeroPageX => format!("${:02X},X", operand),
+        AddressingMode::ZeroPageY => format!("${:02X},Y", operand),
+        AddressingMode::Absolute => format!("${:04X}", operand),
+        AddressingMode::AbsoluteX => format!("${:04X},X", operand),
+        AddressingMode::AbsoluteY => format!("${:04X},Y", operand),
+        AddressingMode::Indirect => format!("(${:04X})", operand),
+        AddressingMode::IndirectX => format!("(${:02X},X)", operand),
+        AddressingMode::IndirectY => format!("(${:02X}),Y", operand),
+        AddressingMode::ZeroPageIndirect => format!("(${:02X})", operand),
+        AddressingMode::AbsoluteIndirectX => format!("(${:04X},X)", operand),
+        AddressingMode::Relative => format!("${:04X}", target.unwrap_or_default())
+    };
+
+    let line = DisassembledLine {
+        address,
+        bytes,
+        label: None,
+        mnemonic: opcode.mnemonic().to_string(),
+        operand
+    };
+    (line, target)
+}
+
+fn data_line(address: u16, bytes: &[u8]) -> DisassembledLine {
+    DisassembledLine {
+        address,
+        bytes: bytes.to_vec(),
+        label: None,
+        mnemonic: DATA_DIRECTIVE.to_string(),
+        operand: bytes.iter().map(|byte| format!("${:02X}", byte)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Names the lines targeted by a branch or a jump, and uses that name as operand of the branch or jump.
+/// Targets outside the range or in the middle of an instruction keep their address
+fn add_labels(lines: &mut [(DisassembledLine, Option<u16>)]) {
+    let addresses: HashSet<u16> = lines.iter().map(|(line, _)| line.address).collect();
+    let targets: HashSet<u16> = lines.iter()
+        .filter_map(|(_, target)| *target)
+        .filter(|target| addresses.contains(target))
+        .collect();
+
+    for (line, target) in lines.iter_mut() {
+        if targets.contains(&line.address) {
+            line.label = Some(label_name(line.address));
+        }
+        if let Some(target) = target.filter(|target| targets.contains(target)) {
+            line.operand = label_name(target);
+        }
+    }
+}
+
+fn label_name(address: u16) -> String {
+    format!("L{:04X}", address)
+}
+
+#[cfg(test)]
+mod tests;
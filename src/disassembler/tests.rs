@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use super::*;
+
+use crate::assembler::{self, types::AssemblerOptions};
+use crate::memory::Memory;
+
+/// Assembles the source of the lines, starting at the address of the first one
+fn assemble_lines(lines: &[DisassembledLine], options: &AssemblerOptions) -> Vec<u8> {
+    let mut address = lines[0].address;
+    let mut program_binary = Vec::new();
+    let mut symbol_table = HashMap::new();
+    for line in lines {
+        if let Err(error) = assembler::process_line_with_options(&line.source(), &mut address, &mut program_binary, &mut symbol_table, options) {
+            panic!("Error assembling [{}]: {:?}", line.source(), error);
+        }
+    }
+    program_binary
+}
+
+#[test]
+fn disassemble_should_decode_instructions() {
+
+    // Given
+    // $0600    a9 01     LDA #$01
+    // $0602    8d 00 02  STA $0200
+    // $0605    b1 10     LDA ($10),Y
+    // $0607    0a        ASL A
+    // $0608    60        RTS
+    let program = [0xA9, 0x01, 0x8D, 0x00, 0x02, 0xB1, 0x10, 0x0A, 0x60];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["LDA #$01", "STA $0200", "LDA ($10),Y", "ASL A", "RTS"], sources);
+    assert_eq!(0x0602, lines[1].address);
+    assert_eq!(vec![0x8D, 0x00, 0x02], lines[1].bytes);
+    assert_eq!("STA", lines[1].mnemonic);
+    assert_eq!("$0200", lines[1].operand);
+    assert_eq!(None, lines[1].label);
+}
+
+#[test]
+fn disassembled_line_should_display_as_listing() {
+
+    // Given
+    let lines = disassemble(&[0x8D, 0x00, 0x02, 0xEA], 0xC000, &DisassemblerOptions::default());
+
+    // Then
+    assert_eq!("C000  8D 00 02  STA $0200", lines[0].to_string());
+    assert_eq!("C003  EA        NOP", lines[1].to_string());
+}
+
+#[test]
+fn disassemble_should_keep_absolute_operands_with_4_digits() {
+
+    // Given
+    // LDA $0010 and LDA $10 are different instructions
+    let program = [0xAD, 0x10, 0x00, 0xA5, 0x10];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    assert_eq!("LDA $0010", lines[0].source());
+    assert_eq!("LDA $10", lines[1].source());
+}
+
+#[test]
+fn disassemble_should_resolve_branch_targets() {
+
+    // Given
+    // $0600    ca        DEX
+    // $0601    d0 fd     BNE $0600
+    // $0603    f0 10     BEQ $0615
+    let program = [0xCA, 0xD0, 0xFD, 0xF0, 0x10];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    assert_eq!("BNE $0600", lines[1].source());
+    assert_eq!("BEQ $0615", lines[2].source());
+}
+
+#[test]
+fn disassemble_should_name_branch_and_jump_targets_when_labels_are_enabled() {
+
+    // Given
+    // $0600    ca        DEX
+    // $0601    d0 fd     BNE $0600
+    // $0603    20 09 06  JSR $0609
+    // $0606    4c 00 06  JMP $0600
+    // $0609    f0 10     BEQ $061B
+    // $060b    60        RTS
+    let program = [0xCA, 0xD0, 0xFD, 0x20, 0x09, 0x06, 0x4C, 0x00, 0x06, 0xF0, 0x10, 0x60];
+    let options = DisassemblerOptions { labels: true, ..Default::default() };
+
+    // When
+    let lines = disassemble(&program, 0x0600, &options);
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["L0600: DEX", "BNE L0600", "JSR L0609", "JMP L0600", "L0609: BEQ $061B", "RTS"], sources);
+}
+
+#[test]
+fn disassemble_should_not_name_targets_in_the_middle_of_an_instruction() {
+
+    // Given
+    // $0600    a9 d0     LDA #$D0
+    // $0602    d0 fd     BNE $0601
+    let program = [0xA9, 0xD0, 0xD0, 0xFD];
+    let options = DisassemblerOptions { labels: true, ..Default::default() };
+
+    // When
+    let lines = disassemble(&program, 0x0600, &options);
+
+    // Then
+    assert_eq!("LDA #$D0", lines[0].source());
+    assert_eq!("BNE $0601", lines[1].source());
+}
+
+#[test]
+fn disassemble_should_write_truncated_instruction_as_data() {
+
+    // Given
+    // $8D (STA) and $01 (ORA) need more bytes
+    let program = [0xEA, 0x8D, 0x01];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["NOP", ".byte $8D", ".byte $01"], sources);
+}
+
+#[test]
+fn disassemble_should_write_opcodes_assembled_differently_as_data() {
+
+    // Given
+    // NOP $xx is assembled as $04, not $44. SBC #$xx is assembled as $E9, not $EB
+    let program = [0xA7, 0x10, 0x44, 0xEA, 0xEB, 0xEA];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["LAX $10", ".byte $44", "NOP", ".byte $EB", "NOP"], sources);
+}
+
+#[test]
+fn disassemble_should_decode_65c02_instructions_when_selected() {
+
+    // Given
+    // $0600    b2 10     LDA ($10)
+    // $0602    9c 00 02  STZ $0200
+    // $0605    80 f9     BRA $0600
+    // $0607    7c 00 10  JMP ($1000,X)
+    // $060a    1a        INC A
+    let program = [0xB2, 0x10, 0x9C, 0x00, 0x02, 0x80, 0xF9, 0x7C, 0x00, 0x10, 0x1A];
+    let options = DisassemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    // When
+    let lines = disassemble(&program, 0x0600, &options);
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["LDA ($10)", "STZ $0200", "BRA $0600", "JMP ($1000,X)", "INC A"], sources);
+}
+
+#[test]
+fn disassemble_bus_should_read_the_address_range() {
+
+    // Given
+    let mut memory = Memory::new();
+    memory.write_array(&[0xA2, 0x05, 0xCA, 0xD0, 0xFD, 0x00], 0x8000);
+
+    // When
+    let lines = disassemble_bus(&memory, 0x8000..=0x8005, &DisassemblerOptions::default());
+
+    // Then
+    let sources: Vec<String> = lines.iter().map(DisassembledLine::source).collect();
+    assert_eq!(vec!["LDX #$05", "DEX", "BNE $8002", "BRK"], sources);
+}
+
+#[test]
+fn disassemble_then_assemble_should_give_back_every_nmos_opcode() {
+
+    // Given
+    let options = DisassemblerOptions::default();
+    let assembler_options = AssemblerOptions { illegal_opcodes: true, ..Default::default() };
+
+    for value in 0..=0xFF {
+        let program = [value, 0x12, 0x34];
+
+        // When
+        let lines = disassemble(&program, 0x0600, &options);
+
+        // Then
+        if lines[0].mnemonic != DATA_DIRECTIVE {
+            let size = lines[0].bytes.len();
+            assert_eq!(program[..size].to_vec(), assemble_lines(&lines[..1], &assembler_options), "opcode ${:02X}", value);
+        }
+    }
+}
+
+#[test]
+fn disassemble_then_assemble_should_give_back_every_65c02_opcode() {
+
+    // Given
+    let options = DisassemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+    let assembler_options = AssemblerOptions { cpu_variant: CpuVariant::Cmos65C02, ..Default::default() };
+
+    for value in 0..=0xFF {
+        let program = [value, 0x12, 0x34];
+
+        // When
+        let lines = disassemble(&program, 0x0600, &options);
+
+        // Then
+        if lines[0].mnemonic != DATA_DIRECTIVE {
+            let size = lines[0].bytes.len();
+            assert_eq!(program[..size].to_vec(), assemble_lines(&lines[..1], &assembler_options), "opcode ${:02X}", value);
+        }
+    }
+}
+
+#[test]
+fn disassemble_then_assemble_should_give_back_the_program() {
+
+    // Given
+    // $0600    a2 08     LDX #$08
+    // $0602    ca        DEX
+    // $0603    8e 00 02  STX $0200
+    // $0606    e0 03     CPX #$03
+    // $0608    d0 f8     BNE $0602
+    // $060a    a1 20     LDA ($20,X)
+    // $060c    6c 00 03  JMP ($0300)
+    let program = [0xA2, 0x08, 0xCA, 0x8E, 0x00, 0x02, 0xE0, 0x03, 0xD0, 0xF8, 0xA1, 0x20, 0x6C, 0x00, 0x03];
+
+    // When
+    let lines = disassemble(&program, 0x0600, &DisassemblerOptions::default());
+
+    // Then
+    assert_eq!(program.to_vec(), assemble_lines(&lines, &AssemblerOptions::default()));
+}
+
+#[test]
+fn disassemble_with_labels_then_assemble_should_give_back_the_program() {
+
+    // Given
+    // $0600    a2 08     LDX #$08
+    // $0602    ca        DEX
+    // $0603    f0 05     BEQ $060A
+    // $0605    20 0b 06  JSR $060B
+    // $0608    d0 f8     BNE $0602
+    // $060a    00        BRK
+    // $060b    60        RTS
+    let program = [0xA2, 0x08, 0xCA, 0xF0, 0x05, 0x20, 0x0B, 0x06, 0xD0, 0xF8, 0x00, 0x60];
+    let options = DisassemblerOptions { labels: true, ..Default::default() };
+
+    // When
+    let lines = disassemble(&program, 0x0600, &options);
+    let source: Vec<String> = std::iter::once(".org $0600".to_string())
+        .chain(lines.iter().map(DisassembledLine::source))
+        .collect();
+
+    // Then
+    assert_eq!("BEQ L060A", lines[2].source());
+    assert_eq!(Ok(program.to_vec()), assembler::assemble_source(&source.join("\n"), &AssemblerOptions::default()));
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use crate::cpu::types::CpuVariant;
+
+/// Mnemonic of the lines holding bytes that are not disassembled as an instruction
+pub const DATA_DIRECTIVE: &str = ".byte";
+
+/// Settings of the disassembler. The defaults write branch and jump targets as absolute addresses
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DisassemblerOptions {
+    /// Names the branch and jump targets found inside the disassembled range (e.g. `L0600`)
+    pub labels: bool,
+    /// CPU model the program is decoded for. The 65C02 adds instructions and addressing modes
+    pub cpu_variant: CpuVariant
+}
+
+/// One decoded instruction, or the bytes that could not be decoded as one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisassembledLine {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    /// Label defined at this address, when labels are enabled and an instruction branches or jumps here
+    pub label: Option<String>,
+    /// Instruction mnemonic (e.g. `LDA`), or `.byte` for data
+    pub mnemonic: String,
+    /// Operand in the syntax of the assembler (e.g. `#$10`, `($20),Y`). Empty for implicit instructions
+    pub operand: String
+}
+
+impl DisassembledLine {
+    /// Source code of the line, as accepted by the assembler (e.g. `L0600: LDA #$01`)
+    pub fn source(&self) -> String {
+        let mut source = String::new();
+        if let Some(label) = &self.label {
+            source.push_str(&format!("{}: ", label));
+        }
+        source.push_str(&self.mnemonic);
+        if !self.operand.is_empty() {
+            source.push_str(&format!(" {}", self.operand));
+        }
+        source
+    }
+}
+
+/// Listing line: address, hexdump and source (e.g. `0600  A9 01     LDA #$01`)
+impl fmt::Display for DisassembledLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hexdump = self.bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<Vec<_>>().join(" ");
+        write!(f, "{:04X}  {:<8}  {}", self.address, hexdump, self.source())
+    }
+}
@@ -0,0 +1,101 @@
+use std::any::Any;
+
+use super::joypad::Joypad;
+use super::{Controller, ControllerPort};
+use crate::ppu::Ppu;
+
+/// Bits returned after the 2 controllers, which let games detect the adapter. Sent MSB first
+const SIGNATURE_BITS: u8 = 8;
+const PORT_ONE_SIGNATURE: u8 = 0b0001_0000;
+const PORT_TWO_SIGNATURE: u8 = 0b0010_0000;
+/// Reads of 2 controllers and the signature, after which the adapter returns 1
+const REPORT_LENGTH: u8 = 24;
+
+/// Four Score multitap, as seen from one controller port
+///
+/// The adapter is plugged into both ports: port one reports players 1 and 3, port two reports
+/// players 2 and 4, each followed by a signature. A `FourScore` must therefore be connected to each port.
+///
+/// | Reads | $4016              | $4017              |
+/// |-------|--------------------|--------------------|
+/// | 1-8   | Player 1 buttons   | Player 2 buttons   |
+/// | 9-16  | Player 3 buttons   | Player 4 buttons   |
+/// | 17-24 | Signature 00010000 | Signature 00100000 |
+/// | 25+   | 1                  | 1                  |
+///
+/// see: [Four Score](https://www.nesdev.org/wiki/Four_player_adapters)
+#[derive(Debug)]
+pub struct FourScore {
+    joypads: [Joypad; 2],
+    signature: u8,
+    strobe: bool,
+    reads: u8
+}
+
+impl FourScore {
+    pub fn new(port: ControllerPort) -> Self {
+        let signature = match port {
+            ControllerPort::One => PORT_ONE_SIGNATURE,
+            ControllerPort::Two => PORT_TWO_SIGNATURE
+        };
+
+        Self {
+            joypads: [Joypad::new(), Joypad::new()],
+            signature,
+            strobe: false,
+            reads: 0
+        }
+    }
+
+    /// Joypad of the first (players 1 or 2) or second (players 3 or 4) controller plugged into the adapter
+    pub fn joypad(&self, index: usize) -> &Joypad {
+        &self.joypads[index]
+    }
+
+    pub fn joypad_mut(&mut self, index: usize) -> &mut Joypad {
+        &mut self.joypads[index]
+    }
+
+    fn next_bit(&self) -> u8 {
+        match self.reads {
+            0..=7 => self.joypads[0].next_bit(),
+            8..=15 => self.joypads[1].next_bit(),
+            16..=23 => {
+                let bit = self.reads - 16;
+                (self.signature >> (SIGNATURE_BITS - 1 - bit)) & 1
+            },
+            _ => 1
+        }
+    }
+}
+
+impl Controller for FourScore {
+    fn write(&mut self, data: u8) {
+        self.strobe = data & 1 != 0;
+        if self.strobe {
+            self.reads = 0;
+        }
+        self.joypads.iter_mut().for_each(|joypad| joypad.set_strobe(data));
+    }
+
+    fn read(&mut self, _ppu: &Ppu) -> u8 {
+        let bit = self.next_bit();
+        if !self.strobe && self.reads < REPORT_LENGTH {
+            match self.reads {
+                0..=7 => { self.joypads[0].shift(); },
+                8..=15 => { self.joypads[1].shift(); },
+                _ => ()
+            }
+            self.reads += 1;
+        }
+        bit
+    }
+
+    fn peek(&self, _ppu: &Ppu) -> u8 {
+        self.next_bit()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
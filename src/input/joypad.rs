@@ -0,0 +1,112 @@
+use std::any::Any;
+
+use bitflags::bitflags;
+
+use super::Controller;
+use crate::ppu::Ppu;
+
+bitflags! {
+    /// Buttons in the order they are reported by the shift register
+    #[derive(Default)]
+    pub struct Buttons: u8 {
+        const A      = 0b00000001;
+        const B      = 0b00000010;
+        const SELECT = 0b00000100;
+        const START  = 0b00001000;
+        const UP     = 0b00010000;
+        const DOWN   = 0b00100000;
+        const LEFT   = 0b01000000;
+        const RIGHT  = 0b10000000;
+    }
+}
+
+/// Standard controller
+///
+/// While the strobe bit is set, the buttons are continuously reloaded in a shift register and reads
+/// return the state of A. Once cleared, every read returns the next button. After the 8 buttons,
+/// official controllers return 1.
+///
+/// see: [Standard controller](https://www.nesdev.org/wiki/Standard_controller)
+#[derive(Debug, Default)]
+pub struct Joypad {
+    buttons: Buttons,
+    strobe: bool,
+    shift_register: u8,
+    /// Amount of bits shifted out since the strobe was cleared
+    reads: u8
+}
+
+impl Joypad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn buttons(&self) -> Buttons {
+        self.buttons
+    }
+
+    pub fn set_buttons(&mut self, buttons: Buttons) {
+        self.buttons = buttons;
+        if self.strobe {
+            self.reload();
+        }
+    }
+
+    pub fn press(&mut self, buttons: Buttons) {
+        self.set_buttons(self.buttons | buttons);
+    }
+
+    pub fn release(&mut self, buttons: Buttons) {
+        self.set_buttons(self.buttons - buttons);
+    }
+
+    fn reload(&mut self) {
+        self.shift_register = self.buttons.bits();
+        self.reads = 0;
+    }
+
+    /// Next bit of the shift register (D0)
+    pub(super) fn next_bit(&self) -> u8 {
+        if self.strobe {
+            self.buttons.bits() & 1
+        } else if self.reads >= 8 {
+            1
+        } else {
+            self.shift_register & 1
+        }
+    }
+
+    pub(super) fn shift(&mut self) -> u8 {
+        let bit = self.next_bit();
+        if !self.strobe && self.reads < 8 {
+            self.shift_register >>= 1;
+            self.reads += 1;
+        }
+        bit
+    }
+
+    pub(super) fn set_strobe(&mut self, data: u8) {
+        self.strobe = data & 1 != 0;
+        if self.strobe {
+            self.reload();
+        }
+    }
+}
+
+impl Controller for Joypad {
+    fn write(&mut self, data: u8) {
+        self.set_strobe(data);
+    }
+
+    fn read(&mut self, _ppu: &Ppu) -> u8 {
+        self.shift()
+    }
+
+    fn peek(&self, _ppu: &Ppu) -> u8 {
+        self.next_bit()
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
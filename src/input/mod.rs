@@ -0,0 +1,49 @@
+pub mod four_score;
+pub mod joypad;
+pub mod zapper;
+
+use std::any::Any;
+
+use crate::ppu::Ppu;
+
+/// Data lines driven by input devices on $4016/$4017 reads (D0-D4)
+pub const DATA_LINES_MASK: u8 = 0b0001_1111;
+
+/// Controller port, read through $4016 (port one) and $4017 (port two)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerPort {
+    One,
+    Two
+}
+
+impl ControllerPort {
+    pub fn index(&self) -> usize {
+        match self {
+            ControllerPort::One => 0,
+            ControllerPort::Two => 1
+        }
+    }
+}
+
+/// Device plugged into a controller port
+///
+/// Writes to $4016 are sent to the devices of both ports (bit 0 is the strobe line), while reads
+/// only reach the device of the port being read. Devices receive the PPU, so light guns can sense
+/// the picture being drawn.
+///
+/// see: [Input devices](https://www.nesdev.org/wiki/Input_devices)
+pub trait Controller {
+    fn write(&mut self, data: u8);
+
+    /// Returns the state of the data lines (D0-D4), applying any side effect (e.g. shifting the next button)
+    fn read(&mut self, ppu: &Ppu) -> u8;
+
+    /// Same as `read`, but without any side effect
+    fn peek(&self, ppu: &Ppu) -> u8;
+
+    /// Allows frontends to get back the concrete device, e.g. to press buttons
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,299 @@
+use super::*;
+use super::four_score::FourScore;
+use super::joypad::{Buttons, Joypad};
+use super::zapper::Zapper;
+use crate::bus::Bus;
+use crate::bus::nes_bus::NesBus;
+
+const WHITE: u8 = 0x30;
+const BLACK: u8 = 0x0F;
+
+fn read_bits(controller: &mut dyn Controller, ppu: &Ppu, count: usize) -> Vec<u8> {
+    (0..count).map(|_| controller.read(ppu) & 1).collect()
+}
+
+/// PPU with rendering disabled, so every pixel shows the backdrop color, stopped at the given beam position
+fn ppu_at(backdrop: u8, scanline: u16, dot: u16) -> Ppu {
+    let mut ppu = Ppu::new();
+    // PPUADDR = $3F00, then PPUDATA
+    ppu.write_register(0x3F, 6, None);
+    ppu.write_register(0x00, 6, None);
+    ppu.write_register(backdrop, 7, None);
+    while ppu.scanline() != scanline || ppu.dot() != dot {
+        ppu.tick(None);
+    }
+    ppu
+}
+
+#[test]
+fn joypad_reports_buttons_in_order_after_strobe() {
+    // Given
+    let ppu = Ppu::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::A | Buttons::START | Buttons::LEFT);
+
+    // When
+    joypad.write(1);
+    joypad.write(0);
+
+    // Then
+    assert_eq!(vec![1, 0, 0, 1, 0, 0, 1, 0], read_bits(&mut joypad, &ppu, 8));
+}
+
+#[test]
+fn joypad_returns_a_while_strobe_is_high() {
+    // Given
+    let ppu = Ppu::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::B);
+    joypad.write(1);
+
+    // When
+    let released = read_bits(&mut joypad, &ppu, 3);
+    joypad.press(Buttons::A);
+    let pressed = read_bits(&mut joypad, &ppu, 3);
+
+    // Then
+    assert_eq!(vec![0, 0, 0], released);
+    assert_eq!(vec![1, 1, 1], pressed);
+}
+
+#[test]
+fn joypad_returns_1_after_8_reads() {
+    // Given
+    let ppu = Ppu::new();
+    let mut joypad = Joypad::new();
+    joypad.write(1);
+    joypad.write(0);
+
+    // When
+    let bits = read_bits(&mut joypad, &ppu, 10);
+
+    // Then
+    assert_eq!(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 1], bits);
+}
+
+#[test]
+fn joypad_latches_buttons_when_strobe_is_cleared() {
+    // Given
+    let ppu = Ppu::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::SELECT);
+    joypad.write(1);
+    joypad.write(0);
+
+    // When
+    joypad.set_buttons(Buttons::RIGHT);
+
+    // Then
+    assert_eq!(vec![0, 0, 1, 0, 0, 0, 0, 0], read_bits(&mut joypad, &ppu, 8));
+}
+
+#[test]
+fn joypad_peek_does_not_shift() {
+    // Given
+    let ppu = Ppu::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::A);
+    joypad.write(1);
+    joypad.write(0);
+
+    // When
+    joypad.peek(&ppu);
+    joypad.peek(&ppu);
+
+    // Then
+    assert_eq!(1, joypad.read(&ppu));
+    assert_eq!(0, joypad.read(&ppu));
+}
+
+#[test]
+fn four_score_port_one_reports_players_1_and_3_then_signature() {
+    // Given
+    let ppu = Ppu::new();
+    let mut four_score = FourScore::new(ControllerPort::One);
+    four_score.joypad_mut(0).set_buttons(Buttons::A);
+    four_score.joypad_mut(1).set_buttons(Buttons::RIGHT);
+
+    // When
+    four_score.write(1);
+    four_score.write(0);
+    let bits = read_bits(&mut four_score, &ppu, 26);
+
+    // Then
+    assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0], bits[0..8]);
+    assert_eq!(vec![0, 0, 0, 0, 0, 0, 0, 1], bits[8..16]);
+    assert_eq!(vec![0, 0, 0, 1, 0, 0, 0, 0], bits[16..24]);
+    assert_eq!(vec![1, 1], bits[24..26]);
+}
+
+#[test]
+fn four_score_port_two_reports_its_own_signature() {
+    // Given
+    let ppu = Ppu::new();
+    let mut four_score = FourScore::new(ControllerPort::Two);
+
+    // When
+    four_score.write(1);
+    four_score.write(0);
+    let bits = read_bits(&mut four_score, &ppu, 24);
+
+    // Then
+    assert_eq!(vec![0; 16], bits[0..16]);
+    assert_eq!(vec![0, 0, 1, 0, 0, 0, 0, 0], bits[16..24]);
+}
+
+#[test]
+fn four_score_strobe_restarts_report() {
+    // Given
+    let ppu = Ppu::new();
+    let mut four_score = FourScore::new(ControllerPort::One);
+    four_score.joypad_mut(0).set_buttons(Buttons::A);
+    four_score.write(1);
+    four_score.write(0);
+    read_bits(&mut four_score, &ppu, 20);
+
+    // When
+    four_score.write(1);
+    four_score.write(0);
+
+    // Then
+    assert_eq!(1, four_score.read(&ppu));
+    assert_eq!(0, four_score.read(&ppu));
+}
+
+#[test]
+fn zapper_reports_trigger_on_d4() {
+    // Given
+    let ppu = Ppu::new();
+    let mut zapper = Zapper::new();
+
+    // When
+    zapper.set_trigger(true);
+    let pulled = zapper.read(&ppu);
+    zapper.set_trigger(false);
+    let released = zapper.read(&ppu);
+
+    // Then
+    assert_eq!(0b0001_0000, pulled & 0b0001_0000);
+    assert_eq!(0, released & 0b0001_0000);
+}
+
+#[test]
+fn zapper_senses_light_after_beam_draws_bright_spot() {
+    // Given
+    let ppu = ppu_at(WHITE, 105, 0);
+    let mut zapper = Zapper::new();
+
+    // When
+    zapper.aim(128, 100);
+
+    // Then
+    assert_eq!(0, zapper.read(&ppu) & 0b0000_1000);
+}
+
+#[test]
+fn zapper_does_not_sense_dark_spot() {
+    // Given
+    let ppu = ppu_at(BLACK, 105, 0);
+    let mut zapper = Zapper::new();
+
+    // When
+    zapper.aim(128, 100);
+
+    // Then
+    assert_eq!(0b0000_1000, zapper.read(&ppu) & 0b0000_1000);
+}
+
+#[test]
+fn zapper_senses_light_only_while_beam_is_near_spot() {
+    // Given
+    let before = ppu_at(WHITE, 100, 50);
+    let after = ppu_at(WHITE, 130, 0);
+    let mut zapper = Zapper::new();
+
+    // When
+    zapper.aim(128, 100);
+
+    // Then
+    assert_eq!(0b0000_1000, zapper.read(&before) & 0b0000_1000);
+    assert_eq!(0b0000_1000, zapper.read(&after) & 0b0000_1000);
+}
+
+#[test]
+fn zapper_aimed_off_screen_does_not_sense_light() {
+    // Given
+    let ppu = ppu_at(WHITE, 105, 0);
+    let mut zapper = Zapper::new();
+
+    // When
+    zapper.aim(300, 100);
+
+    // Then
+    assert_eq!(0b0000_1000, zapper.read(&ppu) & 0b0000_1000);
+}
+
+#[test]
+fn nes_bus_reads_controllers_through_ports() {
+    // Given
+    let mut bus = NesBus::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::B);
+    bus.connect_controller(ControllerPort::One, Box::new(joypad));
+    bus.connect_controller(ControllerPort::Two, Box::new(Joypad::new()));
+    bus.controller_as_mut::<Joypad>(ControllerPort::Two).unwrap().set_buttons(Buttons::A);
+
+    // When
+    bus.write(1, 0x4016);
+    bus.write(0, 0x4016);
+
+    // Then
+    assert_eq!(vec![0x40, 0x41], vec![bus.read(0x4016), bus.read(0x4016)]);
+    assert_eq!(vec![0x41, 0x40], vec![bus.read(0x4017), bus.read(0x4017)]);
+}
+
+#[test]
+fn nes_bus_peek_does_not_shift_controllers() {
+    // Given
+    let mut bus = NesBus::new();
+    let mut joypad = Joypad::new();
+    joypad.set_buttons(Buttons::A);
+    bus.connect_controller(ControllerPort::One, Box::new(joypad));
+    bus.write(1, 0x4016);
+    bus.write(0, 0x4016);
+
+    // When
+    bus.peek(0x4016);
+
+    // Then
+    assert_eq!(0x41, bus.read(0x4016));
+}
+
+#[test]
+fn nes_bus_without_controller_reads_open_bus() {
+    // Given
+    let mut bus = NesBus::new();
+    bus.connect_controller(ControllerPort::Two, Box::new(Zapper::new()));
+
+    // When
+    let disconnected = bus.disconnect_controller(ControllerPort::Two);
+
+    // Then
+    assert!(disconnected.is_some());
+    assert!(bus.controller_as_mut::<Zapper>(ControllerPort::Two).is_none());
+    assert_eq!(0x40, bus.read(0x4016));
+    assert_eq!(0x40, bus.read(0x4017));
+}
+
+#[test]
+fn nes_bus_controller_as_mut_checks_device_type() {
+    // Given
+    let mut bus = NesBus::new();
+
+    // When
+    bus.connect_controller(ControllerPort::One, Box::new(Zapper::new()));
+
+    // Then
+    assert!(bus.controller_as_mut::<Joypad>(ControllerPort::One).is_none());
+    assert!(bus.controller_as_mut::<Zapper>(ControllerPort::One).is_some());
+}
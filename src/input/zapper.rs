@@ -0,0 +1,89 @@
+use std::any::Any;
+
+use super::Controller;
+use crate::ppu::Ppu;
+use crate::ppu::types::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// D3: 0 when light is detected
+const LIGHT_NOT_DETECTED: u8 = 0b0000_1000;
+/// D4: 1 while the trigger is pulled
+const TRIGGER_PULLED: u8 = 0b0001_0000;
+/// The photodiode keeps reporting light for about 26 scanlines after the beam hit the aimed spot
+const LIGHT_SENSE_SCANLINES: usize = 26;
+/// Colors of the 2 brightest rows of the palette, except the grays/blacks of columns $D-$F
+const BRIGHT_COLORS_START: u8 = 0x20;
+const BRIGHT_HUE_END: u8 = 0x0C;
+
+/// Zapper light gun
+///
+/// The Zapper senses light when the aimed spot is bright and the PPU drew it recently, so the
+/// detection depends on the beam position at the time of the read, like on a CRT.
+///
+/// see: [Zapper](https://www.nesdev.org/wiki/Zapper)
+#[derive(Debug, Default)]
+pub struct Zapper {
+    /// Aimed pixel, none when pointing off screen
+    aim: Option<(usize, usize)>,
+    trigger: bool
+}
+
+impl Zapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Points the gun at the pixel (x, y). Coordinates outside of the screen point off screen
+    pub fn aim(&mut self, x: usize, y: usize) {
+        self.aim = (x < SCREEN_WIDTH && y < SCREEN_HEIGHT).then_some((x, y));
+    }
+
+    pub fn aim_off_screen(&mut self) {
+        self.aim = None;
+    }
+
+    pub fn set_trigger(&mut self, pulled: bool) {
+        self.trigger = pulled;
+    }
+
+    fn light_detected(&self, ppu: &Ppu) -> bool {
+        let Some((x, y)) = self.aim else {
+            return false;
+        };
+
+        let scanline = ppu.scanline() as usize;
+        let dot = ppu.dot() as usize;
+        // Dot 0 is idle, so pixel x is drawn at dot x + 1
+        let beam_passed = scanline > y || (scanline == y && dot > x + 1);
+        let still_lit = scanline < y + LIGHT_SENSE_SCANLINES;
+
+        beam_passed && still_lit && is_bright(ppu.framebuffer()[y * SCREEN_WIDTH + x])
+    }
+}
+
+fn is_bright(color: u8) -> bool {
+    color >= BRIGHT_COLORS_START && color & 0x0F <= BRIGHT_HUE_END
+}
+
+impl Controller for Zapper {
+    /// The Zapper ignores the strobe
+    fn write(&mut self, _data: u8) {}
+
+    fn read(&mut self, ppu: &Ppu) -> u8 {
+        self.peek(ppu)
+    }
+
+    fn peek(&self, ppu: &Ppu) -> u8 {
+        let mut data = 0;
+        if !self.light_detected(ppu) {
+            data |= LIGHT_NOT_DETECTED;
+        }
+        if self.trigger {
+            data |= TRIGGER_PULLED;
+        }
+        data
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -1,4 +1,10 @@
+pub mod bus;
+pub mod cartridge;
 pub mod cpu;
+pub mod ppu;
+pub mod apu;
+pub mod input;
 pub mod memory;
 pub mod assembler;
+pub mod disassembler;
 pub mod constants;
\ No newline at end of file
@@ -1,11 +1,19 @@
 pub mod types;
 
+use crate::bus::Bus;
+
 const MEMORY_SIZE: usize = 0x10000;
 
 pub struct Memory {
     mem: [u8; MEMORY_SIZE],
 }
 
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Memory {
     pub fn new() -> Self {
         Self {
@@ -17,10 +25,11 @@ impl Memory {
         self.mem[address as usize]
     }
 
-    /// Uses Little Endian (LE) approach to retrieve 2 bytes from memory as a value
+    /// Uses Little Endian (LE) approach to retrieve 2 bytes from memory as a value.
+    /// The high byte of $FFFF is read from $0000
     pub fn read_u16(&self, address: u16) -> u16 {
         let lsb = self.read(address) as u16;
-        let msb = self.read(address + 1) as u16;
+        let msb = self.read(address.wrapping_add(1)) as u16;
 
         msb << 8 | lsb
     }
@@ -42,5 +51,21 @@ impl Memory {
     }
 }
 
+/// Flat 64 KiB memory without any mapping, useful for tests and for running
+/// programs produced by the assembler
+impl Bus for Memory {
+    fn read(&mut self, address: u16) -> u8 {
+        self.mem[address as usize]
+    }
+
+    fn write(&mut self, data: u8, address: u16) {
+        self.mem[address as usize] = data;
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.mem[address as usize]
+    }
+}
+
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -42,7 +42,54 @@ fn write_array() {
     memory.write_array(&array, address);
 
     // Then
-    for i in 0..array.len() {
-        assert_eq!(array[i], memory.read(address + i as u16));
+    for (i, &byte) in array.iter().enumerate() {
+        assert_eq!(byte, memory.read(address + i as u16));
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn bus_read_and_peek_return_written_data() {
+    // Given
+    let address: u16 = 0x8000;
+    let expected_data: u8 = 0x69;
+
+    // When
+    let mut memory = Memory::new();
+    Bus::write(&mut memory, expected_data, address);
+
+    // Then
+    assert_eq!(expected_data, memory.peek(address));
+    assert_eq!(expected_data, Bus::read(&mut memory, address));
+}
+
+#[test]
+fn bus_read_u16() {
+    // Given
+    let address: u16 = 0x8000;
+    let mut memory = Memory::new();
+    memory.write_array(&[0x69, 0x96], address);
+
+    // When
+    let read_value = Bus::read_u16(&mut memory, address);
+    let peeked_value = memory.peek_u16(address);
+
+    // Then
+    assert_eq!(0x9669, read_value);
+    assert_eq!(0x9669, peeked_value);
+}
+
+#[test]
+fn read_u16_wraps_at_end_of_memory() {
+    // Given
+    let mut memory = Memory::new();
+    memory.write(0x69, 0xFFFF);
+    memory.write(0x96, 0x0000);
+
+    // When
+    let read_value = Bus::read_u16(&mut memory, 0xFFFF);
+
+    // Then
+    assert_eq!(0x9669, memory.read_u16(0xFFFF));
+    assert_eq!(0x9669, read_value);
+    assert_eq!(0x9669, memory.peek_u16(0xFFFF));
+}
@@ -12,12 +12,14 @@ lazy_static! {
     static ref IMMEDIATE_REGEX: Regex = Regex::new(&format!(r"^#{}$", constants::NUM_8_BIT.as_str())).unwrap();
     static ref ZERO_PAGE_REGEX: Regex = Regex::new(&format!(r"^{}$", constants::NUM_8_BIT.as_str())).unwrap();
     static ref ZERO_PAGE_X_REGEX: Regex = Regex::new(&format!(r"^{}\s*,\s*X$", constants::NUM_8_BIT.as_str())).unwrap();
-    static ref ZERO_PAGE_Y_REGEX: Regex = Regex::new(&format!(r"^\${}\s*,\s*Y$", constants::NUM_8_BIT.as_str())).unwrap();
+    static ref ZERO_PAGE_Y_REGEX: Regex = Regex::new(&format!(r"^{}\s*,\s*Y$", constants::NUM_8_BIT.as_str())).unwrap();
     static ref ABSOLUTE_REGEX: Regex = Regex::new(&format!(r"^{}$", constants::NUM_16_BIT.as_str())).unwrap();
     static ref ABSOLUTE_X_REGEX: Regex = Regex::new(&format!(r"^{}\s*,\s*X$", constants::NUM_16_BIT.as_str())).unwrap();
     static ref ABSOLUTE_Y_REGEX: Regex = Regex::new(&format!(r"^{}\s*,\s*Y$", constants::NUM_16_BIT.as_str())).unwrap();
     static ref INDIRECT_REGEX: Regex = Regex::new(&format!(r"^\({}\)$", constants::NUM_16_BIT.as_str())).unwrap();
     static ref INDIRECT_X_REGEX: Regex = Regex::new(&format!(r"^\({}\s*,\s*X\)$", constants::NUM_8_BIT.as_str())).unwrap();
+    static ref ZERO_PAGE_INDIRECT_REGEX: Regex = Regex::new(&format!(r"^\({}\)$", constants::NUM_8_BIT.as_str())).unwrap();
+    static ref ABSOLUTE_INDIRECT_X_REGEX: Regex = Regex::new(&format!(r"^\({}\s*,\s*X\)$", constants::NUM_16_BIT.as_str())).unwrap();
     static ref INDIRECT_Y_REGEX: Regex = Regex::new(&format!(r"^\({}\)\s*,\s*Y$", constants::NUM_8_BIT.as_str())).unwrap();
 }
 
@@ -123,7 +125,21 @@ pub enum AddressingMode {
     /// The same as Zero Page, but the Y register is added to the address.
     ///
     /// Example: `LDA $20,Y` will load the value at zero-page address `$20 + Y` into the accumulator.
-    ZeroPageY
+    ZeroPageY,
+
+    /// Zero Page Indirect addressing (65C02 only)
+    ///
+    /// The operand specifies a zero-page address, which contains the effective address.
+    ///
+    /// Example: `LDA ($20)` will load the value at the address stored at zero-page address `$20`.
+    ZeroPageIndirect,
+
+    /// Absolute Indexed Indirect addressing (65C02 only, used by JMP)
+    ///
+    /// The X register is added to the operand address, which then contains the effective address.
+    ///
+    /// Example: `JMP ($1234,X)` will jump to the address stored at memory location `0x1234 + X`.
+    AbsoluteIndirectX
 }
 
 impl AddressingMode {
@@ -141,7 +157,9 @@ impl AddressingMode {
             AddressingMode::IndirectX => 2,
             AddressingMode::IndirectY => 2,
             AddressingMode::Accumulator => 1,
-            AddressingMode::Relative => 2
+            AddressingMode::Relative => 2,
+            AddressingMode::ZeroPageIndirect => 2,
+            AddressingMode::AbsoluteIndirectX => 3
         }
     }
 
@@ -159,7 +177,9 @@ impl AddressingMode {
             AddressingMode::IndirectX => &INDIRECT_X_REGEX,
             AddressingMode::IndirectY => &INDIRECT_Y_REGEX,
             AddressingMode::Accumulator => &ACCUMULATOR_REGEX,
-            AddressingMode::Relative => &RELATIVE_REGEX
+            AddressingMode::Relative => &RELATIVE_REGEX,
+            AddressingMode::ZeroPageIndirect => &ZERO_PAGE_INDIRECT_REGEX,
+            AddressingMode::AbsoluteIndirectX => &ABSOLUTE_INDIRECT_X_REGEX
         }
     }
 }
@@ -180,6 +200,8 @@ impl fmt::Display for AddressingMode {
             AddressingMode::IndirectY => "IndirectY",
             AddressingMode::Accumulator => "Accumulator",
             AddressingMode::Relative => "Relative",
+            AddressingMode::ZeroPageIndirect => "ZeroPageIndirect",
+            AddressingMode::AbsoluteIndirectX => "AbsoluteIndirectX",
         };
         write!(f, "{}", s)
     }
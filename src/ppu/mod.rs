@@ -0,0 +1,295 @@
+pub mod types;
+
+mod render;
+mod sprites;
+
+use crate::cartridge::mapper::Mapper;
+use crate::cartridge::types::Mirroring;
+
+use types::{PpuCtrl, PpuMask, PpuStatus, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+pub const OAM_SIZE: usize = 256;
+/// 2 KiB of internal VRAM, plus 2 KiB that four screen cartridges provide
+const VRAM_SIZE: usize = 0x1000;
+const NAMETABLE_SIZE: u16 = 0x0400;
+const PALETTE_SIZE: usize = 32;
+
+const PATTERN_TABLES_END: u16 = 0x1FFF;
+const NAMETABLES_START: u16 = 0x2000;
+const NAMETABLES_END: u16 = 0x3EFF;
+const PALETTE_START: u16 = 0x3F00;
+
+/// VRAM addresses are 14 bits wide
+const VRAM_ADDRESS_MASK: u16 = 0x3FFF;
+
+/// Register indexes, once the CPU address is mirrored to $2000-$2007
+const PPUCTRL: u16 = 0;
+const PPUMASK: u16 = 1;
+const PPUSTATUS: u16 = 2;
+const OAMADDR: u16 = 3;
+const OAMDATA: u16 = 4;
+const PPUSCROLL: u16 = 5;
+const PPUADDR: u16 = 6;
+const PPUDATA: u16 = 7;
+
+/// NES Picture Processing Unit (2C02)
+///
+/// The CPU talks to the PPU through 8 registers ($2000-$2007) and the PPU accesses its own
+/// 14-bit address space:
+///
+/// | Address range | Device                                        |
+/// |---------------|-----------------------------------------------|
+/// | $0000-$1FFF   | Pattern tables (cartridge CHR ROM/RAM)        |
+/// | $2000-$2FFF   | Nametables (internal VRAM, cartridge mirroring) |
+/// | $3000-$3EFF   | Mirror of $2000-$2EFF                         |
+/// | $3F00-$3FFF   | Palette RAM, mirrored every 32 bytes          |
+///
+/// The cartridge is owned by the system bus, so every method that may access the pattern tables
+/// receives it as a parameter.
+///
+/// Scrolling uses the internal registers documented in
+/// [PPU scrolling](https://www.nesdev.org/wiki/PPU_scrolling): `vram_address` (v), `temp_vram_address` (t),
+/// `fine_x` (x) and `write_latch` (w).
+///
+/// see: [PPU](https://www.nesdev.org/wiki/PPU)
+pub struct Ppu {
+    ctrl: PpuCtrl,
+    mask: PpuMask,
+    status: PpuStatus,
+    oam_address: u8,
+    oam: [u8; OAM_SIZE],
+    /// Current VRAM address: 0yyy NNYY YYYX XXXX (fine Y, nametable, coarse Y, coarse X)
+    vram_address: u16,
+    /// Temporary VRAM address: address of the top left onscreen tile
+    temp_vram_address: u16,
+    fine_x: u8,
+    /// Toggles between first and second write of PPUSCROLL and PPUADDR
+    write_latch: bool,
+    /// PPUDATA reads return the content of this buffer, which is filled with the byte read
+    read_buffer: u8,
+    /// Last value written to a register. Returned when reading write-only registers
+    data_bus: u8,
+    vram: [u8; VRAM_SIZE],
+    palette: [u8; PALETTE_SIZE],
+    scanline: u16,
+    dot: u16,
+    frame: u64,
+    background: render::BackgroundShifters,
+    sprites: sprites::ScanlineSprites,
+    framebuffer: Vec<u8>
+}
+
+impl Default for Ppu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ppu {
+    pub fn new() -> Self {
+        Self {
+            ctrl: PpuCtrl::empty(),
+            mask: PpuMask::empty(),
+            status: PpuStatus::empty(),
+            oam_address: 0,
+            oam: [0; OAM_SIZE],
+            vram_address: 0,
+            temp_vram_address: 0,
+            fine_x: 0,
+            write_latch: false,
+            read_buffer: 0,
+            data_bus: 0,
+            vram: [0; VRAM_SIZE],
+            palette: [0; PALETTE_SIZE],
+            scanline: 0,
+            dot: 0,
+            frame: 0,
+            background: render::BackgroundShifters::default(),
+            sprites: sprites::ScanlineSprites::default(),
+            framebuffer: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT]
+        }
+    }
+
+    /// 256x240 pixels, row by row. Each pixel holds an index in the NES master palette (0-63)
+    pub fn framebuffer(&self) -> &[u8] {
+        &self.framebuffer
+    }
+
+    /// Scanline being rendered: 0-239 visible, 241-260 vertical blank, 261 pre-render
+    pub fn scanline(&self) -> u16 {
+        self.scanline
+    }
+
+    /// Dot (PPU cycle) of the current scanline, 0-340
+    pub fn dot(&self) -> u16 {
+        self.dot
+    }
+
+    /// Amount of frames completely rendered
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Level of the NMI output: active during vertical blank, if enabled in PPUCTRL
+    pub fn nmi(&self) -> bool {
+        self.status.contains(PpuStatus::VBLANK) && self.ctrl.contains(PpuCtrl::GENERATE_NMI)
+    }
+
+    /// Reads a register as the CPU does, applying any side effect
+    /// (e.g. reading PPUSTATUS clears the vertical blank flag)
+    pub fn read_register(&mut self, address: u16, cartridge: Option<&mut dyn Mapper>) -> u8 {
+        match address & 0x0007 {
+            PPUSTATUS => {
+                let result = self.status.bits() | (self.data_bus & 0b0001_1111);
+                self.status.remove(PpuStatus::VBLANK);
+                self.write_latch = false;
+                self.data_bus = result;
+            },
+            OAMDATA => self.data_bus = self.oam[self.oam_address as usize],
+            PPUDATA => {
+                let address = self.vram_address & VRAM_ADDRESS_MASK;
+                let data = self.read(address, cartridge.as_deref());
+                self.data_bus = if address >= PALETTE_START {
+                    // Palette is returned immediately, but the buffer is filled with the nametable "below" it
+                    self.read_buffer = self.read(address - 0x1000, cartridge.as_deref());
+                    data
+                } else {
+                    let buffered = self.read_buffer;
+                    self.read_buffer = data;
+                    buffered
+                };
+                self.increment_vram_address();
+            },
+            _ => ()
+        }
+        self.data_bus
+    }
+
+    /// Reads a register without any side effect
+    pub fn peek_register(&self, address: u16, cartridge: Option<&dyn Mapper>) -> u8 {
+        match address & 0x0007 {
+            PPUSTATUS => self.status.bits() | (self.data_bus & 0b0001_1111),
+            OAMDATA => self.oam[self.oam_address as usize],
+            PPUDATA => {
+                let address = self.vram_address & VRAM_ADDRESS_MASK;
+                if address >= PALETTE_START { self.read(address, cartridge) } else { self.read_buffer }
+            },
+            _ => self.data_bus
+        }
+    }
+
+    pub fn write_register(&mut self, data: u8, address: u16, cartridge: Option<&mut dyn Mapper>) {
+        self.data_bus = data;
+        match address & 0x0007 {
+            PPUCTRL => {
+                self.ctrl = PpuCtrl::from_bits_truncate(data);
+                // t: ...GH.. ........ <- d: ......GH
+                self.temp_vram_address = (self.temp_vram_address & 0xF3FF) | ((data as u16 & 0b11) << 10);
+            },
+            PPUMASK => self.mask = PpuMask::from_bits_truncate(data),
+            OAMADDR => self.oam_address = data,
+            OAMDATA => self.write_oam_data(data),
+            PPUSCROLL => {
+                if !self.write_latch {
+                    // t: ....... ...ABCDE <- d: ABCDE...
+                    self.temp_vram_address = (self.temp_vram_address & 0xFFE0) | (data as u16 >> 3);
+                    self.fine_x = data & 0b111;
+                } else {
+                    // t: FGH..AB CDE..... <- d: ABCDEFGH
+                    self.temp_vram_address = (self.temp_vram_address & 0x8C1F)
+                        | ((data as u16 & 0b111) << 12)
+                        | ((data as u16 & 0xF8) << 2);
+                }
+                self.write_latch = !self.write_latch;
+            },
+            PPUADDR => {
+                if !self.write_latch {
+                    // t: .CDEFGH ........ <- d: ..CDEFGH (bit 14 is cleared)
+                    self.temp_vram_address = (self.temp_vram_address & 0x00FF) | ((data as u16 & 0x3F) << 8);
+                } else {
+                    self.temp_vram_address = (self.temp_vram_address & 0xFF00) | data as u16;
+                    self.vram_address = self.temp_vram_address;
+                }
+                self.write_latch = !self.write_latch;
+            },
+            PPUDATA => {
+                self.write(data, self.vram_address & VRAM_ADDRESS_MASK, cartridge);
+                self.increment_vram_address();
+            },
+            _ => ()
+        }
+    }
+
+    /// Writes OAM at OAMADDR, then increments OAMADDR. Used by OAMDATA and OAM DMA
+    pub fn write_oam_data(&mut self, data: u8) {
+        self.oam[self.oam_address as usize] = data;
+        self.oam_address = self.oam_address.wrapping_add(1);
+    }
+
+    fn increment_vram_address(&mut self) {
+        let increment = if self.ctrl.contains(PpuCtrl::VRAM_INCREMENT) { 32 } else { 1 };
+        self.vram_address = self.vram_address.wrapping_add(increment) & 0x7FFF;
+    }
+
+    fn rendering_enabled(&self) -> bool {
+        self.mask.intersects(PpuMask::SHOW_BACKGROUND | PpuMask::SHOW_SPRITES)
+    }
+
+    /// Reads the PPU address space
+    fn read(&self, address: u16, cartridge: Option<&dyn Mapper>) -> u8 {
+        let address = address & VRAM_ADDRESS_MASK;
+        match address {
+            0x0000..=PATTERN_TABLES_END => cartridge.map_or(0, |cartridge| cartridge.ppu_read(address)),
+            NAMETABLES_START..=NAMETABLES_END => self.vram[nametable_index(address, mirroring(cartridge))],
+            _ => self.palette[palette_index(address)]
+        }
+    }
+
+    fn write(&mut self, data: u8, address: u16, cartridge: Option<&mut dyn Mapper>) {
+        let address = address & VRAM_ADDRESS_MASK;
+        match address {
+            0x0000..=PATTERN_TABLES_END => {
+                if let Some(cartridge) = cartridge {
+                    cartridge.ppu_write(data, address);
+                }
+            },
+            NAMETABLES_START..=NAMETABLES_END => {
+                let index = nametable_index(address, mirroring(cartridge.as_deref()));
+                self.vram[index] = data;
+            },
+            _ => self.palette[palette_index(address)] = data & 0x3F
+        }
+    }
+}
+
+fn mirroring(cartridge: Option<&dyn Mapper>) -> Mirroring {
+    cartridge.map_or(Mirroring::Horizontal, |cartridge| cartridge.mirroring())
+}
+
+/// Maps a nametable address ($2000-$3EFF) to the VRAM, according to the cartridge mirroring
+///
+/// see: [Mirroring](https://www.nesdev.org/wiki/Mirroring#Nametable_Mirroring)
+fn nametable_index(address: u16, mirroring: Mirroring) -> usize {
+    let address = (address - NAMETABLES_START) & 0x0FFF;
+    let nametable = address / NAMETABLE_SIZE;
+    let offset = address % NAMETABLE_SIZE;
+
+    let physical_nametable = match mirroring {
+        Mirroring::Horizontal => nametable / 2,
+        Mirroring::Vertical => nametable % 2,
+        Mirroring::SingleScreenLower => 0,
+        Mirroring::SingleScreenUpper => 1,
+        Mirroring::FourScreen => nametable
+    };
+    (physical_nametable * NAMETABLE_SIZE + offset) as usize
+}
+
+/// Palette RAM is mirrored every 32 bytes. Entries $3F10/$3F14/$3F18/$3F1C (sprite backdrop)
+/// are mirrors of $3F00/$3F04/$3F08/$3F0C
+fn palette_index(address: u16) -> usize {
+    let index = (address - PALETTE_START) as usize % PALETTE_SIZE;
+    if index >= 0x10 && index & 0b11 == 0 { index - 0x10 } else { index }
+}
+
+#[cfg(test)]
+mod tests;
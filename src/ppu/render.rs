@@ -0,0 +1,219 @@
+use super::{Ppu, NAMETABLES_START};
+use super::types::{PpuCtrl, PpuMask, PpuStatus, DOTS_PER_SCANLINE, PRE_RENDER_SCANLINE, SCANLINES_PER_FRAME, SCREEN_HEIGHT, SCREEN_WIDTH, VBLANK_SCANLINE};
+use crate::cartridge::mapper::Mapper;
+
+const ATTRIBUTE_TABLE_OFFSET: u16 = 0x03C0;
+
+/// Background tiles are fetched 8 dots ahead of the pixel being drawn.
+/// Fetched data goes to the `next_*` latches, which are loaded into the lower byte
+/// of the shift registers every 8 dots
+#[derive(Default)]
+pub(super) struct BackgroundShifters {
+    next_tile: u8,
+    next_attribute: u8,
+    next_pattern_low: u8,
+    next_pattern_high: u8,
+    pattern_low: u16,
+    pattern_high: u16,
+    attribute_low: u16,
+    attribute_high: u16
+}
+
+impl BackgroundShifters {
+    fn load(&mut self) {
+        self.pattern_low = (self.pattern_low & 0xFF00) | self.next_pattern_low as u16;
+        self.pattern_high = (self.pattern_high & 0xFF00) | self.next_pattern_high as u16;
+        // Attribute bits are the same for the whole tile, so they are expanded to 8 pixels
+        self.attribute_low = (self.attribute_low & 0xFF00) | if self.next_attribute & 0b01 != 0 { 0xFF } else { 0x00 };
+        self.attribute_high = (self.attribute_high & 0xFF00) | if self.next_attribute & 0b10 != 0 { 0xFF } else { 0x00 };
+    }
+
+    fn shift(&mut self) {
+        self.pattern_low <<= 1;
+        self.pattern_high <<= 1;
+        self.attribute_low <<= 1;
+        self.attribute_high <<= 1;
+    }
+
+    /// Returns the 2-bit pixel and the 2-bit palette selected by fine X
+    fn pixel(&self, fine_x: u8) -> (u8, u8) {
+        let mask = 0x8000 >> fine_x;
+        let bit = |register: u16| (register & mask != 0) as u8;
+        (bit(self.pattern_high) << 1 | bit(self.pattern_low), bit(self.attribute_high) << 1 | bit(self.attribute_low))
+    }
+}
+
+impl Ppu {
+    /// Runs 1 dot (PPU cycle)
+    ///
+    /// see: [PPU rendering](https://www.nesdev.org/wiki/PPU_rendering)
+    pub fn tick(&mut self, mut cartridge: Option<&mut dyn Mapper>) {
+        let is_visible_scanline = self.scanline < SCREEN_HEIGHT as u16;
+        let is_pre_render_scanline = self.scanline == PRE_RENDER_SCANLINE;
+
+        if is_pre_render_scanline && self.dot == 1 {
+            self.status.remove(PpuStatus::VBLANK | PpuStatus::SPRITE_0_HIT | PpuStatus::SPRITE_OVERFLOW);
+        }
+
+        if (is_visible_scanline || is_pre_render_scanline) && self.rendering_enabled() {
+            self.fetch_background(&mut cartridge);
+            self.fetch_sprites(&mut cartridge);
+        }
+
+        if is_visible_scanline && (1..=SCREEN_WIDTH as u16).contains(&self.dot) {
+            self.render_pixel();
+        }
+
+        if self.scanline == VBLANK_SCANLINE && self.dot == 1 {
+            self.status.insert(PpuStatus::VBLANK);
+        }
+
+        self.advance_dot();
+    }
+
+    fn advance_dot(&mut self) {
+        self.dot += 1;
+
+        // Odd frames skip the last dot of the pre-render scanline when rendering is enabled
+        if self.scanline == PRE_RENDER_SCANLINE && self.dot == DOTS_PER_SCANLINE - 1
+            && self.frame % 2 == 1 && self.rendering_enabled() {
+            self.dot += 1;
+        }
+
+        if self.dot >= DOTS_PER_SCANLINE {
+            self.dot = 0;
+            self.scanline += 1;
+            if self.scanline >= SCANLINES_PER_FRAME {
+                self.scanline = 0;
+                self.frame += 1;
+            }
+        }
+    }
+
+    fn fetch_background(&mut self, cartridge: &mut Option<&mut dyn Mapper>) {
+        let dot = self.dot;
+
+        if (2..=257).contains(&dot) || (321..=337).contains(&dot) {
+            if self.mask.contains(PpuMask::SHOW_BACKGROUND) {
+                self.background.shift();
+            }
+
+            match (dot - 1) % 8 {
+                0 => {
+                    self.background.load();
+                    self.background.next_tile = self.fetch(NAMETABLES_START | (self.vram_address & 0x0FFF), cartridge);
+                },
+                2 => {
+                    let v = self.vram_address;
+                    let address = NAMETABLES_START | ATTRIBUTE_TABLE_OFFSET | (v & 0x0C00) | ((v >> 4) & 0x38) | ((v >> 2) & 0x07);
+                    // Each attribute byte covers 4x4 tiles. Coarse X and Y bit 1 select the 2x2 tiles quadrant
+                    let shift = ((v >> 4) & 0b100) | (v & 0b10);
+                    self.background.next_attribute = (self.fetch(address, cartridge) >> shift) & 0b11;
+                },
+                4 => self.background.next_pattern_low = self.fetch(self.background_pattern_address(), cartridge),
+                6 => self.background.next_pattern_high = self.fetch(self.background_pattern_address() + 8, cartridge),
+                7 => self.increment_coarse_x(),
+                _ => ()
+            }
+        }
+
+        match dot {
+            256 => self.increment_y(),
+            257 => self.copy_horizontal_position(),
+            // Unused nametable fetches at the end of the scanline
+            338 | 340 => self.background.next_tile = self.fetch(NAMETABLES_START | (self.vram_address & 0x0FFF), cartridge),
+            280..=304 if self.scanline == PRE_RENDER_SCANLINE => self.copy_vertical_position(),
+            _ => ()
+        }
+    }
+
+    /// Reads the PPU address space during rendering, so mappers can watch the address bus
+    pub(super) fn fetch(&self, address: u16, cartridge: &mut Option<&mut dyn Mapper>) -> u8 {
+        if let Some(cartridge) = cartridge.as_deref_mut() {
+            cartridge.notify_ppu_address(address);
+        }
+        self.read(address, cartridge.as_deref())
+    }
+
+    fn background_pattern_address(&self) -> u16 {
+        let table = if self.ctrl.contains(PpuCtrl::BACKGROUND_TABLE) { 0x1000 } else { 0x0000 };
+        let fine_y = (self.vram_address >> 12) & 0b111;
+        table + self.background.next_tile as u16 * 16 + fine_y
+    }
+
+    fn increment_coarse_x(&mut self) {
+        if self.vram_address & 0x001F == 31 {
+            // Wraps to the horizontally adjacent nametable
+            self.vram_address &= !0x001F;
+            self.vram_address ^= 0x0400;
+        } else {
+            self.vram_address += 1;
+        }
+    }
+
+    fn increment_y(&mut self) {
+        if self.vram_address & 0x7000 != 0x7000 {
+            self.vram_address += 0x1000;
+            return;
+        }
+
+        self.vram_address &= !0x7000;
+        let mut coarse_y = (self.vram_address & 0x03E0) >> 5;
+        if coarse_y == 29 {
+            // Last row of the nametable: wraps to the vertically adjacent one
+            coarse_y = 0;
+            self.vram_address ^= 0x0800;
+        } else if coarse_y == 31 {
+            // Coarse Y out of bounds (attribute table): wraps without switching nametable
+            coarse_y = 0;
+        } else {
+            coarse_y += 1;
+        }
+        self.vram_address = (self.vram_address & !0x03E0) | (coarse_y << 5);
+    }
+
+    /// v: ....A.. ...BCDEF <- t: ....A.. ...BCDEF
+    fn copy_horizontal_position(&mut self) {
+        self.vram_address = (self.vram_address & !0x041F) | (self.temp_vram_address & 0x041F);
+    }
+
+    /// v: GHIA.BC DEF..... <- t: GHIA.BC DEF.....
+    fn copy_vertical_position(&mut self) {
+        self.vram_address = (self.vram_address & !0x7BE0) | (self.temp_vram_address & 0x7BE0);
+    }
+
+    /// Composes background and sprite pixels, then writes the color to the framebuffer
+    ///
+    /// see: [PPU rendering](https://www.nesdev.org/wiki/PPU_rendering#Preface)
+    fn render_pixel(&mut self) {
+        let x = self.dot as usize - 1;
+        let y = self.scanline as usize;
+
+        let show_background = self.mask.contains(PpuMask::SHOW_BACKGROUND)
+            && (x >= 8 || self.mask.contains(PpuMask::SHOW_BACKGROUND_LEFT));
+        let show_sprites = self.mask.contains(PpuMask::SHOW_SPRITES)
+            && (x >= 8 || self.mask.contains(PpuMask::SHOW_SPRITES_LEFT));
+
+        let (pixel, palette) = if show_background { self.background.pixel(self.fine_x) } else { (0, 0) };
+        let sprite = if show_sprites { self.sprite_pixel(x) } else { None };
+
+        if let Some(sprite) = &sprite {
+            // Sprite 0 hit never happens at X=255
+            if sprite.is_sprite_0 && pixel != 0 && x != SCREEN_WIDTH - 1 {
+                self.status.insert(PpuStatus::SPRITE_0_HIT);
+            }
+        }
+
+        // Transparent pixels show the backdrop color ($3F00). Sprite palettes start at $3F10
+        let palette_address = match sprite {
+            Some(sprite) if pixel == 0 || !sprite.behind_background => 0x10 | (sprite.palette << 2 | sprite.pixel) as usize,
+            _ if pixel != 0 => (palette << 2 | pixel) as usize,
+            _ => 0
+        };
+        let mut color = self.palette[palette_address];
+        if self.mask.contains(PpuMask::GRAYSCALE) {
+            color &= 0x30;
+        }
+        self.framebuffer[y * SCREEN_WIDTH + x] = color;
+    }
+}
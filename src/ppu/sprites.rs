@@ -0,0 +1,185 @@
+use super::{Ppu, NAMETABLES_START};
+use super::types::{PpuCtrl, PpuStatus, SpriteAttributes, SCREEN_HEIGHT};
+use crate::cartridge::mapper::Mapper;
+
+const SPRITE_COUNT: usize = 64;
+const SPRITE_BYTES: usize = 4;
+pub const MAX_SPRITES_PER_SCANLINE: usize = 8;
+
+/// Sprite fetches happen on dots 257-320, 8 dots per sprite
+const SPRITE_FETCH_START: u16 = 257;
+const SPRITE_FETCH_END: u16 = 320;
+/// Unused sprite slots fetch the pattern of tile $FF
+const EMPTY_SLOT_TILE: u8 = 0xFF;
+
+/// Sprite selected for the next scanline, with its pattern row already fetched
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct SpriteSlot {
+    y: u8,
+    tile: u8,
+    attributes: u8,
+    x: u8,
+    is_sprite_0: bool,
+    pattern_low: u8,
+    pattern_high: u8
+}
+
+/// Secondary OAM: sprites found during the evaluation of the previous scanline
+#[derive(Debug, Default)]
+pub(super) struct ScanlineSprites {
+    slots: [SpriteSlot; MAX_SPRITES_PER_SCANLINE],
+    count: usize
+}
+
+/// Opaque sprite pixel, ready to be composed with the background
+pub(super) struct SpritePixel {
+    pub(super) pixel: u8,
+    pub(super) palette: u8,
+    pub(super) behind_background: bool,
+    pub(super) is_sprite_0: bool
+}
+
+impl Ppu {
+    /// Runs sprite evaluation and pattern fetches for the next scanline (dots 257-320)
+    ///
+    /// see: [PPU sprite evaluation](https://www.nesdev.org/wiki/PPU_sprite_evaluation)
+    pub(super) fn fetch_sprites(&mut self, cartridge: &mut Option<&mut dyn Mapper>) {
+        if !(SPRITE_FETCH_START..=SPRITE_FETCH_END).contains(&self.dot) {
+            return;
+        }
+
+        // OAMADDR is reset during sprite fetches
+        self.oam_address = 0;
+
+        if self.dot == SPRITE_FETCH_START {
+            if self.scanline < SCREEN_HEIGHT as u16 {
+                self.evaluate_sprites();
+            } else {
+                // No sprites on the first scanline: the pre-render scanline doesn't evaluate any
+                self.sprites.count = 0;
+            }
+        }
+
+        let slot = ((self.dot - SPRITE_FETCH_START) / 8) as usize;
+        match (self.dot - SPRITE_FETCH_START) % 8 {
+            // Unused nametable fetches
+            0 | 2 => { self.fetch(NAMETABLES_START | (self.vram_address & 0x0FFF), cartridge); },
+            4 => {
+                let data = self.fetch(self.sprite_pattern_address(slot), cartridge);
+                self.sprites.slots[slot].pattern_low = self.flip_horizontally(slot, data);
+            },
+            6 => {
+                let data = self.fetch(self.sprite_pattern_address(slot) + 8, cartridge);
+                self.sprites.slots[slot].pattern_high = self.flip_horizontally(slot, data);
+            },
+            _ => ()
+        }
+    }
+
+    fn sprite_height(&self) -> u16 {
+        if self.ctrl.contains(PpuCtrl::SPRITE_SIZE_8X16) { 16 } else { 8 }
+    }
+
+    /// Copies up to 8 sprites in range of the next scanline to the secondary OAM.
+    /// OAM holds Y minus 1, so sprites in range of the current scanline are drawn on the next one
+    fn evaluate_sprites(&mut self) {
+        let height = self.sprite_height();
+        let scanline = self.scanline;
+        let in_range = |y: u8| scanline.wrapping_sub(y as u16) < height;
+
+        self.sprites.count = 0;
+        let mut n = 0;
+        while n < SPRITE_COUNT && self.sprites.count < MAX_SPRITES_PER_SCANLINE {
+            let sprite = &self.oam[n * SPRITE_BYTES..(n + 1) * SPRITE_BYTES];
+            if in_range(sprite[0]) {
+                self.sprites.slots[self.sprites.count] = SpriteSlot {
+                    y: sprite[0],
+                    tile: sprite[1],
+                    attributes: sprite[2],
+                    x: sprite[3],
+                    is_sprite_0: n == 0,
+                    pattern_low: 0,
+                    pattern_high: 0
+                };
+                self.sprites.count += 1;
+            }
+            n += 1;
+        }
+
+        // Hardware bug: once 8 sprites are found, the byte index is incremented along with the sprite
+        // index, so tile, attributes or X bytes are checked as if they were Y coordinates
+        let mut m = 0;
+        while n < SPRITE_COUNT {
+            if in_range(self.oam[n * SPRITE_BYTES + m]) {
+                self.status.insert(PpuStatus::SPRITE_OVERFLOW);
+                break;
+            }
+            n += 1;
+            m = (m + 1) % SPRITE_BYTES;
+        }
+    }
+
+    fn sprite_pattern_address(&self, slot: usize) -> u16 {
+        let height = self.sprite_height();
+        let (tile, mut row) = if slot < self.sprites.count {
+            let sprite = &self.sprites.slots[slot];
+            (sprite.tile, self.scanline.wrapping_sub(sprite.y as u16) % height)
+        } else {
+            (EMPTY_SLOT_TILE, 0)
+        };
+
+        let attributes = self.sprite_attributes(slot);
+        if attributes.contains(SpriteAttributes::FLIP_VERTICAL) {
+            row = height - 1 - row;
+        }
+
+        if height == 16 {
+            // 8x16 sprites take the pattern table from bit 0 of the tile index,
+            // then use 2 consecutive tiles: top and bottom halves
+            let table = (tile as u16 & 1) * 0x1000;
+            let tile = (tile & 0xFE) as u16 + row / 8;
+            table + tile * 16 + row % 8
+        } else {
+            let table = if self.ctrl.contains(PpuCtrl::SPRITE_TABLE) { 0x1000 } else { 0x0000 };
+            table + tile as u16 * 16 + row
+        }
+    }
+
+    fn sprite_attributes(&self, slot: usize) -> SpriteAttributes {
+        if slot < self.sprites.count {
+            SpriteAttributes::from_bits_truncate(self.sprites.slots[slot].attributes)
+        } else {
+            SpriteAttributes::empty()
+        }
+    }
+
+    /// Patterns are stored with the leftmost pixel in bit 7, flipped sprites are reversed once fetched
+    fn flip_horizontally(&self, slot: usize, data: u8) -> u8 {
+        if self.sprite_attributes(slot).contains(SpriteAttributes::FLIP_HORIZONTAL) {
+            data.reverse_bits()
+        } else {
+            data
+        }
+    }
+
+    /// Returns the first opaque sprite pixel at the given X coordinate.
+    /// Sprites with lower OAM index have priority, even when they are behind the background
+    pub(super) fn sprite_pixel(&self, x: usize) -> Option<SpritePixel> {
+        self.sprites.slots[..self.sprites.count].iter().find_map(|sprite| {
+            let offset = x.checked_sub(sprite.x as usize).filter(|&offset| offset < 8)?;
+            let bit = 7 - offset;
+            let pixel = ((sprite.pattern_high >> bit) & 1) << 1 | ((sprite.pattern_low >> bit) & 1);
+            if pixel == 0 {
+                return None;
+            }
+
+            let attributes = SpriteAttributes::from_bits_truncate(sprite.attributes);
+            Some(SpritePixel {
+                pixel,
+                palette: (attributes & SpriteAttributes::PALETTE).bits(),
+                behind_background: attributes.contains(SpriteAttributes::BEHIND_BACKGROUND),
+                is_sprite_0: sprite.is_sprite_0
+            })
+        })
+    }
+}
@@ -0,0 +1,673 @@
+use super::*;
+use super::types::{DOTS_PER_SCANLINE, PRE_RENDER_SCANLINE, SCANLINES_PER_FRAME, VBLANK_SCANLINE};
+use crate::cartridge::Cartridge;
+use crate::cartridge::mapper;
+
+const BACKDROP: u8 = 0x0F;
+const COLOR_1: u8 = 0x16;
+const COLOR_2: u8 = 0x2A;
+
+/// NROM cartridge with 8 KiB of CHR RAM, so tests can write their own tiles
+fn chr_ram_cartridge(flags_6: u8) -> Box<dyn Mapper> {
+    let mut rom = vec![0x4E, 0x45, 0x53, 0x1A, 1, 0, flags_6, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    rom.extend(vec![0; 0x4000]);
+    mapper::new_mapper(Cartridge::from_bytes(&rom).unwrap()).unwrap()
+}
+
+fn set_vram_address(ppu: &mut Ppu, address: u16) {
+    ppu.write_register((address >> 8) as u8, PPUADDR, None);
+    ppu.write_register(address as u8, PPUADDR, None);
+}
+
+fn write_vram(ppu: &mut Ppu, cartridge: &mut dyn Mapper, address: u16, data: &[u8]) {
+    set_vram_address(ppu, address);
+    for &byte in data {
+        ppu.write_register(byte, PPUDATA, Some(&mut *cartridge));
+    }
+}
+
+fn run_frame(ppu: &mut Ppu, cartridge: &mut dyn Mapper) {
+    let frame = ppu.frame();
+    while ppu.frame() == frame {
+        ppu.tick(Some(&mut *cartridge));
+    }
+}
+
+/// Tile 1 uses color 1 for every pixel, backdrop is BACKDROP and palette 0 color 1 is COLOR_1.
+/// Background is enabled and scroll is reset
+fn background_setup(cartridge: &mut dyn Mapper) -> Ppu {
+    let mut ppu = Ppu::new();
+    write_vram(&mut ppu, cartridge, 0x0010, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0]);
+    write_vram(&mut ppu, cartridge, 0x3F00, &[BACKDROP, COLOR_1]);
+    write_vram(&mut ppu, cartridge, 0x3F0D, &[COLOR_2]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUCTRL, None);
+    ppu.write_register(0b0000_1010, PPUMASK, None);
+    ppu
+}
+
+fn first_row(ppu: &Ppu) -> &[u8] {
+    &ppu.framebuffer()[0..32]
+}
+
+#[test]
+fn reading_status_clears_vblank_and_write_latch() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.status.insert(PpuStatus::VBLANK);
+    ppu.write_register(0x21, PPUADDR, None);
+
+    // When
+    let status = ppu.read_register(PPUSTATUS, None);
+
+    // Then
+    assert_eq!(0x80, status & 0xE0);
+    assert!(!ppu.status.contains(PpuStatus::VBLANK));
+    assert!(!ppu.write_latch);
+}
+
+#[test]
+fn status_lower_bits_return_data_bus() {
+    // Given
+    let mut ppu = Ppu::new();
+
+    // When
+    ppu.write_register(0x1F, PPUMASK, None);
+
+    // Then
+    assert_eq!(0x1F, ppu.peek_register(PPUSTATUS, None));
+    assert_eq!(0x1F, ppu.read_register(PPUCTRL, None));
+}
+
+#[test]
+fn ppuaddr_double_write_sets_vram_address() {
+    // Given
+    let mut ppu = Ppu::new();
+
+    // When
+    ppu.write_register(0x7F, PPUADDR, None);
+    assert_eq!(0, ppu.vram_address);
+    ppu.write_register(0x34, PPUADDR, None);
+
+    // Then
+    // Bit 14 is cleared on the first write
+    assert_eq!(0x3F34, ppu.vram_address);
+}
+
+#[test]
+fn ppuscroll_double_write_sets_temp_address_and_fine_x() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.write_register(0b0000_0011, PPUCTRL, None);
+
+    // When
+    ppu.write_register(0b0111_1101, PPUSCROLL, None);
+    ppu.write_register(0b0101_1110, PPUSCROLL, None);
+
+    // Then
+    // Fine Y 110, nametable 11, coarse Y 01011, coarse X 01111
+    assert_eq!(0b0110_1101_0110_1111, ppu.temp_vram_address);
+    assert_eq!(0b101, ppu.fine_x);
+    assert!(!ppu.write_latch);
+}
+
+#[test]
+fn ppudata_read_is_buffered() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = Ppu::new();
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2000, &[0x11, 0x22]);
+    set_vram_address(&mut ppu, 0x2000);
+
+    // When
+    let first = ppu.read_register(PPUDATA, Some(cartridge.as_mut()));
+    let second = ppu.read_register(PPUDATA, Some(cartridge.as_mut()));
+    let third = ppu.read_register(PPUDATA, Some(cartridge.as_mut()));
+
+    // Then
+    assert_eq!((0x00, 0x11, 0x22), (first, second, third));
+}
+
+#[test]
+fn ppudata_palette_read_is_immediate_and_buffers_nametable() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = Ppu::new();
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2F00, &[0x42]);
+    write_vram(&mut ppu, cartridge.as_mut(), 0x3F00, &[0x2C]);
+    set_vram_address(&mut ppu, 0x3F00);
+
+    // When
+    let palette = ppu.read_register(PPUDATA, Some(cartridge.as_mut()));
+
+    // Then
+    assert_eq!(0x2C, palette);
+    assert_eq!(0x42, ppu.read_buffer);
+}
+
+#[test]
+fn ppudata_increments_by_32_going_down() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.write_register(0b0000_0100, PPUCTRL, None);
+    set_vram_address(&mut ppu, 0x2000);
+
+    // When
+    ppu.write_register(0x01, PPUDATA, None);
+    ppu.write_register(0x02, PPUDATA, None);
+
+    // Then
+    assert_eq!(0x2040, ppu.vram_address);
+    assert_eq!(0x02, ppu.read(0x2020, None));
+}
+
+#[test]
+fn ppudata_writes_chr_ram() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = Ppu::new();
+
+    // When
+    write_vram(&mut ppu, cartridge.as_mut(), 0x1FFF, &[0x42]);
+
+    // Then
+    assert_eq!(0x42, cartridge.ppu_read(0x1FFF));
+}
+
+#[test]
+fn palette_sprite_backdrop_entries_are_mirrored() {
+    // Given
+    let mut ppu = Ppu::new();
+
+    // When
+    write_vram(&mut ppu, &mut *chr_ram_cartridge(0), 0x3F10, &[0x3D]);
+    write_vram(&mut ppu, &mut *chr_ram_cartridge(0), 0x3F24, &[0xFF]);
+
+    // Then
+    assert_eq!(0x3D, ppu.read(0x3F00, None));
+    assert_eq!(0x3F, ppu.read(0x3F04, None));
+    assert_eq!(0x3F, ppu.read(0x3F14, None));
+}
+
+#[test]
+fn nametables_are_mirrored_according_to_cartridge() {
+    assert_eq!(nametable_index(0x2400, Mirroring::Horizontal), 0x0000);
+    assert_eq!(nametable_index(0x2801, Mirroring::Horizontal), 0x0401);
+    assert_eq!(nametable_index(0x2C00, Mirroring::Horizontal), 0x0400);
+    assert_eq!(nametable_index(0x2400, Mirroring::Vertical), 0x0400);
+    assert_eq!(nametable_index(0x2801, Mirroring::Vertical), 0x0001);
+    assert_eq!(nametable_index(0x2C00, Mirroring::SingleScreenLower), 0x0000);
+    assert_eq!(nametable_index(0x2000, Mirroring::SingleScreenUpper), 0x0400);
+    assert_eq!(nametable_index(0x2C00, Mirroring::FourScreen), 0x0C00);
+    assert_eq!(nametable_index(0x3400, Mirroring::Vertical), 0x0400);
+}
+
+#[test]
+fn nametable_writes_use_cartridge_mirroring() {
+    // Given
+    // Vertical mirroring
+    let mut cartridge = chr_ram_cartridge(0b0000_0001);
+    let mut ppu = Ppu::new();
+
+    // When
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2805, &[0x42]);
+
+    // Then
+    assert_eq!(0x42, ppu.read(0x2005, Some(cartridge.as_ref())));
+    assert_eq!(0x00, ppu.read(0x2405, Some(cartridge.as_ref())));
+}
+
+#[test]
+fn vblank_starts_at_scanline_241_dot_1() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.write_register(0b1000_0000, PPUCTRL, None);
+
+    // When
+    while !(ppu.scanline() == VBLANK_SCANLINE && ppu.dot() == 1) {
+        ppu.tick(None);
+    }
+    assert!(!ppu.nmi());
+    ppu.tick(None);
+
+    // Then
+    assert!(ppu.status.contains(PpuStatus::VBLANK));
+    assert!(ppu.nmi());
+}
+
+#[test]
+fn vblank_ends_at_pre_render_scanline() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.status.insert(PpuStatus::VBLANK | PpuStatus::SPRITE_0_HIT | PpuStatus::SPRITE_OVERFLOW);
+    ppu.scanline = PRE_RENDER_SCANLINE;
+    ppu.dot = 1;
+
+    // When
+    ppu.tick(None);
+
+    // Then
+    assert!(ppu.status.is_empty());
+}
+
+#[test]
+fn nmi_follows_ppuctrl_during_vblank() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.status.insert(PpuStatus::VBLANK);
+
+    // When / Then
+    assert!(!ppu.nmi());
+    ppu.write_register(0b1000_0000, PPUCTRL, None);
+    assert!(ppu.nmi());
+    ppu.read_register(PPUSTATUS, None);
+    assert!(!ppu.nmi());
+}
+
+#[test]
+fn odd_frames_are_one_dot_shorter_when_rendering() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = background_setup(cartridge.as_mut());
+    let frame_dots = DOTS_PER_SCANLINE as u32 * SCANLINES_PER_FRAME as u32;
+    let mut dots = [0u32; 2];
+
+    // When
+    for frame_dots in dots.iter_mut() {
+        let frame = ppu.frame();
+        while ppu.frame() == frame {
+            ppu.tick(Some(cartridge.as_mut()));
+            *frame_dots += 1;
+        }
+    }
+
+    // Then
+    assert_eq!([frame_dots, frame_dots - 1], dots);
+}
+
+#[test]
+fn renders_background_tiles() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = background_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2000, &[1, 0, 1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+
+    // When
+    run_frame(&mut ppu, cartridge.as_mut());
+    run_frame(&mut ppu, cartridge.as_mut());
+
+    // Then
+    let mut expected = [BACKDROP; 32];
+    expected[0..8].fill(COLOR_1);
+    expected[16..24].fill(COLOR_1);
+    assert_eq!(&expected, first_row(&ppu));
+    // Every row of the tile is drawn, next tile row is empty
+    assert_eq!(COLOR_1, ppu.framebuffer()[7 * SCREEN_WIDTH]);
+    assert_eq!(BACKDROP, ppu.framebuffer()[8 * SCREEN_WIDTH]);
+}
+
+#[test]
+fn attribute_table_selects_palette() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = background_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2000, &[1, 0, 1]);
+    // Top right 2x2 tiles use palette 3
+    write_vram(&mut ppu, cartridge.as_mut(), 0x23C0, &[0b0000_1100]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+
+    // When
+    run_frame(&mut ppu, cartridge.as_mut());
+    run_frame(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(COLOR_1, ppu.framebuffer()[0]);
+    assert_eq!(COLOR_2, ppu.framebuffer()[16]);
+}
+
+#[test]
+fn fine_x_scroll_shifts_background() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = background_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2000, &[1]);
+    ppu.write_register(3, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+
+    // When
+    run_frame(&mut ppu, cartridge.as_mut());
+    run_frame(&mut ppu, cartridge.as_mut());
+
+    // Then
+    let mut expected = [BACKDROP; 32];
+    expected[0..5].fill(COLOR_1);
+    assert_eq!(&expected, first_row(&ppu));
+}
+
+#[test]
+fn background_can_be_hidden_in_leftmost_8_pixels() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = background_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2000, &[1, 1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0b0000_1000, PPUMASK, None);
+
+    // When
+    run_frame(&mut ppu, cartridge.as_mut());
+    run_frame(&mut ppu, cartridge.as_mut());
+
+    // Then
+    let mut expected = [BACKDROP; 32];
+    expected[8..16].fill(COLOR_1);
+    assert_eq!(&expected, first_row(&ppu));
+}
+
+const SPRITE_COLOR_1: u8 = 0x30;
+const SPRITE_COLOR_2: u8 = 0x21;
+
+/// Same as `background_setup`, with sprites enabled and an empty nametable.
+/// Tile 2 only has the leftmost pixel of every row and tile 3 only has its first row.
+/// Sprite palette 0 color 1 is SPRITE_COLOR_1 and sprite palette 1 color 1 is SPRITE_COLOR_2.
+/// Every sprite is hidden below the screen
+fn sprite_setup(cartridge: &mut dyn Mapper) -> Ppu {
+    let mut ppu = background_setup(cartridge);
+    write_vram(&mut ppu, cartridge, 0x0020, &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80]);
+    write_vram(&mut ppu, cartridge, 0x0030, &[0xFF]);
+    write_vram(&mut ppu, cartridge, 0x3F11, &[SPRITE_COLOR_1, 0, 0, 0, SPRITE_COLOR_2]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0b0001_1110, PPUMASK, None);
+    ppu.oam = [0xFF; OAM_SIZE];
+    ppu
+}
+
+/// Sets sprite number `index` in OAM
+fn set_sprite(ppu: &mut Ppu, index: u8, y: u8, tile: u8, attributes: u8, x: u8) {
+    ppu.write_register(index * 4, OAMADDR, None);
+    for data in [y, tile, attributes, x] {
+        ppu.write_register(data, OAMDATA, None);
+    }
+}
+
+/// Renders a whole frame, then stops at the start of the vertical blank,
+/// before the pre-render scanline clears the status flags
+fn render(ppu: &mut Ppu, cartridge: &mut dyn Mapper) {
+    run_frame(ppu, cartridge);
+    while ppu.scanline() != VBLANK_SCANLINE {
+        ppu.tick(Some(&mut *cartridge));
+    }
+}
+
+fn pixel(ppu: &Ppu, x: usize, y: usize) -> u8 {
+    ppu.framebuffer()[y * SCREEN_WIDTH + x]
+}
+
+#[test]
+fn oamdata_write_increments_oamaddr() {
+    // Given
+    let mut ppu = Ppu::new();
+    ppu.write_register(0xFF, OAMADDR, None);
+
+    // When
+    ppu.write_register(0x42, OAMDATA, None);
+    ppu.write_register(0x69, OAMDATA, None);
+
+    // Then
+    assert_eq!(0x42, ppu.oam[0xFF]);
+    assert_eq!(0x69, ppu.oam[0x00]);
+    ppu.write_register(0xFF, OAMADDR, None);
+    assert_eq!(0x42, ppu.read_register(OAMDATA, None));
+}
+
+#[test]
+fn renders_sprite_one_scanline_below_oam_y() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    set_sprite(&mut ppu, 0, 9, 1, 0, 20);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(BACKDROP, pixel(&ppu, 20, 9));
+    assert_eq!(BACKDROP, pixel(&ppu, 19, 10));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 20, 10));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 27, 17));
+    assert_eq!(BACKDROP, pixel(&ppu, 28, 17));
+    assert_eq!(BACKDROP, pixel(&ppu, 20, 18));
+}
+
+#[test]
+fn sprite_attributes_select_palette_and_flip() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    set_sprite(&mut ppu, 0, 9, 2, 0b0100_0001, 20);
+    set_sprite(&mut ppu, 1, 19, 3, 0b1000_0000, 20);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(BACKDROP, pixel(&ppu, 20, 10));
+    assert_eq!(SPRITE_COLOR_2, pixel(&ppu, 27, 10));
+    assert_eq!(BACKDROP, pixel(&ppu, 20, 20));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 20, 27));
+}
+
+#[test]
+fn sprites_with_lower_oam_index_have_priority() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    set_sprite(&mut ppu, 0, 9, 1, 0b0000_0001, 20);
+    set_sprite(&mut ppu, 1, 9, 1, 0, 24);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(SPRITE_COLOR_2, pixel(&ppu, 27, 10));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 28, 10));
+}
+
+#[test]
+fn sprite_behind_background_is_only_visible_on_transparent_background() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2020, &[0, 0, 1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    set_sprite(&mut ppu, 0, 9, 1, 0b0010_0000, 12);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 15, 10));
+    assert_eq!(COLOR_1, pixel(&ppu, 16, 10));
+}
+
+#[test]
+fn sprites_can_be_hidden_in_leftmost_8_pixels() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    ppu.write_register(0b0001_1010, PPUMASK, None);
+    set_sprite(&mut ppu, 0, 9, 1, 0, 4);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(BACKDROP, pixel(&ppu, 7, 10));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 8, 10));
+}
+
+#[test]
+fn renders_8x16_sprites() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    ppu.write_register(0b0010_0000, PPUCTRL, None);
+    // Tiles 2 and 3 of the pattern table at $0000
+    set_sprite(&mut ppu, 0, 9, 2, 0, 20);
+    set_sprite(&mut ppu, 1, 39, 2, 0b1000_0000, 20);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 20, 17));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 21, 18));
+    assert_eq!(BACKDROP, pixel(&ppu, 21, 19));
+    // Vertical flip swaps top and bottom tiles
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 21, 40 + 15 - 8));
+    assert_eq!(BACKDROP, pixel(&ppu, 21, 40 + 15 - 9));
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 20, 40 + 15));
+}
+
+#[test]
+fn draws_up_to_8_sprites_per_scanline() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    for index in 0..9 {
+        set_sprite(&mut ppu, index, 9, 1, 0, index * 10 + 10);
+    }
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert_eq!(SPRITE_COLOR_1, pixel(&ppu, 80, 10));
+    assert_eq!(BACKDROP, pixel(&ppu, 90, 10));
+}
+
+#[test]
+fn sprite_overflow_is_set_with_more_than_8_sprites_on_a_scanline() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    for index in 0..8 {
+        set_sprite(&mut ppu, index, 9, 1, 0, 0);
+    }
+    render(&mut ppu, cartridge.as_mut());
+    assert!(!ppu.status.contains(PpuStatus::SPRITE_OVERFLOW));
+
+    // When
+    set_sprite(&mut ppu, 8, 16, 1, 0, 0);
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert!(ppu.status.contains(PpuStatus::SPRITE_OVERFLOW));
+}
+
+#[test]
+fn sprite_overflow_checks_wrong_bytes_after_8_sprites() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    for index in 0..8 {
+        set_sprite(&mut ppu, index, 9, 1, 0, 0);
+    }
+    // Sprite 8 is out of range, so sprite 9 is checked with its tile byte instead of Y
+    set_sprite(&mut ppu, 8, 0xF0, 1, 0, 0);
+    set_sprite(&mut ppu, 9, 9, 0xF0, 0, 0);
+
+    // When / Then
+    render(&mut ppu, cartridge.as_mut());
+    assert!(!ppu.status.contains(PpuStatus::SPRITE_OVERFLOW));
+
+    set_sprite(&mut ppu, 9, 0xF0, 9, 0, 0);
+    render(&mut ppu, cartridge.as_mut());
+    assert!(ppu.status.contains(PpuStatus::SPRITE_OVERFLOW));
+}
+
+#[test]
+fn sprite_0_hit_when_opaque_pixels_overlap() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2022, &[1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    set_sprite(&mut ppu, 0, 9, 1, 0, 4);
+    render(&mut ppu, cartridge.as_mut());
+    assert!(!ppu.status.contains(PpuStatus::SPRITE_0_HIT));
+
+    // When
+    set_sprite(&mut ppu, 0, 9, 1, 0b0010_0000, 12);
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    // Priority doesn't matter
+    assert!(ppu.status.contains(PpuStatus::SPRITE_0_HIT));
+}
+
+#[test]
+fn sprite_0_hit_never_happens_at_x_255() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x203F, &[1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    set_sprite(&mut ppu, 0, 9, 1, 0, 255);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert!(!ppu.status.contains(PpuStatus::SPRITE_0_HIT));
+}
+
+#[test]
+fn only_sprite_0_triggers_sprite_0_hit() {
+    // Given
+    let mut cartridge = chr_ram_cartridge(0);
+    let mut ppu = sprite_setup(cartridge.as_mut());
+    write_vram(&mut ppu, cartridge.as_mut(), 0x2022, &[1]);
+    ppu.write_register(0, PPUSCROLL, None);
+    ppu.write_register(0, PPUSCROLL, None);
+    set_sprite(&mut ppu, 1, 9, 1, 0, 16);
+
+    // When
+    render(&mut ppu, cartridge.as_mut());
+
+    // Then
+    assert!(!ppu.status.contains(PpuStatus::SPRITE_0_HIT));
+}
+
+#[test]
+fn sprite_fetches_clock_mmc3_scanline_counter() {
+    // Given
+    // MMC3 with CHR RAM, background at $0000 and sprites at $1000
+    let mut rom = vec![0x4E, 0x45, 0x53, 0x1A, 2, 0, 0x40, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    rom.extend(vec![0; 0x8000]);
+    let mut cartridge = mapper::new_mapper(Cartridge::from_bytes(&rom).unwrap()).unwrap();
+    cartridge.cpu_write(10, 0xC000);
+    cartridge.cpu_write(0, 0xC001);
+    cartridge.cpu_write(0, 0xE001);
+    let mut ppu = Ppu::new();
+    ppu.scanline = PRE_RENDER_SCANLINE;
+    ppu.write_register(0b0000_1000, PPUCTRL, None);
+    ppu.write_register(0b0001_1000, PPUMASK, None);
+
+    // When
+    while !cartridge.irq() {
+        ppu.tick(Some(cartridge.as_mut()));
+    }
+
+    // Then
+    // Pre-render scanline reloads the counter, then it's decremented once per scanline
+    assert_eq!(9, ppu.scanline());
+}
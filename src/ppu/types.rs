@@ -0,0 +1,62 @@
+use bitflags::bitflags;
+
+pub const SCREEN_WIDTH: usize = 256;
+pub const SCREEN_HEIGHT: usize = 240;
+
+/// Dots (PPU cycles) per scanline
+pub const DOTS_PER_SCANLINE: u16 = 341;
+/// Scanlines per frame, including vertical blank and the pre-render scanline (NTSC)
+pub const SCANLINES_PER_FRAME: u16 = 262;
+pub const VBLANK_SCANLINE: u16 = 241;
+pub const PRE_RENDER_SCANLINE: u16 = 261;
+
+/// PPU dots per CPU cycle (NTSC)
+pub const DOTS_PER_CPU_CYCLE: u8 = 3;
+
+bitflags! {
+    /// $2000 PPUCTRL
+    pub struct PpuCtrl: u8 {
+        const NAMETABLE_X          = 0b00000001;
+        const NAMETABLE_Y          = 0b00000010;
+        /// Add 32 to the VRAM address after each PPUDATA access (going down), instead of 1
+        const VRAM_INCREMENT       = 0b00000100;
+        const SPRITE_TABLE         = 0b00001000;
+        const BACKGROUND_TABLE     = 0b00010000;
+        const SPRITE_SIZE_8X16     = 0b00100000;
+        const MASTER_SLAVE         = 0b01000000;
+        const GENERATE_NMI         = 0b10000000;
+    }
+}
+
+bitflags! {
+    /// $2001 PPUMASK
+    pub struct PpuMask: u8 {
+        const GRAYSCALE            = 0b00000001;
+        const SHOW_BACKGROUND_LEFT = 0b00000010;
+        const SHOW_SPRITES_LEFT    = 0b00000100;
+        const SHOW_BACKGROUND      = 0b00001000;
+        const SHOW_SPRITES         = 0b00010000;
+        const EMPHASIZE_RED        = 0b00100000;
+        const EMPHASIZE_GREEN      = 0b01000000;
+        const EMPHASIZE_BLUE       = 0b10000000;
+    }
+}
+
+bitflags! {
+    /// $2002 PPUSTATUS. The lower 5 bits are not driven and return the last value on the PPU data bus
+    pub struct PpuStatus: u8 {
+        const SPRITE_OVERFLOW      = 0b00100000;
+        const SPRITE_0_HIT         = 0b01000000;
+        const VBLANK               = 0b10000000;
+    }
+}
+
+bitflags! {
+    /// Byte 2 of a sprite in OAM
+    pub struct SpriteAttributes: u8 {
+        const PALETTE              = 0b00000011;
+        const BEHIND_BACKGROUND    = 0b00100000;
+        const FLIP_HORIZONTAL      = 0b01000000;
+        const FLIP_VERTICAL        = 0b10000000;
+    }
+}
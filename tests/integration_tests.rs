@@ -1,4 +1,180 @@
+use std::fs;
+use std::path::PathBuf;
 
+use k_nes::bus::Bus;
+use k_nes::bus::nes_bus::NesBus;
+use k_nes::cartridge::{mapper, Cartridge};
+use k_nes::cpu::Cpu;
+use k_nes::cpu::opcode;
+use k_nes::cpu::types::CpuVariant;
+use k_nes::memory::Memory;
+
+/// In automation mode, nestest runs every test without a PPU, starting at $C000
+const NESTEST_START: u16 = 0xC000;
+/// Amount of matching lines shown before the first divergence
+const DIVERGENCE_CONTEXT: usize = 5;
+
+/// The functional test image fills the whole 64 KiB and starts at $0400
+const FUNCTIONAL_TEST_START: u16 = 0x0400;
+/// Address of the `JMP *` reached when every test passed, for the default build of the suite
+const FUNCTIONAL_TEST_SUCCESS: u16 = 0x3469;
+/// Number of the test being run, left in memory when the suite traps on a failure
+const FUNCTIONAL_TEST_CASE: u16 = 0x0200;
+
+/// The decimal test image is loaded and started at $0200
+const DECIMAL_TEST_START: u16 = 0x0200;
+/// Holds 0 when every decimal operation matched, 1 otherwise
+const DECIMAL_TEST_ERROR: u16 = 0x000B;
+
+fn resource(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("resources/test").join(name)
+}
+
+/// Full Nintendulator trace line: CPU state, then PPU scanline and dot, then CPU cycles
+fn trace_line(cpu: &Cpu<NesBus>) -> String {
+    let ppu = cpu.bus().ppu();
+    format!("{} PPU:{:>3},{:>3} CYC:{}", cpu.trace(), ppu.scanline(), ppu.dot(), cpu.cycles())
+}
+
+fn divergence_report(expected: &[&str], actual: &[String], line: &str) -> String {
+    let index = actual.len();
+    let mut report = format!("Trace diverges from the golden log at line {}\n", index + 1);
+    for matching_line in &actual[index.saturating_sub(DIVERGENCE_CONTEXT)..] {
+        report += &format!("          {}\n", matching_line);
+    }
+    report += &format!("expected: {}\n", expected[index]);
+    report += &format!("actual:   {}\n", line);
+    report
+}
+
+/// Runs nestest in automation mode and compares each trace line with the golden log.
+/// Panics on the first divergence, showing the lines that matched before it
+fn run_against_golden_log(rom: &[u8], log: &str) -> Cpu<NesBus> {
+    let mut bus = NesBus::new();
+    bus.insert_cartridge(mapper::new_mapper(Cartridge::from_bytes(rom).unwrap()).unwrap());
+    let mut cpu = Cpu::new_with_bus(bus);
+    cpu.reset();
+    cpu.set_program_counter(NESTEST_START);
+
+    let expected: Vec<&str> = log.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect();
+    let mut actual: Vec<String> = Vec::with_capacity(expected.len());
+
+    for expected_line in &expected {
+        let line = trace_line(&cpu);
+        if line != *expected_line {
+            panic!("{}", divergence_report(&expected, &actual, &line));
+        }
+        actual.push(line);
+
+        if let Err(error) = cpu.step() {
+            panic!("CPU error at line {}: {}\n{}", actual.len(), error, actual[actual.len() - 1]);
+        }
+    }
+    cpu
+}
+
+/// nestest.nes and its golden log are not bundled. Place them in `resources/test`
+/// and run `cargo test -- --ignored` to run the test
+///
+/// see: [nestest](https://www.qmtpro.com/~nes/misc/nestest.txt)
 #[test]
-fn test() {
-}
\ No newline at end of file
+#[ignore = "needs resources/test/nestest.*"]
+fn nestest_matches_golden_log() {
+    // Given
+    let rom = fs::read(resource("nestest.nes")).expect("resources/test/nestest.nes not found");
+    let log = fs::read_to_string(resource("nestest.log")).expect("resources/test/nestest.log not found");
+
+    // When
+    let cpu = run_against_golden_log(&rom, &log);
+
+    // Then
+    // nestest stores the code of the first failed test in $02 (official) and $03 (unofficial opcodes)
+    assert_eq!(0x00, cpu.bus().peek(0x0002), "Official opcodes test failed");
+    assert_eq!(0x00, cpu.bus().peek(0x0003), "Unofficial opcodes test failed");
+}
+
+/// NROM image with a single 16 KiB PRG bank, mirrored at $C000, and CHR RAM
+fn nrom_image(program: &[u8]) -> Vec<u8> {
+    let mut image = vec![0x4E, 0x45, 0x53, 0x1A, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let mut prg = vec![0xEA; 0x4000];
+    prg[..program.len()].copy_from_slice(program);
+    prg[0x3FFC..0x3FFE].copy_from_slice(&NESTEST_START.to_le_bytes());
+    image.extend(prg);
+    image
+}
+
+#[test]
+fn golden_log_harness_runs_compares_from_c000() {
+    // Given
+    // Compares where bit 7 of the register differs from bit 7 of the difference,
+    // as in the first compare tests of nestest
+    let rom = nrom_image(&[
+        0xA9, 0x40,         // LDA #$40
+        0xC9, 0x41,         // CMP #$41 -> N, no C
+        0xA2, 0x80,         // LDX #$80
+        0xE0, 0x01,         // CPX #$01 -> C, no N
+        0xC0, 0x00,         // CPY #$00 -> Z, C
+    ]);
+    let log = "\
+C000  A9 40     LDA #$40                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+C002  C9 41     CMP #$41                        A:40 X:00 Y:00 P:24 SP:FD PPU:  0, 27 CYC:9
+C004  A2 80     LDX #$80                        A:40 X:00 Y:00 P:A4 SP:FD PPU:  0, 33 CYC:11
+C006  E0 01     CPX #$01                        A:40 X:80 Y:00 P:A4 SP:FD PPU:  0, 39 CYC:13
+C008  C0 00     CPY #$00                        A:40 X:80 Y:00 P:25 SP:FD PPU:  0, 45 CYC:15
+C00A  EA        NOP                             A:40 X:80 Y:00 P:27 SP:FD PPU:  0, 51 CYC:17
+";
+
+    // When
+    let cpu = run_against_golden_log(&rom, log);
+
+    // Then
+    assert_eq!(0xC00B, cpu.program_counter());
+}
+
+/// Loads the image into flat memory, ready to run on an NMOS 6502
+fn load_klaus_dormann_test(image: &[u8], load_address: u16, start_address: u16) -> Cpu {
+    let mut memory = Memory::new();
+    memory.write_array(image, load_address);
+    let mut cpu = Cpu::new_with_bus(memory);
+    cpu.set_variant(CpuVariant::Nmos6502);
+    cpu.set_program_counter(start_address);
+    cpu
+}
+
+/// The binaries of Klaus Dormann's test suite are not bundled.
+/// Place the default build of `6502_functional_test.bin` in `resources/test`
+/// and run `cargo test -- --ignored` to run the test
+///
+/// see: [6502 functional tests](https://github.com/Klaus2m5/6502_65C02_functional_tests)
+#[test]
+#[ignore = "needs resources/test/6502_functional_test.bin"]
+fn klaus_dormann_functional_test() {
+    // Given
+    let image = fs::read(resource("6502_functional_test.bin")).expect("resources/test/6502_functional_test.bin not found");
+    let mut cpu = load_klaus_dormann_test(&image, 0x0000, FUNCTIONAL_TEST_START);
+
+    // When
+    let trap_address = cpu.run_until_trap().unwrap_or_else(|error| panic!("CPU error: {}", error));
+
+    // Then
+    assert_eq!(FUNCTIONAL_TEST_SUCCESS, trap_address,
+        "Trapped at {:#06X} in test case {:#04X}", trap_address, cpu.bus().peek(FUNCTIONAL_TEST_CASE));
+}
+
+/// Place `6502_decimal_test.bin`, assembled with `chk_c` and `chk_a` enabled and `end_of_test` as `brk`,
+/// in `resources/test` and run `cargo test -- --ignored` to run the test.
+/// The test stops at the BRK in `DONE`, before it jumps through the empty IRQ vector
+#[test]
+#[ignore = "needs resources/test/6502_decimal_test.bin"]
+fn klaus_dormann_decimal_test() {
+    // Given
+    let image = fs::read(resource("6502_decimal_test.bin")).expect("resources/test/6502_decimal_test.bin not found");
+    let mut cpu = load_klaus_dormann_test(&image, DECIMAL_TEST_START, DECIMAL_TEST_START);
+
+    // When
+    cpu.run_until(|cpu| cpu.bus().peek(cpu.program_counter()) == opcode::BRK)
+        .unwrap_or_else(|error| panic!("CPU error: {}", error));
+
+    // Then
+    assert_eq!(0x00, cpu.bus().peek(DECIMAL_TEST_ERROR), "Decimal test failed, stopped at {:#06X}", cpu.program_counter());
+}